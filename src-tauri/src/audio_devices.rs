@@ -0,0 +1,90 @@
+// TTP - Talk To Paste
+// Microphone enumeration and selection
+//
+// The preferred input device is stored by name in settings (names are the
+// only identifier cpal keeps stable across reconnects). When that device is
+// not plugged in, recording falls back to the system default.
+
+use crate::logging::log_warn;
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::Serialize;
+
+/// One supported capture configuration range of a device
+#[derive(Debug, Clone, Serialize)]
+pub struct InputConfigInfo {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    /// cpal sample format ("f32", "i16", ...)
+    pub sample_format: String,
+}
+
+/// An input device as shown in the microphone picker
+#[derive(Debug, Clone, Serialize)]
+pub struct InputDeviceInfo {
+    pub name: String,
+    /// Whether this is the current system default input
+    pub is_default: bool,
+    pub configs: Vec<InputConfigInfo>,
+}
+
+/// Pick the input device to record from
+///
+/// Uses the device named `preferred` when it is connected, otherwise the
+/// system default input.
+pub fn select_input_device(preferred: Option<&str>) -> Result<cpal::Device, String> {
+    let host = cpal::default_host();
+
+    if let Some(wanted) = preferred.filter(|name| !name.is_empty()) {
+        let found = host
+            .input_devices()
+            .map_err(|e| format!("Failed to list input devices: {}", e))?
+            .find(|device| device.name().map(|name| name == wanted).unwrap_or(false));
+        match found {
+            Some(device) => return Ok(device),
+            None => log_warn(&format!("Microphone '{}' not connected, using system default", wanted)),
+        }
+    }
+
+    host.default_input_device()
+        .ok_or_else(|| "No input device available".to_string())
+}
+
+/// Tauri command: list connected input devices with their supported configs
+#[tauri::command]
+pub fn list_input_devices() -> Result<Vec<InputDeviceInfo>, String> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+
+    let devices = host
+        .input_devices()
+        .map_err(|e| format!("Failed to list input devices: {}", e))?;
+
+    let mut result = Vec::new();
+    for device in devices {
+        // Devices that vanish or refuse queries mid-enumeration are skipped
+        let Ok(name) = device.name() else {
+            continue;
+        };
+        let configs = device
+            .supported_input_configs()
+            .map(|ranges| {
+                ranges
+                    .map(|range| InputConfigInfo {
+                        channels: range.channels(),
+                        min_sample_rate: range.min_sample_rate().0,
+                        max_sample_rate: range.max_sample_rate().0,
+                        sample_format: range.sample_format().to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        result.push(InputDeviceInfo {
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+            configs,
+        });
+    }
+    Ok(result)
+}
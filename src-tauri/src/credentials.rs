@@ -1,6 +1,7 @@
 // TTP - Talk To Paste
-// API key storage for Groq
+// API key storage for Groq and the other transcription / LLM providers
 
+use crate::settings::{LlmProviderKind, TranscriptionProviderKind};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -9,6 +10,75 @@ use std::time::Duration;
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ApiKeys {
     pub groq: Option<String>,
+    #[serde(default)]
+    pub openai: Option<String>,
+    #[serde(default)]
+    pub deepgram: Option<String>,
+    /// Key for a self-hosted OpenAI-compatible endpoint (often not needed)
+    #[serde(default)]
+    pub custom: Option<String>,
+    #[serde(default)]
+    pub anthropic: Option<String>,
+}
+
+/// Which stored key to read or write
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyProvider {
+    Groq,
+    #[serde(rename = "openai")]
+    OpenAi,
+    /// Self-hosted OpenAI-compatible endpoint
+    Custom,
+    Deepgram,
+    Anthropic,
+}
+
+impl ApiKeyProvider {
+    /// Key used by an LLM provider (Ollama runs locally without one)
+    pub fn for_llm(kind: LlmProviderKind) -> Option<Self> {
+        match kind {
+            LlmProviderKind::Groq => Some(Self::Groq),
+            LlmProviderKind::OpenAi => Some(Self::OpenAi),
+            LlmProviderKind::OpenAiCompatible => Some(Self::Custom),
+            LlmProviderKind::Anthropic => Some(Self::Anthropic),
+            LlmProviderKind::Ollama => None,
+        }
+    }
+
+    /// Environment variable that overrides the stored key
+    fn env_var(&self) -> &'static str {
+        match self {
+            Self::Groq => "GROQ_API_KEY",
+            Self::OpenAi => "OPENAI_API_KEY",
+            Self::Custom => "TTP_CUSTOM_API_KEY",
+            Self::Deepgram => "DEEPGRAM_API_KEY",
+            Self::Anthropic => "ANTHROPIC_API_KEY",
+        }
+    }
+}
+
+impl From<TranscriptionProviderKind> for ApiKeyProvider {
+    fn from(kind: TranscriptionProviderKind) -> Self {
+        match kind {
+            TranscriptionProviderKind::Groq => Self::Groq,
+            TranscriptionProviderKind::OpenAi => Self::OpenAi,
+            TranscriptionProviderKind::OpenAiCompatible => Self::Custom,
+            TranscriptionProviderKind::Deepgram => Self::Deepgram,
+        }
+    }
+}
+
+impl ApiKeys {
+    fn slot_mut(&mut self, provider: ApiKeyProvider) -> &mut Option<String> {
+        match provider {
+            ApiKeyProvider::Groq => &mut self.groq,
+            ApiKeyProvider::OpenAi => &mut self.openai,
+            ApiKeyProvider::Custom => &mut self.custom,
+            ApiKeyProvider::Deepgram => &mut self.deepgram,
+            ApiKeyProvider::Anthropic => &mut self.anthropic,
+        }
+    }
 }
 
 /// Get the API keys file path (~/.config/ttp/api-keys.json on macOS)
@@ -58,6 +128,38 @@ pub fn get_groq_api_key_internal(_app: &tauri::AppHandle) -> Result<Option<Strin
     Ok(load_keys().groq)
 }
 
+/// Get the API key for a provider (env var first, then stored key)
+pub fn get_provider_api_key_internal(provider: ApiKeyProvider) -> Option<String> {
+    if let Ok(key) = std::env::var(provider.env_var()) {
+        if !key.is_empty() {
+            return Some(key);
+        }
+    }
+    load_keys().slot_mut(provider).take().filter(|k| !k.is_empty())
+}
+
+/// Tauri command to store the API key for a provider
+#[tauri::command]
+pub async fn set_provider_api_key(provider: ApiKeyProvider, key: String) -> Result<(), String> {
+    let mut keys = load_keys();
+    *keys.slot_mut(provider) = Some(key);
+    save_keys(&keys)
+}
+
+/// Tauri command to check whether a provider has an API key
+#[tauri::command]
+pub async fn has_provider_api_key(provider: ApiKeyProvider) -> Result<bool, String> {
+    Ok(get_provider_api_key_internal(provider).is_some())
+}
+
+/// Tauri command to remove the stored API key for a provider
+#[tauri::command]
+pub async fn delete_provider_api_key(provider: ApiKeyProvider) -> Result<(), String> {
+    let mut keys = load_keys();
+    *keys.slot_mut(provider) = None;
+    save_keys(&keys)
+}
+
 #[tauri::command]
 pub async fn get_groq_api_key(_app: tauri::AppHandle) -> Result<Option<String>, String> {
     if let Ok(key) = std::env::var("GROQ_API_KEY") {
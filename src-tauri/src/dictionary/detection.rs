@@ -6,8 +6,10 @@
 
 use super::classify::classify_correction;
 use super::store::add_entry;
-use crate::credentials::get_groq_api_key_internal;
+use crate::language::is_stop_word;
+use crate::llm::{resolve_task, LlmTask};
 use crate::paste::read_focused_text;
+use crate::settings::get_settings;
 use tauri::{AppHandle, Emitter};
 use tokio::time::{sleep, Duration};
 
@@ -26,7 +28,8 @@ const DETECTION_WINDOW_SECS: u64 = 15;
 /// # Arguments
 /// * `_app` - Tauri app handle (kept for future use)
 /// * `pasted_text` - The text that was just pasted
-pub fn start_correction_window(app: &AppHandle, pasted_text: String) {
+/// * `language` - Spoken language for stop words (`None` = auto)
+pub fn start_correction_window(app: &AppHandle, pasted_text: String, language: Option<String>) {
     let app_handle = app.clone();
     // Spawn background task for detection
     tokio::spawn(async move {
@@ -47,18 +50,21 @@ pub fn start_correction_window(app: &AppHandle, pasted_text: String) {
             };
 
             // Compare with pasted text
-            if let Some(corrections) = detect_corrections(&pasted_text, &current_text) {
-                // Get API key for LLM classification gate
-                let api_key = match get_groq_api_key_internal(&app_handle) {
-                    Ok(Some(key)) => Some(key),
-                    _ => None,
+            if let Some(corrections) = detect_corrections(&pasted_text, &current_text, language.as_deref()) {
+                // Resolve the classify LLM (provider, model, key) for the gate
+                let classify_llm = match resolve_task(&get_settings().classify_llm, LlmTask::Classify) {
+                    Ok(config) => Some(config),
+                    Err(e) => {
+                        eprintln!("[Detection] Classify LLM unavailable: {}", e);
+                        None
+                    }
                 };
 
                 let mut added = false;
                 for (original, correction) in &corrections {
                     // LLM validation gate: classify before adding
-                    let should_learn = if let Some(ref key) = api_key {
-                        match classify_correction(key, original, correction, &current_text).await {
+                    let should_learn = if let Some(ref config) = classify_llm {
+                        match classify_correction(config, original, correction, &current_text).await {
                             Ok(true) => {
                                 eprintln!("[Detection] LLM classified '{}' → '{}' as LEARN", original, correction);
                                 true
@@ -74,8 +80,8 @@ pub fn start_correction_window(app: &AppHandle, pasted_text: String) {
                             }
                         }
                     } else {
-                        // No API key available — fail closed, skip entry
-                        eprintln!("[Detection] No API key available for LLM classification, skipping '{}' → '{}'", original, correction);
+                        // No classify LLM available — fail closed, skip entry
+                        eprintln!("[Detection] No LLM available for classification, skipping '{}' → '{}'", original, correction);
                         false
                     };
 
@@ -102,26 +108,14 @@ pub fn start_correction_window(app: &AppHandle, pasted_text: String) {
     });
 }
 
-/// Common stop words that should never be treated as corrections.
-/// These are short grammar/context words in French and English that users
-/// frequently edit for grammatical reasons, not because of transcription errors.
-const STOP_WORDS: &[&str] = &[
-    "le", "la", "les", "de", "du", "des", "un", "une", "et", "ou", "en", "au", "aux", "ce", "se",
-    "ne", "que", "qui", "the", "a", "an", "is", "are", "was", "were", "be", "to", "of", "in",
-    "it", "for", "on", "at",
-];
-
-/// Check if a word is a common stop word
-fn is_stop_word(word: &str) -> bool {
-    STOP_WORDS.contains(&word.to_lowercase().as_str())
-}
-
 /// Check if a word change looks like a real spelling correction vs noise
 ///
 /// A real correction is: "Whysper" → "Whisper", "parris" → "Paris"
 /// NOT a correction: "donne" → "donne.Magnifique", "marche" → "ma",
 /// "bonjour" → "Bonjour" (case-only), "du" → "de" (stop words)
-fn is_valid_correction(orig: &str, curr: &str) -> bool {
+///
+/// Stop words come from the profile of `language` (`None` = all languages).
+fn is_valid_correction(orig: &str, curr: &str, language: Option<&str>) -> bool {
     let orig_lower = orig.to_lowercase();
     let curr_lower = curr.to_lowercase();
 
@@ -139,7 +133,7 @@ fn is_valid_correction(orig: &str, curr: &str) -> bool {
 
     // Reject if either word is a common stop word
     // Stop words are edited for grammar, not transcription correction
-    if is_stop_word(orig) || is_stop_word(curr) {
+    if is_stop_word(orig, language) || is_stop_word(curr, language) {
         return false;
     }
 
@@ -181,7 +175,7 @@ fn is_valid_correction(orig: &str, curr: &str) -> bool {
 /// substring concatenation). Ignores text added after the paste.
 ///
 /// Returns a list of (original, correction) pairs, or None if no corrections found.
-fn detect_corrections(pasted: &str, current: &str) -> Option<Vec<(String, String)>> {
+fn detect_corrections(pasted: &str, current: &str, language: Option<&str>) -> Option<Vec<(String, String)>> {
     // Quick check: if they're identical, no correction
     if pasted == current {
         return None;
@@ -216,7 +210,7 @@ fn detect_corrections(pasted: &str, current: &str) -> Option<Vec<(String, String
             let curr_clean = curr.trim_matches(|c: char| c.is_ascii_punctuation());
 
             if orig_clean != curr_clean && !orig_clean.is_empty() && !curr_clean.is_empty() {
-                if is_valid_correction(orig_clean, curr_clean) {
+                if is_valid_correction(orig_clean, curr_clean, language) {
                     corrections.push((orig_clean.to_string(), curr_clean.to_string()));
                 }
             }
@@ -231,7 +225,7 @@ fn detect_corrections(pasted: &str, current: &str) -> Option<Vec<(String, String
             let curr_clean = curr.trim_matches(|c: char| c.is_ascii_punctuation());
 
             if orig_clean != curr_clean && !orig_clean.is_empty() && !curr_clean.is_empty() {
-                if is_valid_correction(orig_clean, curr_clean) {
+                if is_valid_correction(orig_clean, curr_clean, language) {
                     corrections.push((orig_clean.to_string(), curr_clean.to_string()));
                 }
             }
@@ -362,6 +356,7 @@ mod tests {
         let corrections = detect_corrections(
             "J'utilise Whysper pour la transcription.",
             "J'utilise Whisper pour la transcription.",
+            None,
         );
         assert!(corrections.is_some());
         let c = corrections.unwrap();
@@ -371,7 +366,7 @@ mod tests {
 
     #[test]
     fn test_detect_no_change() {
-        let corrections = detect_corrections("Hello world", "Hello world");
+        let corrections = detect_corrections("Hello world", "Hello world", None);
         assert!(corrections.is_none());
     }
 
@@ -381,6 +376,7 @@ mod tests {
         let corrections = detect_corrections(
             "I visited Barlin and saw the Colloseum",
             "I visited Berlin and saw the Colosseum",
+            None,
         );
         assert!(corrections.is_some());
         let c = corrections.unwrap();
@@ -393,6 +389,7 @@ mod tests {
         let corrections = detect_corrections(
             "I visited Barlin and saw Colloseum near Buckingam Palace",
             "I visited Berlin and saw Colosseum near Buckingham Palace",
+            None,
         );
         // 3 valid corrections: Barlin→Berlin, Colloseum→Colosseum, Buckingam→Buckingham
         // This exceeds the limit of 2, so it should return None
@@ -410,59 +407,59 @@ mod tests {
     #[test]
     fn test_reject_concatenation() {
         // User typed after a word without space: "donne.Magnifique"
-        assert!(!is_valid_correction("donne", "donne.Magnifique"));
+        assert!(!is_valid_correction("donne", "donne.Magnifique", None));
     }
 
     #[test]
     fn test_reject_very_different_words() {
         // "marche" → "ma" is not a correction, it's a different word
-        assert!(!is_valid_correction("marche", "ma"));
+        assert!(!is_valid_correction("marche", "ma", None));
     }
 
     #[test]
     fn test_reject_short_words() {
         // Single character words should not be corrections
-        assert!(!is_valid_correction("a", "I"));
+        assert!(!is_valid_correction("a", "I", None));
         // Two-character words should also be rejected now (min length = 3)
-        assert!(!is_valid_correction("du", "de"));
-        assert!(!is_valid_correction("je", "le"));
+        assert!(!is_valid_correction("du", "de", None));
+        assert!(!is_valid_correction("je", "le", None));
     }
 
     #[test]
     fn test_reject_case_only_differences() {
         // Case-only changes should NOT be dictionary entries
         // The LLM already handles capitalization
-        assert!(!is_valid_correction("bonjour", "Bonjour"));
-        assert!(!is_valid_correction("Kellou", "KELLOU"));
-        assert!(!is_valid_correction("AmirKs", "AmirKS"));
-        assert!(!is_valid_correction("hello", "HELLO"));
+        assert!(!is_valid_correction("bonjour", "Bonjour", None));
+        assert!(!is_valid_correction("Kellou", "KELLOU", None));
+        assert!(!is_valid_correction("AmirKs", "AmirKS", None));
+        assert!(!is_valid_correction("hello", "HELLO", None));
     }
 
     #[test]
     fn test_reject_stop_words() {
         // Stop words should never be treated as corrections
-        assert!(!is_valid_correction("les", "des"));
-        assert!(!is_valid_correction("the", "teh")); // "the" is a stop word
-        assert!(!is_valid_correction("fait", "for")); // "for" is a stop word
-        assert!(!is_valid_correction("are", "ore"));
+        assert!(!is_valid_correction("les", "des", None));
+        assert!(!is_valid_correction("the", "teh", None)); // "the" is a stop word
+        assert!(!is_valid_correction("fait", "for", None)); // "for" is a stop word
+        assert!(!is_valid_correction("are", "ore", None));
     }
 
     #[test]
     fn test_reject_short_low_similarity() {
         // Both words < 5 chars with similarity < 0.8 should be rejected
         // These are grammar variations, not transcription errors
-        assert!(!is_valid_correction("fait", "fais"));
-        assert!(!is_valid_correction("mais", "mois"));
-        assert!(!is_valid_correction("Grok", "Groq")); // both 4 chars, sim 0.75 < 0.8
+        assert!(!is_valid_correction("fait", "fais", None));
+        assert!(!is_valid_correction("mais", "mois", None));
+        assert!(!is_valid_correction("Grok", "Groq", None)); // both 4 chars, sim 0.75 < 0.8
     }
 
     #[test]
     fn test_accept_real_corrections() {
-        assert!(is_valid_correction("parris", "Paris")); // transcription error (6 chars)
-        assert!(is_valid_correction("Whysper", "Whisper")); // brand name transcription error
-        assert!(is_valid_correction("resultats", "résultats")); // accent correction
-        assert!(is_valid_correction("transcription", "transcripcion")); // long word, clear error
-        assert!(is_valid_correction("Barlin", "Berlin")); // city name transcription error
+        assert!(is_valid_correction("parris", "Paris", None)); // transcription error (6 chars)
+        assert!(is_valid_correction("Whysper", "Whisper", None)); // brand name transcription error
+        assert!(is_valid_correction("resultats", "résultats", None)); // accent correction
+        assert!(is_valid_correction("transcription", "transcripcion", None)); // long word, clear error
+        assert!(is_valid_correction("Barlin", "Berlin", None)); // city name transcription error
     }
 
     #[test]
@@ -471,6 +468,7 @@ mod tests {
         let corrections = detect_corrections(
             "il donne des resultats bien",
             "il donne des résultats bien. Magnifique c'est cool",
+            None,
         );
         // Should detect "resultats" → "résultats" but ignore the added text
         assert!(corrections.is_some());
@@ -8,4 +8,7 @@ pub mod classify;
 pub mod detection;
 pub mod store;
 
-pub use store::{add_dictionary_entry, add_entry, apply_dictionary, clear_dictionary, delete_dictionary_entry, get_dictionary, DictionaryEntry};
+pub use store::{
+    active_entries, add_dictionary_entry, add_entry, apply_dictionary, clear_dictionary,
+    delete_dictionary_entry, get_dictionary, set_dictionary_entry_enabled, DictionaryEntry, MatchMode,
+};
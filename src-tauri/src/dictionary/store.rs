@@ -1,10 +1,37 @@
 // TTP - Talk To Paste
 // Dictionary persistence layer - stores learned corrections in JSON file
-
+//
+// Entries written before match modes existed only have `original`,
+// `correction` and `created_at`; they load as enabled whole-word entries for
+// every language, and the file is rewritten in the current format the first
+// time it is read.
+
+use caseless::default_case_fold_str;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{Read, Write};
 use std::path::PathBuf;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How an entry's `original` is found in the text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// Whole words, ignoring case ("sequel" but not "sequels")
+    #[default]
+    Word,
+    /// The exact text anywhere, ignoring case, even inside words
+    Phrase,
+    /// Whole words with the exact case ("SQL" but not "sql")
+    CaseSensitive,
+    /// `original` is a regular expression; `correction` may use `$1`, `${name}`
+    Regex,
+}
+
+fn default_true() -> bool {
+    true
+}
 
 /// A single dictionary entry mapping original (misheard) text to correction
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +42,30 @@ pub struct DictionaryEntry {
     pub correction: String,
     /// Unix timestamp when entry was created
     pub created_at: i64,
+    /// How `original` is matched
+    #[serde(default)]
+    pub match_mode: MatchMode,
+    /// Disabled entries are kept but never applied
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Spoken language the entry applies to (ISO 639-1, `None` = all)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Free-form note for the user ("product code", "not the database")
+    #[serde(default)]
+    pub note: String,
+}
+
+impl DictionaryEntry {
+    /// Whether the entry applies when `language` is spoken
+    ///
+    /// With auto-detect (`None`) the language is unknown, so every entry applies.
+    pub fn applies_to(&self, language: Option<&str>) -> bool {
+        match (self.language.as_deref(), language) {
+            (Some(scope), Some(spoken)) => scope.eq_ignore_ascii_case(spoken),
+            _ => true,
+        }
+    }
 }
 
 /// Get the path to the dictionary JSON file
@@ -68,59 +119,134 @@ pub fn get_dictionary() -> Vec<DictionaryEntry> {
         return Vec::new();
     }
 
-    match serde_json::from_str(&contents) {
+    let entries: Vec<DictionaryEntry> = match serde_json::from_str(&contents) {
         Ok(entries) => entries,
         Err(e) => {
             eprintln!("[Dictionary] Failed to parse JSON: {}", e);
-            Vec::new()
+            return Vec::new();
         }
+    };
+
+    if needs_migration(&contents) {
+        match write_entries(&path, &entries) {
+            Ok(()) => eprintln!("[Dictionary] Migrated {} entries to the current format", entries.len()),
+            Err(e) => eprintln!("[Dictionary] Failed to migrate dictionary: {}", e),
+        }
+    }
+
+    entries
+}
+
+/// Whether the file has entries written before match modes existed
+fn needs_migration(contents: &str) -> bool {
+    serde_json::from_str::<Vec<serde_json::Value>>(contents)
+        .map(|entries| entries.iter().any(|e| e.get("match_mode").is_none()))
+        .unwrap_or(false)
+}
+
+/// Enabled entries that apply to `language` (`None` = auto-detect)
+pub fn active_entries(language: Option<&str>) -> Vec<DictionaryEntry> {
+    get_dictionary()
+        .into_iter()
+        .filter(|e| e.enabled && e.applies_to(language))
+        .collect()
+}
+
+fn write_entries(path: &PathBuf, entries: &[DictionaryEntry]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize dictionary: {}", e))?;
+
+    let mut file = fs::File::create(path)
+        .map_err(|e| format!("Failed to create dictionary file: {}", e))?;
+
+    file.write_all(json.as_bytes())
+        .map_err(|e| format!("Failed to write dictionary file: {}", e))
+}
+
+/// Tauri command to add or replace a dictionary entry from the frontend
+///
+/// Options left out keep their defaults: whole-word, enabled, every language.
+#[tauri::command]
+pub fn add_dictionary_entry(
+    original: String,
+    correction: String,
+    match_mode: Option<MatchMode>,
+    language: Option<String>,
+    note: Option<String>,
+    enabled: Option<bool>,
+) -> Result<(), String> {
+    let match_mode = match_mode.unwrap_or_default();
+    if match_mode == MatchMode::Regex {
+        Regex::new(&original).map_err(|e| format!("Invalid regular expression: {}", e))?;
     }
+
+    save_entry(DictionaryEntry {
+        original,
+        correction,
+        created_at: chrono::Utc::now().timestamp(),
+        match_mode,
+        enabled: enabled.unwrap_or(true),
+        language: language.filter(|l| !l.is_empty()),
+        note: note.unwrap_or_default(),
+    })
 }
 
-/// Tauri command to add a dictionary entry from the frontend
+/// Tauri command to turn an entry on or off without deleting it
 #[tauri::command]
-pub fn add_dictionary_entry(original: String, correction: String) -> Result<(), String> {
-    add_entry(&original, &correction)
+pub fn set_dictionary_entry_enabled(original: String, enabled: bool) -> Result<(), String> {
+    let path = get_dictionary_path()?;
+    let mut entries = get_dictionary();
+    let entry = entries
+        .iter_mut()
+        .find(|e| e.original.to_lowercase() == original.to_lowercase())
+        .ok_or_else(|| format!("Entry not found: {}", original))?;
+    entry.enabled = enabled;
+    write_entries(&path, &entries)
 }
 
-/// Add a new entry to the dictionary
-/// If an entry with the same original text exists, it will be updated
+/// Add a learned correction to the dictionary
+/// If an entry with the same original text exists, its correction is updated
+/// and its match mode, scope and note are kept
 pub fn add_entry(original: &str, correction: &str) -> Result<(), String> {
+    let existing = get_dictionary()
+        .into_iter()
+        .find(|e| e.original.to_lowercase() == original.to_lowercase());
+
+    save_entry(DictionaryEntry {
+        original: original.to_string(),
+        correction: correction.to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+        ..existing.unwrap_or(DictionaryEntry {
+            original: String::new(),
+            correction: String::new(),
+            created_at: 0,
+            match_mode: MatchMode::Word,
+            enabled: true,
+            language: None,
+            note: String::new(),
+        })
+    })
+}
+
+/// Insert an entry, replacing one with the same original text (case-insensitive)
+fn save_entry(new_entry: DictionaryEntry) -> Result<(), String> {
     let path = get_dictionary_path()?;
 
     // Load existing entries
     let mut entries = get_dictionary();
 
-    // Check if entry already exists (case-insensitive update)
-    let original_lower = original.to_lowercase();
+    let original_lower = new_entry.original.to_lowercase();
     let existing_idx = entries
         .iter()
         .position(|e| e.original.to_lowercase() == original_lower);
 
-    let timestamp = chrono::Utc::now().timestamp();
-    let new_entry = DictionaryEntry {
-        original: original.to_string(),
-        correction: correction.to_string(),
-        created_at: timestamp,
-    };
-
     if let Some(idx) = existing_idx {
         entries[idx] = new_entry;
     } else {
         entries.push(new_entry);
     }
 
-    // Write back to file
-    let json = serde_json::to_string_pretty(&entries)
-        .map_err(|e| format!("Failed to serialize dictionary: {}", e))?;
-
-    let mut file = fs::File::create(&path)
-        .map_err(|e| format!("Failed to create dictionary file: {}", e))?;
-
-    file.write_all(json.as_bytes())
-        .map_err(|e| format!("Failed to write dictionary file: {}", e))?;
-
-    Ok(())
+    write_entries(&path, &entries)
 }
 
 /// Delete an entry from the dictionary by its original text
@@ -152,14 +278,7 @@ fn delete_entry_internal(original: &str) -> Result<(), String> {
                 .map_err(|e| format!("Failed to delete dictionary file: {}", e))?;
         }
     } else {
-        let json = serde_json::to_string_pretty(&entries)
-            .map_err(|e| format!("Failed to serialize dictionary: {}", e))?;
-
-        let mut file = fs::File::create(&path)
-            .map_err(|e| format!("Failed to create dictionary file: {}", e))?;
-
-        file.write_all(json.as_bytes())
-            .map_err(|e| format!("Failed to write dictionary file: {}", e))?;
+        write_entries(&path, &entries)?;
     }
 
     Ok(())
@@ -169,50 +288,125 @@ fn delete_entry_internal(original: &str) -> Result<(), String> {
 ///
 /// This is a post-processing step that guarantees dictionary entries
 /// are applied regardless of whether the LLM honored them.
-/// Uses case-insensitive word-boundary matching.
-pub fn apply_dictionary(text: &str) -> String {
-    let entries = get_dictionary();
-    if entries.is_empty() {
-        return text.to_string();
-    }
+/// Only enabled entries scoped to `language` (`None` = auto) are used.
+pub fn apply_dictionary(text: &str, language: Option<&str>) -> String {
+    apply_entries(text, &active_entries(language))
+}
 
+/// Apply entries in order, each according to its match mode
+pub fn apply_entries(text: &str, entries: &[DictionaryEntry]) -> String {
     let mut result = text.to_string();
-    for entry in &entries {
-        // Case-insensitive replacement preserving word boundaries
-        // We search for the original word and replace with correction
-        let original_lower = entry.original.to_lowercase();
-        let mut new_result = String::new();
-        let mut remaining = result.as_str();
-
-        while !remaining.is_empty() {
-            // Find next occurrence (case-insensitive)
-            if let Some(pos) = remaining.to_lowercase().find(&original_lower) {
-                // Check word boundaries
-                let before_ok = pos == 0
-                    || !remaining.as_bytes()[pos - 1].is_ascii_alphanumeric();
-                let after_pos = pos + entry.original.len();
-                let after_ok = after_pos >= remaining.len()
-                    || !remaining.as_bytes()[after_pos].is_ascii_alphanumeric();
-
-                if before_ok && after_ok {
-                    new_result.push_str(&remaining[..pos]);
-                    new_result.push_str(&entry.correction);
-                    remaining = &remaining[after_pos..];
-                } else {
-                    // Not a word boundary match, skip past this occurrence
-                    new_result.push_str(&remaining[..pos + entry.original.len()]);
-                    remaining = &remaining[pos + entry.original.len()..];
+    for entry in entries {
+        if entry.original.is_empty() {
+            continue;
+        }
+        result = match entry.match_mode {
+            MatchMode::Word => replace_literal(&result, &entry.original, &entry.correction, true, true),
+            MatchMode::Phrase => replace_literal(&result, &entry.original, &entry.correction, true, false),
+            MatchMode::CaseSensitive => replace_literal(&result, &entry.original, &entry.correction, false, true),
+            MatchMode::Regex => match Regex::new(&entry.original) {
+                Ok(re) => re.replace_all(&result, entry.correction.as_str()).into_owned(),
+                Err(e) => {
+                    eprintln!("[Dictionary] Skipping invalid regex '{}': {}", entry.original, e);
+                    result
                 }
-            } else {
-                new_result.push_str(remaining);
-                break;
+            },
+        };
+    }
+
+    result
+}
+
+/// Replace every occurrence of `original` with `correction`
+///
+/// `ignore_case` compares Unicode case-folded text ("STRASSE" matches
+/// "straße"); `whole_word` only accepts matches that start and end on word
+/// boundaries (UAX #29, plus apostrophes so "été" matches in "l'été").
+/// Matches are found in the folded text and mapped back to whole characters
+/// of the original, whose length may differ from the folded one.
+fn replace_literal(text: &str, original: &str, correction: &str, ignore_case: bool, whole_word: bool) -> String {
+    let needle = fold(original, ignore_case);
+    if needle.is_empty() {
+        return text.to_string();
+    }
+
+    let folded = FoldedText::new(text, ignore_case);
+    let boundaries = whole_word.then(|| word_boundaries(text));
+    let is_boundary = |pos: usize| boundaries.as_ref().is_none_or(|b| b[pos]);
+
+    let mut result = String::with_capacity(text.len());
+    let mut copied = 0; // original text before this offset is in `result`
+    let mut from = 0; // folded offset to search from
+    while let Some(found) = folded.text[from..].find(&needle) {
+        let start = from + found;
+        let end = start + needle.len();
+        match (folded.original_offset(start), folded.original_offset(end)) {
+            (Some(orig_start), Some(orig_end)) if is_boundary(orig_start) && is_boundary(orig_end) => {
+                result.push_str(&text[copied..orig_start]);
+                result.push_str(correction);
+                copied = orig_end;
+                from = end;
             }
+            // Inside a word, or part of one character's folding ("s" of "ß")
+            _ => from = start + folded.text[start..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    result.push_str(&text[copied..]);
+    result
+}
+
+fn fold(text: &str, ignore_case: bool) -> String {
+    if ignore_case {
+        default_case_fold_str(text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Text folded character by character, remembering where each character went
+struct FoldedText {
+    text: String,
+    /// (folded offset, original offset) of each character, then of the end
+    offsets: Vec<(usize, usize)>,
+}
+
+impl FoldedText {
+    fn new(original: &str, ignore_case: bool) -> Self {
+        let mut text = String::with_capacity(original.len());
+        let mut offsets = Vec::with_capacity(original.len() + 1);
+        let mut buf = [0u8; 4];
+        for (pos, c) in original.char_indices() {
+            offsets.push((text.len(), pos));
+            text.push_str(&fold(c.encode_utf8(&mut buf), ignore_case));
         }
+        offsets.push((text.len(), original.len()));
+        Self { text, offsets }
+    }
 
-        result = new_result;
+    /// Original offset of a folded offset, if it falls between two characters
+    fn original_offset(&self, folded: usize) -> Option<usize> {
+        self.offsets
+            .binary_search_by_key(&folded, |&(f, _)| f)
+            .ok()
+            .map(|i| self.offsets[i].1)
     }
+}
 
-    result
+/// For each byte offset of `text`, whether a word starts or ends there
+fn word_boundaries(text: &str) -> Vec<bool> {
+    let mut boundaries = vec![false; text.len() + 1];
+    for (start, segment) in text.split_word_bound_indices() {
+        boundaries[start] = true;
+        // Elisions stay one segment ("l'été"), but each side is a word here
+        for (i, c) in segment.char_indices() {
+            if c == '\'' || c == '’' {
+                boundaries[start + i] = true;
+                boundaries[start + i + c.len_utf8()] = true;
+            }
+        }
+    }
+    boundaries[text.len()] = true;
+    boundaries
 }
 
 /// Clear all dictionary entries (delete file)
@@ -227,3 +421,127 @@ pub fn clear_dictionary() -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(original: &str, correction: &str, match_mode: MatchMode) -> DictionaryEntry {
+        DictionaryEntry {
+            original: original.to_string(),
+            correction: correction.to_string(),
+            created_at: 0,
+            match_mode,
+            enabled: true,
+            language: None,
+            note: String::new(),
+        }
+    }
+
+    #[test]
+    fn old_entries_load_as_enabled_word_entries() {
+        let old = r#"[{"original": "grok", "correction": "Groq", "created_at": 1}]"#;
+        assert!(needs_migration(old));
+        let entries: Vec<DictionaryEntry> = serde_json::from_str(old).unwrap();
+        assert_eq!(entries[0].match_mode, MatchMode::Word);
+        assert!(entries[0].enabled && entries[0].language.is_none());
+        assert!(!needs_migration(&serde_json::to_string(&entries).unwrap()));
+    }
+
+    #[test]
+    fn each_mode_matches_differently() {
+        let text = "Sequel and sequels, sql or SQL, ab-1234";
+        let apply = |e| apply_entries(text, &[e]);
+        assert_eq!(apply(entry("sequel", "SQL", MatchMode::Word)), "SQL and sequels, sql or SQL, ab-1234");
+        assert_eq!(apply(entry("sequel", "SQL", MatchMode::Phrase)), "SQL and SQLs, sql or SQL, ab-1234");
+        assert_eq!(apply(entry("sql", "SQLite", MatchMode::CaseSensitive)), "Sequel and sequels, SQLite or SQL, ab-1234");
+        assert_eq!(
+            apply(entry(r"(?i)\bab-(\d+)", "AB-$1", MatchMode::Regex)),
+            "Sequel and sequels, sql or SQL, AB-1234"
+        );
+        // An invalid pattern leaves the text alone
+        assert_eq!(apply(entry("ab-(", "x", MatchMode::Regex)), text);
+    }
+
+    #[test]
+    fn accented_and_unicode_text() {
+        let word = |o, c, text| apply_entries(text, &[entry(o, c, MatchMode::Word)]);
+        assert_eq!(word("élodie", "Élodie", "Bonjour ÉLODIE, ça va ? élodie."), "Bonjour Élodie, ça va ? Élodie.");
+        assert_eq!(word("ete", "été", "l'ete dernier"), "l'été dernier");
+        // Lowercasing "İ" grows it, which used to shift every later match
+        assert_eq!(word("groq", "Groq", "İİİ groq"), "İİİ Groq");
+        // "é" is a letter: no word boundary before it
+        assert_eq!(word("groq", "Groq", "groqé"), "groqé");
+        // Full case folding: "ß" folds to "ss"
+        assert_eq!(word("STRASSE", "Straße", "die strasse, die straße"), "die Straße, die Straße");
+        // Half of a character's folding is not a match
+        assert_eq!(apply_entries("Straße", &[entry("s", "z", MatchMode::Phrase)]), "ztraße");
+    }
+
+    #[test]
+    fn language_scope() {
+        let mut e = entry("sequel", "SQL", MatchMode::Word);
+        e.language = Some("en".to_string());
+        assert!(e.applies_to(Some("en")) && e.applies_to(None));
+        assert!(!e.applies_to(Some("fr")));
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Latin, accented, Turkish, German, Greek and combining characters
+    const ALPHABET: &str = "[a-zA-ZéÉèàçÇœßẞİıÏïΣσς\u{0301}\u{0307} ,.'’-]{0,40}";
+    const WORD: &str = "[a-zA-ZéÉçÇœßİıΣσ]{1,8}";
+    const MARKER: &str = "⟦X⟧";
+
+    fn literal(original: &str, correction: &str, match_mode: MatchMode) -> DictionaryEntry {
+        DictionaryEntry {
+            original: original.to_string(),
+            correction: correction.to_string(),
+            created_at: 0,
+            match_mode,
+            enabled: true,
+            language: None,
+            note: String::new(),
+        }
+    }
+
+    fn literal_mode() -> impl Strategy<Value = MatchMode> {
+        prop_oneof![Just(MatchMode::Word), Just(MatchMode::Phrase), Just(MatchMode::CaseSensitive)]
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(text in ALPHABET, original in ALPHABET, mode in literal_mode()) {
+            apply_entries(&text, &[literal(&original, MARKER, mode)]);
+        }
+
+        #[test]
+        fn text_without_the_original_is_unchanged(text in ALPHABET, original in WORD, mode in literal_mode()) {
+            if !default_case_fold_str(&text).contains(&default_case_fold_str(&original)) {
+                prop_assert_eq!(apply_entries(&text, &[literal(&original, MARKER, mode)]), text);
+            }
+        }
+
+        #[test]
+        fn replacing_a_word_by_itself_is_a_no_op(text in ALPHABET, original in WORD) {
+            let entries = [literal(&original, &original, MatchMode::CaseSensitive)];
+            prop_assert_eq!(apply_entries(&text, &entries), text);
+        }
+
+        #[test]
+        fn standalone_words_are_replaced(before in ALPHABET, original in WORD, after in ALPHABET) {
+            let text = format!("{} {} {}", before, original, after);
+            let upper = format!("{} {} {}", before, original.to_uppercase(), after);
+            let entries = [literal(&original, MARKER, MatchMode::Word)];
+            prop_assert!(apply_entries(&text, &entries).contains(MARKER));
+            // Uppercasing can change the letter itself (dotless "ı" → "I")
+            if default_case_fold_str(&original.to_uppercase()) == default_case_fold_str(&original) {
+                prop_assert!(apply_entries(&upper, &entries).contains(MARKER));
+            }
+        }
+    }
+}
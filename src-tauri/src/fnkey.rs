@@ -14,6 +14,7 @@
 // to ignore the system's quick Fn/Globe key tap (emoji picker, etc.)
 // and to filter out brief F-key presses.
 
+use crate::settings::PipelineMode;
 use crate::shortcuts::handle_shortcut_event_public;
 use block::ConcreteBlock;
 use cocoa::base::id;
@@ -162,7 +163,7 @@ pub fn start_fn_key_monitor(app: &AppHandle) {
                     FN_RECORDING_ACTIVE.store(true, Ordering::Relaxed);
                     fnlog!("[FnKey] Fn key HELD ({}ms, flags=0x{:X}) — starting recording", elapsed, flags);
                     if let Some(app) = APP_HANDLE.get() {
-                        handle_shortcut_event_public(app, ShortcutState::Pressed);
+                        handle_shortcut_event_public(app, PipelineMode::Dictate, ShortcutState::Pressed);
                     }
                 }
             } else if !fn_held && was_held {
@@ -174,7 +175,7 @@ pub fn start_fn_key_monitor(app: &AppHandle) {
                     FN_RECORDING_ACTIVE.store(false, Ordering::Relaxed);
                     fnlog!("[FnKey] Fn key UP (flags=0x{:X}) — stopping recording", flags);
                     if let Some(app) = APP_HANDLE.get() {
-                        handle_shortcut_event_public(app, ShortcutState::Released);
+                        handle_shortcut_event_public(app, PipelineMode::Dictate, ShortcutState::Released);
                     }
                 } else {
                     // Released before debounce — ignore (system emoji tap)
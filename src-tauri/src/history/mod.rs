@@ -3,4 +3,8 @@
 
 pub mod store;
 
-pub use store::{add_history_entry, clear_history, get_history, HistoryEntry};
+pub use store::{
+    add_history_entry, add_recovered_history_entry, clear_history, delete_history_entry,
+    get_history, get_history_entry, remove_history_entry, retain_history_audio,
+    update_history_entry, HistoryEntry,
+};
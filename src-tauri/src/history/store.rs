@@ -1,9 +1,14 @@
 // TTP - Talk To Paste
 // History store - handles transcription history persistence to JSON file
+//
+// With `keep_history_audio` on, each entry also owns a copy of the audio it
+// was transcribed from (in `history_audio/`, next to history.json). Audio is
+// deleted together with its entry.
 
+use crate::settings::PipelineMode;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// A single history entry representing a past transcription
@@ -15,13 +20,73 @@ pub struct HistoryEntry {
     pub timestamp: i64,
     /// The raw transcription text before AI polish (if polish was enabled)
     pub raw_text: Option<String>,
+    /// Transcribed later by the retry queue after the original attempt failed
+    #[serde(default)]
+    pub recovered: bool,
+    /// Retained audio (mono 16kHz WAV) this entry was transcribed from
+    #[serde(default)]
+    pub audio_path: Option<String>,
+    /// Mode the recording was made in (re-runs repeat it)
+    #[serde(default)]
+    pub mode: PipelineMode,
+    /// The text is a snippet's expansion; `raw_text` holds its trigger
+    #[serde(default)]
+    pub snippet: bool,
 }
 
+impl HistoryEntry {
+    /// Why the entry can't be re-transcribed, re-polished or marked as not
+    /// speech, if it can't
+    ///
+    /// Command-mode entries hold the edited selection and the spoken
+    /// instruction: neither is a dictation that could be redone or filtered.
+    /// Snippet entries hold stored text, and marking their trigger as not
+    /// speech would stop the snippet from working.
+    pub fn rerun_blocker(&self) -> Option<&'static str> {
+        if self.snippet {
+            return Some("Not available for snippets");
+        }
+        match self.mode {
+            PipelineMode::Edit => Some("Not available for edits of selected text"),
+            PipelineMode::Dictate | PipelineMode::Translate => None,
+        }
+    }
+}
+
+/// Maximum number of entries kept (oldest are dropped, with their audio)
+const MAX_HISTORY_ENTRIES: usize = 500;
+
 /// Get the history file path (~/.config/ttp/history.json)
 fn get_history_path() -> Option<PathBuf> {
     dirs::config_dir().map(|p| p.join("ttp").join("history.json"))
 }
 
+/// Directory holding retained audio (~/.config/ttp/history_audio/)
+fn get_history_audio_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("ttp").join("history_audio"))
+}
+
+fn save_history(entries: &[HistoryEntry]) -> Result<(), String> {
+    let path = get_history_path().ok_or("Could not determine config directory")?;
+
+    // Ensure parent directory exists
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+
+    fs::write(&path, json).map_err(|e| format!("Failed to write history file: {}", e))
+}
+
+fn remove_entry_audio(entry: &HistoryEntry) {
+    if let Some(ref audio) = entry.audio_path {
+        let _ = fs::remove_file(audio);
+    }
+}
+
 /// Load history from file, return empty vec if file doesn't exist
 /// Returns entries sorted by timestamp, newest first
 #[tauri::command]
@@ -47,45 +112,124 @@ pub fn get_history() -> Vec<HistoryEntry> {
 }
 
 /// Add a new entry to history
-/// Prepends to existing history (newest first)
-pub fn add_history_entry(text: &str, raw_text: Option<&str>) -> Result<(), String> {
-    let path = get_history_path().ok_or("Could not determine config directory")?;
+/// Prepends to existing history (newest first). Returns the entry's
+/// timestamp, which identifies it in later calls.
+pub fn add_history_entry(
+    text: &str,
+    raw_text: Option<&str>,
+    mode: PipelineMode,
+    snippet: bool,
+) -> Result<i64, String> {
+    insert_history_entry(text, raw_text, mode, snippet, false)
+}
 
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
+/// Add an entry transcribed by the retry queue
+pub fn add_recovered_history_entry(text: &str, raw_text: Option<&str>, mode: PipelineMode) -> Result<i64, String> {
+    insert_history_entry(text, raw_text, mode, false, true)
+}
 
+fn insert_history_entry(
+    text: &str,
+    raw_text: Option<&str>,
+    mode: PipelineMode,
+    snippet: bool,
+    recovered: bool,
+) -> Result<i64, String> {
     // Load existing history
     let mut entries = get_history();
 
-    // Create new entry with current timestamp
-    let timestamp = SystemTime::now()
+    // Create new entry with current timestamp (bumped past the newest so it stays unique)
+    let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_millis() as i64)
         .unwrap_or(0);
+    let timestamp = entries.first().map_or(now, |newest| now.max(newest.timestamp + 1));
 
     let entry = HistoryEntry {
         text: text.to_string(),
         timestamp,
         raw_text: raw_text.map(|s| s.to_string()),
+        recovered,
+        audio_path: None,
+        mode,
+        snippet,
     };
 
     // Prepend new entry (will be at start after sort)
     entries.insert(0, entry);
 
     // Enforce maximum history size to prevent unbounded growth
-    const MAX_HISTORY_ENTRIES: usize = 500;
-    entries.truncate(MAX_HISTORY_ENTRIES);
+    if entries.len() > MAX_HISTORY_ENTRIES {
+        for dropped in entries.drain(MAX_HISTORY_ENTRIES..) {
+            remove_entry_audio(&dropped);
+        }
+    }
 
     // Save back to file
-    let json = serde_json::to_string_pretty(&entries)
-        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+    save_history(&entries)?;
 
-    fs::write(&path, json).map_err(|e| format!("Failed to write history file: {}", e))?;
+    Ok(timestamp)
+}
 
-    Ok(())
+/// Modify the entry with `timestamp` and save; returns the updated entry
+pub fn update_history_entry(
+    timestamp: i64,
+    change: impl FnOnce(&mut HistoryEntry),
+) -> Result<HistoryEntry, String> {
+    let mut entries = get_history();
+    let entry = entries
+        .iter_mut()
+        .find(|e| e.timestamp == timestamp)
+        .ok_or("History entry not found")?;
+    change(entry);
+    let updated = entry.clone();
+    save_history(&entries)?;
+    Ok(updated)
+}
+
+/// Find the entry with `timestamp`
+pub fn get_history_entry(timestamp: i64) -> Option<HistoryEntry> {
+    get_history().into_iter().find(|e| e.timestamp == timestamp)
+}
+
+/// Tauri command: delete one history entry and its kept audio
+#[tauri::command]
+pub fn delete_history_entry(timestamp: i64) -> Result<(), String> {
+    remove_history_entry(timestamp).map(|_| ())
+}
+
+/// Delete the entry with `timestamp` (and its audio); returns the removed entry
+pub fn remove_history_entry(timestamp: i64) -> Result<HistoryEntry, String> {
+    let mut entries = get_history();
+    let index = entries
+        .iter()
+        .position(|e| e.timestamp == timestamp)
+        .ok_or("History entry not found")?;
+    let removed = entries.remove(index);
+    save_history(&entries)?;
+    remove_entry_audio(&removed);
+    Ok(removed)
+}
+
+/// Move `audio` into the history audio directory and link it to an entry
+pub fn retain_history_audio(timestamp: i64, audio: &Path) -> Result<(), String> {
+    let dir = get_history_audio_dir().ok_or("Could not determine config directory")?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create history audio directory: {}", e))?;
+
+    let target = dir.join(format!("{}.wav", timestamp));
+    // Rename fails across filesystems (temp dir on another volume): copy instead
+    if fs::rename(audio, &target).is_err() {
+        fs::copy(audio, &target).map_err(|e| format!("Failed to keep audio: {}", e))?;
+        let _ = fs::remove_file(audio);
+    }
+
+    let target_str = target.to_string_lossy().to_string();
+    let result = update_history_entry(timestamp, |entry| entry.audio_path = Some(target_str));
+    if result.is_err() {
+        let _ = fs::remove_file(&target);
+    }
+    result.map(|_| ())
 }
 
 /// Clear all history by deleting the history file
@@ -99,5 +243,47 @@ pub fn clear_history() -> Result<(), String> {
         fs::remove_file(&path).map_err(|e| format!("Failed to delete history file: {}", e))?;
     }
 
+    // Retained audio belongs to the entries just deleted
+    if let Some(dir) = get_history_audio_dir() {
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to delete history audio: {}", e))?;
+        }
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(mode: PipelineMode) -> HistoryEntry {
+        HistoryEntry {
+            text: "Hello.".to_string(),
+            timestamp: 0,
+            raw_text: None,
+            recovered: false,
+            audio_path: None,
+            mode,
+            snippet: false,
+        }
+    }
+
+    #[test]
+    fn edits_cannot_be_rerun() {
+        assert!(entry(PipelineMode::Dictate).rerun_blocker().is_none());
+        assert!(entry(PipelineMode::Translate).rerun_blocker().is_none());
+        assert!(entry(PipelineMode::Edit).rerun_blocker().is_some());
+        let snippet = HistoryEntry { snippet: true, ..entry(PipelineMode::Dictate) };
+        assert!(snippet.rerun_blocker().is_some());
+    }
+
+    #[test]
+    fn entries_without_a_mode_are_dictations() {
+        let json = r#"{"text": "Hi", "timestamp": 1, "raw_text": null}"#;
+        let entry: HistoryEntry = serde_json::from_str(json).unwrap();
+        assert_eq!(entry.mode, PipelineMode::Dictate);
+        assert!(!entry.snippet);
+    }
+}
@@ -0,0 +1,173 @@
+// TTP - Talk To Paste
+// Per-language profiles: Whisper hint, filler words, stop words
+//
+// `TranscriptionSettings::language` is either unset (auto-detect) or an ISO
+// 639-1 code sent to the provider. A fixed language with a profile uses only
+// that profile; auto-detect (or a code without a profile) merges every
+// profile, so mixed-language speakers get fillers removed in all of them.
+// Hallucination phrases are data, not code: see `transcription::hallucination`.
+
+use serde::Serialize;
+
+/// Language-specific lists used around transcription
+#[derive(Debug, Serialize)]
+pub struct LanguageProfile {
+    /// ISO 639-1 code (Whisper `language` parameter)
+    pub code: &'static str,
+    /// English display name
+    pub name: &'static str,
+    /// Whisper prompt lead-in, written in the language so Whisper keeps to it
+    #[serde(skip)]
+    pub whisper_hint: &'static str,
+    /// Filler words the polish LLM removes ("(as filler)" marks words that
+    /// are only fillers in some positions)
+    #[serde(skip)]
+    pub fillers: &'static [&'static str],
+    /// Short grammar words, edited for grammar rather than to fix a transcription
+    #[serde(skip)]
+    pub stop_words: &'static [&'static str],
+}
+
+/// Whisper hint in auto-detect mode: narrows detection to the two languages
+/// most of our users mix; other speakers should pick a fixed language
+const AUTO_WHISPER_HINT: &str = "French and English bilingual speaker.";
+
+pub const PROFILES: &[LanguageProfile] = &[
+    LanguageProfile {
+        code: "en",
+        name: "English",
+        whisper_hint: "Hello, this is a dictation in English.",
+        fillers: &["um", "uh", "like (as filler)", "you know", "basically"],
+        stop_words: &["the", "a", "an", "is", "are", "was", "were", "be", "to", "of", "in", "it", "for", "on", "at"],
+    },
+    LanguageProfile {
+        code: "fr",
+        name: "French",
+        whisper_hint: "Bonjour, ceci est une dictée en français.",
+        fillers: &["euh", "bah", "ben", "genre (as filler)", "en fait (as filler)"],
+        stop_words: &[
+            "le", "la", "les", "de", "du", "des", "un", "une", "et", "ou", "en", "au", "aux", "ce", "se",
+            "ne", "que", "qui",
+        ],
+    },
+    LanguageProfile {
+        code: "de",
+        name: "German",
+        whisper_hint: "Hallo, das ist ein Diktat auf Deutsch.",
+        fillers: &["äh", "ähm", "öhm", "halt (as filler)", "also (as filler)", "sozusagen", "quasi (as filler)"],
+        stop_words: &[
+            "der", "die", "das", "den", "dem", "des", "ein", "eine", "einen", "und", "oder", "ist", "zu",
+            "im", "in", "am", "an", "auf", "mit", "von",
+        ],
+    },
+    LanguageProfile {
+        code: "es",
+        name: "Spanish",
+        whisper_hint: "Hola, esto es un dictado en español.",
+        fillers: &["eh", "em", "este (as filler)", "o sea", "pues (as filler)", "bueno (as filler)", "vale (as filler)"],
+        stop_words: &[
+            "el", "la", "los", "las", "de", "del", "un", "una", "unos", "y", "o", "en", "al", "que", "se",
+            "es", "por", "con",
+        ],
+    },
+    LanguageProfile {
+        code: "it",
+        name: "Italian",
+        whisper_hint: "Ciao, questo è un dettato in italiano.",
+        fillers: &["ehm", "cioè (as filler)", "tipo (as filler)", "allora (as filler)", "praticamente (as filler)"],
+        stop_words: &[
+            "il", "lo", "la", "gli", "le", "di", "del", "della", "un", "una", "e", "o", "in", "che", "si",
+            "per", "con",
+        ],
+    },
+    LanguageProfile {
+        code: "pt",
+        name: "Portuguese",
+        whisper_hint: "Olá, isto é um ditado em português.",
+        fillers: &["hum", "tipo (as filler)", "né (as filler)", "então (as filler)", "pois (as filler)"],
+        stop_words: &[
+            "o", "a", "os", "as", "de", "do", "da", "dos", "das", "um", "uma", "e", "ou", "em", "no", "na",
+            "que", "se", "por", "com",
+        ],
+    },
+    LanguageProfile {
+        code: "nl",
+        name: "Dutch",
+        whisper_hint: "Hallo, dit is een dictaat in het Nederlands.",
+        fillers: &["eh", "ehm", "nou (as filler)", "zeg maar", "eigenlijk (as filler)"],
+        stop_words: &[
+            "de", "het", "een", "en", "of", "van", "in", "op", "te", "is", "dat", "die", "met", "voor",
+        ],
+    },
+];
+
+/// Profile for an ISO 639-1 code
+pub fn profile(code: &str) -> Option<&'static LanguageProfile> {
+    PROFILES.iter().find(|p| p.code.eq_ignore_ascii_case(code))
+}
+
+/// Profiles that apply to a language setting (`None` = auto-detect)
+pub fn active_profiles(language: Option<&str>) -> Vec<&'static LanguageProfile> {
+    match language.and_then(profile) {
+        Some(p) => vec![p],
+        None => PROFILES.iter().collect(),
+    }
+}
+
+/// Whisper prompt lead-in for a language setting
+///
+/// A fixed language without a profile gets no hint: the `language`
+/// parameter already pins it, and a FR/EN hint would only confuse Whisper.
+pub fn whisper_hint(language: Option<&str>) -> Option<&'static str> {
+    match language {
+        None => Some(AUTO_WHISPER_HINT),
+        Some(code) => profile(code).map(|p| p.whisper_hint),
+    }
+}
+
+/// Filler words for the polish prompt, deduplicated in profile order
+pub fn fillers(language: Option<&str>) -> Vec<&'static str> {
+    let mut fillers: Vec<&'static str> = Vec::new();
+    for filler in active_profiles(language).iter().flat_map(|p| p.fillers.iter()) {
+        if !fillers.contains(filler) {
+            fillers.push(filler);
+        }
+    }
+    fillers
+}
+
+/// Whether a word is a stop word in the active language(s)
+pub fn is_stop_word(word: &str, language: Option<&str>) -> bool {
+    let lower = word.to_lowercase();
+    active_profiles(language)
+        .iter()
+        .any(|p| p.stop_words.contains(&lower.as_str()))
+}
+
+/// Tauri command: languages with a profile, for the language picker
+#[tauri::command]
+pub fn get_languages() -> &'static [LanguageProfile] {
+    PROFILES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_language_uses_only_its_profile() {
+        assert!(is_stop_word("der", Some("de")));
+        assert!(!is_stop_word("the", Some("de")));
+        assert_eq!(fillers(Some("es"))[0], "eh");
+    }
+
+    #[test]
+    fn auto_and_unknown_codes_merge_every_profile() {
+        assert!(is_stop_word("les", None));
+        assert!(is_stop_word("die", Some("ja")));
+        assert_eq!(whisper_hint(None), Some(AUTO_WHISPER_HINT));
+        assert_eq!(whisper_hint(Some("ja")), None);
+        let all = fillers(None);
+        assert_eq!(all.iter().filter(|f| **f == "eh").count(), 1);
+    }
+}
@@ -1,19 +1,24 @@
 // TTP - Talk To Paste
 // Main Tauri application entry point
 
+mod audio_devices;
 mod audio_monitor;
 mod credentials;
 mod dictionary;
 #[cfg(target_os = "macos")]
 mod fnkey;
 mod history;
+mod language;
+mod llm;
 pub mod logging;
 mod onboarding;
 mod paste;
 mod permissions;
+mod profiles;
 mod recording;
 mod settings;
 mod shortcuts;
+mod snippets;
 mod sounds;
 mod state;
 mod telemetry;
@@ -22,21 +27,36 @@ mod tray;
 mod whatsnew;
 
 use credentials::{
-    delete_groq_api_key, get_groq_api_key, has_groq_api_key, set_groq_api_key,
-    validate_groq_api_key,
+    delete_groq_api_key, delete_provider_api_key, get_groq_api_key, has_groq_api_key,
+    has_provider_api_key, set_groq_api_key, set_provider_api_key, validate_groq_api_key,
 };
-use dictionary::{add_dictionary_entry, clear_dictionary, delete_dictionary_entry, get_dictionary};
-use history::{clear_history, get_history};
+use dictionary::{
+    add_dictionary_entry, clear_dictionary, delete_dictionary_entry, get_dictionary,
+    set_dictionary_entry_enabled,
+};
+use history::{clear_history, delete_history_entry, get_history};
+use language::get_languages;
 use onboarding::{close_onboarding, show_onboarding};
 use permissions::{
     check_microphone_permission, is_first_launch_cmd, mark_first_launch_complete_cmd,
     check_accessibility_permission, request_accessibility_permission,
     reset_accessibility_permission, PermissionStatus,
 };
+use audio_devices::list_input_devices;
 use recording::{get_recordings_dir, RecordingContext};
-use settings::{get_settings, reset_settings, set_settings, open_settings_window};
+use settings::{get_settings, reset_settings, set_settings, open_settings_window, PipelineMode};
+use snippets::{add_snippet, delete_snippet, get_snippets};
 use state::AppState;
+use transcription::local::{
+    delete_local_model, download_local_model, list_local_models, verify_local_model,
+};
+use transcription::hallucination::mark_history_hallucination;
 use transcription::process_audio;
+use transcription::rerun::{repolish_history_entry, retranscribe_history_entry};
+use transcription::retry::{
+    copy_retry_job_text, discard_retry_job, get_retry_jobs, retry_job_now,
+};
+use transcription::styles::{delete_polish_style, get_polish_styles, save_polish_style};
 use whatsnew::{check_whats_new, dismiss_whats_new};
 use std::sync::Mutex;
 #[cfg(target_os = "macos")]
@@ -50,15 +70,30 @@ fn update_shortcut_cmd(app: AppHandle, shortcut: String) -> Result<(), String> {
     shortcuts::update_shortcut(&app, &shortcut)
 }
 
-/// Tauri command to unregister all global shortcuts (used when switching to Fn mode)
+/// Tauri command to unregister the recording shortcut (used when switching to Fn mode)
+///
+/// The translation and command mode shortcuts stay registered.
 #[tauri::command]
 fn unregister_shortcuts_cmd(app: AppHandle) -> Result<(), String> {
     app.global_shortcut()
         .unregister_all()
         .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+    shortcuts::register_mode_shortcuts(&app);
     Ok(())
 }
 
+/// Tauri command to change the translation-mode shortcut (`None` turns it off)
+#[tauri::command]
+fn update_translation_shortcut_cmd(app: AppHandle, shortcut: Option<String>) -> Result<(), String> {
+    shortcuts::update_mode_shortcut(&app, PipelineMode::Translate, shortcut.as_deref())
+}
+
+/// Tauri command to change the command mode (edit selection) shortcut (`None` turns it off)
+#[tauri::command]
+fn update_edit_shortcut_cmd(app: AppHandle, shortcut: Option<String>) -> Result<(), String> {
+    shortcuts::update_mode_shortcut(&app, PipelineMode::Edit, shortcut.as_deref())
+}
+
 /// Tauri command to toggle Fn key monitoring
 #[tauri::command]
 fn set_fn_key_enabled(enabled: bool) {
@@ -136,13 +171,13 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(move |app, _shortcut, event| {
-                    shortcuts::handle_shortcut_event_public(app, event.state());
+                .with_handler(move |app, shortcut, event| {
+                    let mode = shortcuts::mode_for_shortcut(shortcut);
+                    shortcuts::handle_shortcut_event_public(app, mode, event.state());
                 })
                 .build(),
         )
         .plugin(tauri_plugin_positioner::init())
-        .plugin(tauri_plugin_mic_recorder::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_notification::init())
@@ -169,9 +204,15 @@ pub fn run() {
                 });
             }
 
+            // Trace API request attempts as Sentry breadcrumbs (no-op without consent)
+            llm::add_trace_hook(telemetry::sentry::http_breadcrumb);
+
             // Clean up stale audio backups (>24 hours old)
             transcription::backup::cleanup_stale_backups(app.handle());
 
+            // Resume retrying recordings whose transcription failed
+            transcription::retry::start(app.handle());
+
             // Hide from dock — TTP is a tray-only app
             #[cfg(target_os = "macos")]
             app.set_activation_policy(ActivationPolicy::Accessory);
@@ -271,20 +312,46 @@ pub fn run() {
             has_groq_api_key,
             delete_groq_api_key,
             validate_groq_api_key,
+            set_provider_api_key,
+            has_provider_api_key,
+            delete_provider_api_key,
             get_recordings_dir,
+            list_input_devices,
             process_audio,
+            get_retry_jobs,
+            retry_job_now,
+            discard_retry_job,
+            copy_retry_job_text,
+            list_local_models,
+            download_local_model,
+            verify_local_model,
+            delete_local_model,
             get_settings,
             set_settings,
             reset_settings,
             open_settings_window,
+            get_languages,
             get_dictionary,
             add_dictionary_entry,
             delete_dictionary_entry,
+            set_dictionary_entry_enabled,
             clear_dictionary,
+            get_snippets,
+            add_snippet,
+            delete_snippet,
             get_history,
             clear_history,
+            delete_history_entry,
+            retranscribe_history_entry,
+            repolish_history_entry,
+            mark_history_hallucination,
+            get_polish_styles,
+            save_polish_style,
+            delete_polish_style,
             update_shortcut_cmd,
             unregister_shortcuts_cmd,
+            update_translation_shortcut_cmd,
+            update_edit_shortcut_cmd,
             set_fn_key_enabled,
             check_input_monitoring,
             reset_to_idle,
@@ -0,0 +1,80 @@
+// TTP - Talk To Paste
+// Anthropic-style Messages API wire format (`/v1/messages`)
+
+use super::LlmConfig;
+use serde::{Deserialize, Serialize};
+
+/// API version header required by the Messages API
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Messages request body (system prompt is a top-level field)
+#[derive(Debug, Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    system: &'a str,
+    messages: Vec<Message<'a>>,
+    max_tokens: u32,
+    temperature: f32,
+}
+
+/// Message structure
+#[derive(Debug, Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+/// Messages response body
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+/// Content block in the response (only text blocks are used)
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: String,
+}
+
+/// Build a `POST {base}/v1/messages` request
+pub fn build_request(
+    client: &reqwest::Client,
+    config: &LlmConfig,
+    system_prompt: &str,
+    user_content: &str,
+) -> reqwest::RequestBuilder {
+    let body = MessagesRequest {
+        model: &config.model,
+        system: system_prompt,
+        messages: vec![Message {
+            role: "user",
+            content: user_content,
+        }],
+        max_tokens: config.max_tokens,
+        temperature: config.temperature,
+    };
+
+    let mut request = client
+        .post(format!("{}/v1/messages", config.base_url))
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .json(&body);
+    if let Some(key) = &config.api_key {
+        request = request.header("x-api-key", key);
+    }
+    request
+}
+
+/// Concatenate the text blocks of the reply
+pub async fn parse_response(response: reqwest::Response) -> Result<String, String> {
+    let messages_response: MessagesResponse = response.json().await.map_err(|e| e.to_string())?;
+    let text: String = messages_response
+        .content
+        .into_iter()
+        .filter(|block| block.kind == "text")
+        .map(|block| block.text)
+        .collect();
+    Ok(text)
+}
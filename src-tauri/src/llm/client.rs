@@ -0,0 +1,363 @@
+// TTP - Talk To Paste
+// Shared HTTP plumbing for every API call (chat, transcription)
+//
+// One `reqwest::Client` is reused for the whole app lifetime so back-to-back
+// dictations keep their TCP/TLS connections warm. Requests go through
+// `send_with_retry`, which applies a `RetryPolicy` (honoring `Retry-After`
+// on 429), maps failures to `LlmError`, and reports every attempt to the
+// registered trace hooks.
+
+use crate::logging::log_error;
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// Upper bound for a server-requested `Retry-After` wait
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Typed error for API calls
+#[derive(Debug, Clone)]
+pub enum LlmError {
+    /// Missing key, bad URL, unknown provider... (never retried)
+    Config(String),
+    /// Connection / DNS / TLS failure
+    Network(String),
+    /// Request exceeded its timeout
+    Timeout,
+    /// 429 Too Many Requests (after retries were exhausted)
+    RateLimited { retry_after: Option<Duration> },
+    /// 401 / 403
+    Unauthorized { status: reqwest::StatusCode, body: String },
+    /// Any other non-success HTTP status
+    Http { status: reqwest::StatusCode, body: String },
+    /// Response body could not be parsed
+    InvalidResponse(String),
+    /// Response parsed but contained no text
+    EmptyResponse,
+}
+
+impl LlmError {
+    /// Whether another attempt could succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Network(_) | Self::Timeout | Self::RateLimited { .. } => true,
+            Self::Http { status, .. } => status.is_server_error(),
+            _ => false,
+        }
+    }
+
+    fn from_reqwest(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::Network(e.to_string())
+        }
+    }
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Config(msg) => write!(f, "{}", msg),
+            Self::Network(msg) => write!(f, "request failed (network): {}", msg),
+            Self::Timeout => write!(f, "request timeout"),
+            Self::RateLimited { retry_after: Some(d) } => {
+                write!(f, "API error: 429 rate limited (retry after {}s)", d.as_secs())
+            }
+            Self::RateLimited { retry_after: None } => write!(f, "API error: 429 rate limited"),
+            Self::Unauthorized { status, body } => write!(f, "API error: {} (unauthorized) - {}", status, body),
+            Self::Http { status, body } => write!(f, "API error: {} - {}", status, body),
+            Self::InvalidResponse(msg) => write!(f, "invalid response: {}", msg),
+            Self::EmptyResponse => write!(f, "empty response"),
+        }
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+/// How many times to try a request and how long to wait in between
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one
+    pub max_attempts: u32,
+    /// Linear backoff step: attempt N waits `base_delay * N`
+    pub base_delay: Duration,
+    /// Wait what the server asks for on 429 (capped at 30s) instead of the backoff
+    pub honor_retry_after: bool,
+}
+
+impl RetryPolicy {
+    /// The app's historical behavior: 3 attempts, 500ms / 1000ms backoff
+    pub const fn standard() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            honor_retry_after: true,
+        }
+    }
+
+    /// Single attempt (non-critical calls such as classification)
+    pub const fn once() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay: Duration::from_millis(0),
+            honor_retry_after: false,
+        }
+    }
+
+    /// Same policy with a different attempt count
+    pub const fn with_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..self
+        }
+    }
+
+    fn delay_before(&self, attempt: u32, last_error: Option<&LlmError>) -> Duration {
+        if self.honor_retry_after {
+            if let Some(LlmError::RateLimited {
+                retry_after: Some(wait),
+            }) = last_error
+            {
+                return (*wait).min(MAX_RETRY_AFTER);
+            }
+        }
+        self.base_delay * attempt
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// One finished attempt, as seen by trace hooks
+#[derive(Debug, Clone)]
+pub struct TraceEvent {
+    /// Caller label ("Polish", "Transcription", ...)
+    pub label: String,
+    pub url: String,
+    /// 1-based attempt number
+    pub attempt: u32,
+    /// HTTP status, if a response arrived
+    pub status: Option<u16>,
+    pub elapsed: Duration,
+    /// Error description for failed attempts
+    pub error: Option<String>,
+}
+
+type TraceHook = Box<dyn Fn(&TraceEvent) + Send + Sync>;
+
+/// Registered request/response observers
+static TRACE_HOOKS: RwLock<Vec<TraceHook>> = RwLock::new(Vec::new());
+
+/// Register a hook called after every request attempt
+pub fn add_trace_hook(hook: impl Fn(&TraceEvent) + Send + Sync + 'static) {
+    if let Ok(mut hooks) = TRACE_HOOKS.write() {
+        hooks.push(Box::new(hook));
+    }
+}
+
+fn trace(event: &TraceEvent) {
+    if let Ok(hooks) = TRACE_HOOKS.read() {
+        for hook in hooks.iter() {
+            hook(event);
+        }
+    }
+}
+
+/// App-wide HTTP client (connection pool shared by all API calls)
+pub fn shared_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// Parse a `Retry-After` value: delta-seconds ("30") or an HTTP-date
+/// ("Wed, 21 Oct 2015 07:28:00 GMT"), relative to `now`
+///
+/// A date in the past means "retry now" (zero wait).
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&chrono::Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Typed error for a non-success status
+fn classify_status(status: reqwest::StatusCode, body: String, retry_after: Option<Duration>) -> LlmError {
+    match status.as_u16() {
+        429 => LlmError::RateLimited { retry_after },
+        401 | 403 => LlmError::Unauthorized { status, body },
+        _ => LlmError::Http { status, body },
+    }
+}
+
+/// Map a non-success response to a typed error (consumes the body)
+async fn error_from_response(response: reqwest::Response) -> LlmError {
+    let status = response.status();
+    if status.as_u16() == 429 {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| parse_retry_after(v, chrono::Utc::now()));
+        return classify_status(status, String::new(), retry_after);
+    }
+    let body = response.text().await.unwrap_or_default();
+    classify_status(status, body, None)
+}
+
+/// Send a request with retries, returning the first successful response
+///
+/// `build` is called once per attempt because request bodies (multipart
+/// forms in particular) are consumed by sending. 4xx errors other than 429
+/// fail immediately; network errors, timeouts, 429 and 5xx are retried.
+pub async fn send_with_retry<F>(
+    label: &str,
+    policy: RetryPolicy,
+    timeout: Duration,
+    build: F,
+) -> Result<reqwest::Response, LlmError>
+where
+    F: Fn(&reqwest::Client) -> Result<reqwest::RequestBuilder, LlmError>,
+{
+    let client = shared_client();
+    let mut last_error: Option<LlmError> = None;
+
+    for attempt in 0..policy.max_attempts.max(1) {
+        if attempt > 0 {
+            sleep(policy.delay_before(attempt, last_error.as_ref())).await;
+        }
+
+        let request = build(client)?
+            .timeout(timeout)
+            .build()
+            .map_err(|e| LlmError::Config(format!("Invalid request: {}", e)))?;
+        let url = request.url().to_string();
+        let started = Instant::now();
+
+        let error = match client.execute(request).await {
+            Ok(response) if response.status().is_success() => {
+                trace(&TraceEvent {
+                    label: label.to_string(),
+                    url,
+                    attempt: attempt + 1,
+                    status: Some(response.status().as_u16()),
+                    elapsed: started.elapsed(),
+                    error: None,
+                });
+                return Ok(response);
+            }
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let error = error_from_response(response).await;
+                trace(&TraceEvent {
+                    label: label.to_string(),
+                    url,
+                    attempt: attempt + 1,
+                    status: Some(status),
+                    elapsed: started.elapsed(),
+                    error: Some(error.to_string()),
+                });
+                error
+            }
+            Err(e) => {
+                let error = LlmError::from_reqwest(e);
+                trace(&TraceEvent {
+                    label: label.to_string(),
+                    url,
+                    attempt: attempt + 1,
+                    status: None,
+                    elapsed: started.elapsed(),
+                    error: Some(error.to_string()),
+                });
+                error
+            }
+        };
+
+        let message: String = error.to_string().chars().take(300).collect();
+        log_error(&format!("{} {}", label, message));
+
+        if !error.is_retryable() {
+            return Err(error);
+        }
+        last_error = Some(error);
+    }
+
+    // All retries exhausted
+    Err(last_error.unwrap_or(LlmError::Network("no attempts made".to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn rate_limited(secs: u64) -> LlmError {
+        LlmError::RateLimited {
+            retry_after: Some(Duration::from_secs(secs)),
+        }
+    }
+
+    #[test]
+    fn backoff_is_linear() {
+        let policy = RetryPolicy::standard();
+        assert_eq!(policy.delay_before(1, None), Duration::from_millis(500));
+        assert_eq!(policy.delay_before(2, None), Duration::from_millis(1000));
+        assert_eq!(policy.delay_before(2, Some(&LlmError::Timeout)), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn retry_after_is_honored_and_capped() {
+        let policy = RetryPolicy::standard();
+        assert_eq!(policy.delay_before(1, Some(&rate_limited(7))), Duration::from_secs(7));
+        assert_eq!(policy.delay_before(1, Some(&rate_limited(600))), MAX_RETRY_AFTER);
+        // Without a Retry-After the backoff applies
+        let no_hint = LlmError::RateLimited { retry_after: None };
+        assert_eq!(policy.delay_before(1, Some(&no_hint)), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn once_makes_a_single_attempt_without_waiting() {
+        let policy = RetryPolicy::once();
+        assert_eq!(policy.max_attempts, 1);
+        assert_eq!(policy.delay_before(1, Some(&rate_limited(7))), Duration::ZERO);
+        assert_eq!(RetryPolicy::standard().with_attempts(5).max_attempts, 5);
+    }
+
+    #[test]
+    fn retry_after_parses_seconds_and_dates() {
+        let now = chrono::DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(parse_retry_after(" 30 ", now), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:45 GMT", now), Some(Duration::from_secs(45)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+        assert_eq!(parse_retry_after("-5", now), None);
+    }
+
+    #[test]
+    fn statuses_are_classified() {
+        let classify = |code| classify_status(StatusCode::from_u16(code).unwrap(), String::new(), None);
+        assert!(matches!(classify(429), LlmError::RateLimited { retry_after: None }));
+        assert!(matches!(classify(401), LlmError::Unauthorized { .. }));
+        assert!(matches!(classify(403), LlmError::Unauthorized { .. }));
+        assert!(matches!(classify(400), LlmError::Http { .. }));
+
+        assert!(classify(429).is_retryable());
+        assert!(classify(500).is_retryable() && classify(503).is_retryable());
+        assert!(!classify(401).is_retryable() && !classify(404).is_retryable());
+        assert!(LlmError::Timeout.is_retryable() && LlmError::Network(String::new()).is_retryable());
+        assert!(!LlmError::Config(String::new()).is_retryable());
+        assert!(!LlmError::EmptyResponse.is_retryable());
+    }
+}
@@ -0,0 +1,183 @@
+// TTP - Talk To Paste
+// Shared chat client for LLM tasks (AI polish, dictionary classification)
+//
+// Each task has its own provider, endpoint, model, temperature and
+// max_tokens in `Settings`. `resolve_task` turns those into an `LlmConfig`
+// and `complete` sends the request in the provider's wire format:
+// OpenAI-compatible chat completions (Groq, OpenAI, vLLM, ...), Ollama's
+// native chat API, or Anthropic-style messages. Transport (shared client,
+// retries, tracing) lives in `client` and is also used by transcription.
+
+pub mod anthropic;
+pub mod client;
+pub mod ollama;
+pub mod openai;
+
+pub use client::{add_trace_hook, send_with_retry, LlmError, RetryPolicy, TraceEvent};
+
+use crate::credentials::{get_provider_api_key_internal, ApiKeyProvider};
+use crate::settings::{LlmProviderKind, LlmSettings};
+use std::time::Duration;
+
+/// Groq OpenAI-compatible API base
+const GROQ_BASE_URL: &str = "https://api.groq.com/openai/v1";
+
+/// OpenAI API base
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Default local Ollama server
+const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+/// Anthropic API base
+const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com";
+
+/// What the LLM is being used for (decides defaults and log labels)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmTask {
+    Polish,
+    Classify,
+}
+
+impl LlmTask {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Polish => "Polish",
+            Self::Classify => "Classify",
+        }
+    }
+
+    fn default_temperature(&self) -> f32 {
+        match self {
+            Self::Polish => 0.1, // Very low for consistency
+            Self::Classify => 0.0,
+        }
+    }
+
+    fn default_max_tokens(&self) -> u32 {
+        match self {
+            Self::Polish => 8192, // Enough for long transcriptions
+            Self::Classify => 16,
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        match self {
+            Self::Polish => Duration::from_secs(30),
+            Self::Classify => Duration::from_secs(10), // Short — this is non-critical
+        }
+    }
+
+    fn default_retry_policy(&self) -> RetryPolicy {
+        match self {
+            Self::Polish => RetryPolicy::standard(),
+            Self::Classify => RetryPolicy::once(), // No retries — this is non-critical
+        }
+    }
+}
+
+/// Fully resolved settings for one chat request
+#[derive(Debug, Clone)]
+pub struct LlmConfig {
+    pub task: LlmTask,
+    pub provider: LlmProviderKind,
+    pub base_url: String,
+    pub model: String,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub api_key: Option<String>,
+    pub retry: RetryPolicy,
+}
+
+/// Default model per provider when the settings don't name one
+fn default_model(provider: LlmProviderKind) -> Option<&'static str> {
+    match provider {
+        LlmProviderKind::Groq => Some("llama-3.3-70b-versatile"),
+        LlmProviderKind::OpenAi => Some("gpt-4o-mini"),
+        LlmProviderKind::Ollama => Some("llama3.1"),
+        LlmProviderKind::Anthropic => Some("claude-3-5-haiku-latest"),
+        LlmProviderKind::OpenAiCompatible => None,
+    }
+}
+
+/// Resolve the settings of one task into a request config
+///
+/// Fails when the provider needs an API key that isn't configured, or when
+/// an OpenAI-compatible server has no base URL / model.
+pub fn resolve_task(settings: &LlmSettings, task: LlmTask) -> Result<LlmConfig, String> {
+    let provider = settings.provider;
+    let base_url = settings.base_url.as_deref().filter(|u| !u.trim().is_empty());
+    let model = settings.model.as_deref().filter(|m| !m.trim().is_empty());
+
+    let base_url = match (provider, base_url) {
+        (_, Some(url)) => url,
+        (LlmProviderKind::Groq, None) => GROQ_BASE_URL,
+        (LlmProviderKind::OpenAi, None) => OPENAI_BASE_URL,
+        (LlmProviderKind::Ollama, None) => OLLAMA_BASE_URL,
+        (LlmProviderKind::Anthropic, None) => ANTHROPIC_BASE_URL,
+        (LlmProviderKind::OpenAiCompatible, None) => {
+            return Err("OpenAI-compatible LLM requires a base URL".to_string())
+        }
+    };
+
+    let model = model
+        .or_else(|| default_model(provider))
+        .ok_or_else(|| "OpenAI-compatible LLM requires a model name".to_string())?;
+
+    let api_key = ApiKeyProvider::for_llm(provider).and_then(get_provider_api_key_internal);
+    let needs_key = matches!(
+        provider,
+        LlmProviderKind::Groq | LlmProviderKind::OpenAi | LlmProviderKind::Anthropic
+    );
+    if needs_key && api_key.is_none() {
+        return Err(format!("No API key configured for {} LLM", task.label().to_lowercase()));
+    }
+
+    Ok(LlmConfig {
+        task,
+        provider,
+        base_url: base_url.trim_end_matches('/').to_string(),
+        model: model.to_string(),
+        temperature: settings.temperature.unwrap_or_else(|| task.default_temperature()),
+        max_tokens: settings.max_tokens.unwrap_or_else(|| task.default_max_tokens()),
+        api_key,
+        retry: match settings.max_attempts {
+            Some(attempts) => task.default_retry_policy().with_attempts(attempts),
+            None => task.default_retry_policy(),
+        },
+    })
+}
+
+/// Send a system + user prompt and return the trimmed reply text
+///
+/// Retries follow `config.retry` (see `client::send_with_retry`).
+pub async fn complete(
+    config: &LlmConfig,
+    system_prompt: &str,
+    user_content: &str,
+) -> Result<String, LlmError> {
+    let response = send_with_retry(config.task.label(), config.retry, config.task.timeout(), |client| {
+        Ok(match config.provider {
+            LlmProviderKind::Groq | LlmProviderKind::OpenAi | LlmProviderKind::OpenAiCompatible => {
+                openai::build_request(client, config, system_prompt, user_content)
+            }
+            LlmProviderKind::Ollama => ollama::build_request(client, config, system_prompt, user_content),
+            LlmProviderKind::Anthropic => {
+                anthropic::build_request(client, config, system_prompt, user_content)
+            }
+        })
+    })
+    .await?;
+
+    let content = match config.provider {
+        LlmProviderKind::Ollama => ollama::parse_response(response).await,
+        LlmProviderKind::Anthropic => anthropic::parse_response(response).await,
+        _ => openai::parse_response(response).await,
+    }
+    .map_err(LlmError::InvalidResponse)?;
+
+    let trimmed = content.trim().to_string();
+    if trimmed.is_empty() {
+        return Err(LlmError::EmptyResponse);
+    }
+    Ok(trimmed)
+}
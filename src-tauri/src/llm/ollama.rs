@@ -0,0 +1,80 @@
+// TTP - Talk To Paste
+// Ollama native chat wire format (`/api/chat`, non-streaming)
+
+use super::LlmConfig;
+use serde::{Deserialize, Serialize};
+
+/// Chat request body
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    stream: bool,
+    options: ChatOptions,
+}
+
+/// Chat message structure
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+/// Sampling options (Ollama calls max_tokens `num_predict`)
+#[derive(Debug, Serialize)]
+struct ChatOptions {
+    temperature: f32,
+    num_predict: u32,
+}
+
+/// Chat response body
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    message: ChatMessageResponse,
+}
+
+/// Message content in chat response
+#[derive(Debug, Deserialize)]
+struct ChatMessageResponse {
+    content: String,
+}
+
+/// Build a `POST {base}/api/chat` request
+pub fn build_request(
+    client: &reqwest::Client,
+    config: &LlmConfig,
+    system_prompt: &str,
+    user_content: &str,
+) -> reqwest::RequestBuilder {
+    let body = ChatRequest {
+        model: &config.model,
+        messages: vec![
+            ChatMessage {
+                role: "system",
+                content: system_prompt,
+            },
+            ChatMessage {
+                role: "user",
+                content: user_content,
+            },
+        ],
+        stream: false,
+        options: ChatOptions {
+            temperature: config.temperature,
+            num_predict: config.max_tokens,
+        },
+    };
+
+    let mut request = client.post(format!("{}/api/chat", config.base_url)).json(&body);
+    // Ollama itself has no auth, but reverse proxies in front of it often do
+    if let Some(key) = &config.api_key {
+        request = request.header("Authorization", format!("Bearer {}", key));
+    }
+    request
+}
+
+/// Extract the assistant message content
+pub async fn parse_response(response: reqwest::Response) -> Result<String, String> {
+    let chat_response: ChatResponse = response.json().await.map_err(|e| e.to_string())?;
+    Ok(chat_response.message.content)
+}
@@ -0,0 +1,82 @@
+// TTP - Talk To Paste
+// OpenAI-compatible chat completions wire format (Groq, OpenAI, vLLM, ...)
+
+use super::LlmConfig;
+use serde::{Deserialize, Serialize};
+
+/// Chat completion request body
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    temperature: f32,
+    max_tokens: u32,
+}
+
+/// Chat message structure
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+/// Chat completion response body
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+/// Individual choice in chat response
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessageResponse,
+}
+
+/// Message content in chat response
+#[derive(Debug, Deserialize)]
+struct ChatMessageResponse {
+    content: String,
+}
+
+/// Build a `POST {base}/chat/completions` request
+pub fn build_request(
+    client: &reqwest::Client,
+    config: &LlmConfig,
+    system_prompt: &str,
+    user_content: &str,
+) -> reqwest::RequestBuilder {
+    let body = ChatRequest {
+        model: &config.model,
+        messages: vec![
+            ChatMessage {
+                role: "system",
+                content: system_prompt,
+            },
+            ChatMessage {
+                role: "user",
+                content: user_content,
+            },
+        ],
+        temperature: config.temperature,
+        max_tokens: config.max_tokens,
+    };
+
+    let mut request = client
+        .post(format!("{}/chat/completions", config.base_url))
+        .json(&body);
+    if let Some(key) = &config.api_key {
+        request = request.header("Authorization", format!("Bearer {}", key));
+    }
+    request
+}
+
+/// Extract the content of the first choice
+pub async fn parse_response(response: reqwest::Response) -> Result<String, String> {
+    let chat_response: ChatResponse = response.json().await.map_err(|e| e.to_string())?;
+    chat_response
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message.content)
+        .ok_or_else(|| "no choices in response".to_string())
+}
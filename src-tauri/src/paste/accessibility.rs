@@ -1,5 +1,5 @@
 // TTP - Accessibility text reading
-// Reads the text content of the currently focused UI element using macOS Accessibility API
+// Reads the text content (or selection) of the currently focused UI element using macOS Accessibility API
 // Supports both native text fields (AXValue) and Chrome contenteditable (AXStringForRange)
 
 #[cfg(target_os = "macos")]
@@ -86,19 +86,80 @@ pub fn read_focused_text() -> Option<String> {
     }
 }
 
-/// Try reading text from Chrome's active element via AppleScript + JavaScript
-/// Requires "Allow JavaScript from Apple Events" enabled in Chrome (View > Developer)
+/// Read the selected text of the currently focused UI element (AXSelectedText)
+///
+/// Returns `None` when nothing is selected or the app doesn't expose its
+/// selection (many Electron and web apps); callers fall back to copying.
 #[cfg(target_os = "macos")]
-fn read_chrome_active_element() -> Option<String> {
-    // Check if Chrome is frontmost
-    let check = std::process::Command::new("osascript")
+pub fn read_selected_text() -> Option<String> {
+    unsafe {
+        let system_wide = AXUIElementCreateSystemWide();
+        if system_wide.is_null() {
+            return None;
+        }
+
+        let focused_attr = CFString::new("AXFocusedUIElement");
+        let mut focused: CFTypeRef = std::ptr::null_mut();
+        let err = AXUIElementCopyAttributeValue(
+            system_wide,
+            focused_attr.as_concrete_TypeRef(),
+            &mut focused,
+        );
+        CFRelease(system_wide as CFTypeRef);
+
+        if err != AX_ERROR_SUCCESS || focused.is_null() {
+            return None;
+        }
+
+        let selected_attr = CFString::new("AXSelectedText");
+        let mut value: CFTypeRef = std::ptr::null_mut();
+        let err = AXUIElementCopyAttributeValue(
+            focused as AXUIElementRef,
+            selected_attr.as_concrete_TypeRef(),
+            &mut value,
+        );
+        CFRelease(focused);
+
+        if err != AX_ERROR_SUCCESS || value.is_null() {
+            return None;
+        }
+
+        if core_foundation::base::CFGetTypeID(value) != CFString::type_id() {
+            CFRelease(value);
+            return None;
+        }
+        let text = CFString::wrap_under_create_rule(value as CFStringRef).to_string();
+        if text.is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    }
+}
+
+/// Name of the frontmost application process ("Google Chrome", "Terminal")
+#[cfg(target_os = "macos")]
+pub fn frontmost_app() -> Option<String> {
+    let output = std::process::Command::new("osascript")
         .arg("-e")
         .arg("tell application \"System Events\" to get name of first process whose frontmost is true")
         .output()
         .ok()?;
 
-    let app_name = String::from_utf8_lossy(&check.stdout).trim().to_string();
-    if app_name != "Google Chrome" {
+    let app_name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if app_name.is_empty() {
+        None
+    } else {
+        Some(app_name)
+    }
+}
+
+/// Try reading text from Chrome's active element via AppleScript + JavaScript
+/// Requires "Allow JavaScript from Apple Events" enabled in Chrome (View > Developer)
+#[cfg(target_os = "macos")]
+fn read_chrome_active_element() -> Option<String> {
+    // Check if Chrome is frontmost
+    if frontmost_app()? != "Google Chrome" {
         return None;
     }
 
@@ -226,3 +287,13 @@ unsafe fn read_ax_string_for_range(element: AXUIElementRef) -> Option<String> {
 pub fn read_focused_text() -> Option<String> {
     None
 }
+
+#[cfg(not(target_os = "macos"))]
+pub fn read_selected_text() -> Option<String> {
+    None
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn frontmost_app() -> Option<String> {
+    None
+}
@@ -36,6 +36,11 @@ impl ClipboardGuard {
         }
     }
 
+    /// Read the current clipboard text
+    pub fn read_text(&self) -> Option<String> {
+        self.app.clipboard().read_text().ok()
+    }
+
     /// Write text to the clipboard
     pub fn write_text(&self, text: &str) -> Result<(), String> {
         self.app
@@ -0,0 +1,56 @@
+// TTP - Selection reading
+// Reads the text selected in the frontmost app, for command mode
+//
+// The Accessibility API is tried first; apps that don't expose their
+// selection (Electron, many web apps) get a copy round-trip instead: the
+// clipboard is emptied, Cmd+C is simulated, and the original clipboard is
+// restored once the copy lands.
+
+use super::accessibility::read_selected_text;
+use super::clipboard::ClipboardGuard;
+use super::simulate::simulate_copy;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// How long to wait for the target app to answer Cmd+C
+const COPY_TIMEOUT_MS: u64 = 500;
+const COPY_POLL_MS: u64 = 50;
+
+/// Read the current selection, `None` when nothing is selected
+///
+/// Blocking (simulated keystrokes and polling): call from `spawn_blocking`.
+pub fn read_selection(app: &AppHandle) -> Option<String> {
+    if let Some(text) = read_selected_text().filter(|t| !t.trim().is_empty()) {
+        return Some(text);
+    }
+    copy_selection(app)
+}
+
+/// Copy the selection through the clipboard, restoring it afterwards
+fn copy_selection(app: &AppHandle) -> Option<String> {
+    let guard = ClipboardGuard::new(app);
+    if let Err(e) = guard.write_text("") {
+        eprintln!("[Selection] {}", e);
+        return None;
+    }
+
+    let mut copied = None;
+    match simulate_copy() {
+        Ok(()) => {
+            for _ in 0..COPY_TIMEOUT_MS / COPY_POLL_MS {
+                thread::sleep(Duration::from_millis(COPY_POLL_MS));
+                if let Some(text) = guard.read_text().filter(|t| !t.is_empty()) {
+                    copied = Some(text);
+                    break;
+                }
+            }
+        }
+        Err(e) => eprintln!("[Selection] Failed to simulate copy: {}", e),
+    }
+
+    if let Err(e) = guard.restore() {
+        eprintln!("[Selection] {}", e);
+    }
+    copied.filter(|t| !t.trim().is_empty())
+}
@@ -1,5 +1,5 @@
 // TTP - Keyboard simulation
-// Simulates Cmd+V paste keystroke on macOS, Ctrl+V on Windows
+// Simulates Cmd+V / Cmd+C keystrokes on macOS (Ctrl on Windows), and Enter
 
 use std::thread;
 use std::time::Duration;
@@ -9,24 +9,36 @@ pub fn simulate_paste() -> Result<(), String> {
     // Small delay to ensure target app has focus
     thread::sleep(Duration::from_millis(100));
 
+    // Key code for 'v' on macOS
+    simulate_command_key(9, 'v')
+}
+
+/// Simulate a copy keystroke (Cmd+C on macOS, Ctrl+C on Windows)
+pub fn simulate_copy() -> Result<(), String> {
+    // Key code for 'c' on macOS
+    simulate_command_key(8, 'c')
+}
+
+/// Press a letter with Cmd (macOS) or Ctrl (Windows)
+///
+/// `mac_key` is the macOS virtual key code of `key`.
+#[allow(unused_variables)]
+fn simulate_command_key(mac_key: u16, key: char) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        use core_graphics::event::{CGEvent, CGEventFlags, CGKeyCode, CGEventTapLocation};
+        use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation};
         use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 
-        // Key code for 'v' on macOS
-        const KEY_V: CGKeyCode = 9;
-
         let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
             .map_err(|_| "Failed to create event source")?;
 
-        // Create key down event for 'v' with Command modifier
-        let key_down = CGEvent::new_keyboard_event(source.clone(), KEY_V, true)
+        // Create key down event with Command modifier
+        let key_down = CGEvent::new_keyboard_event(source.clone(), mac_key, true)
             .map_err(|_| "Failed to create key down event")?;
         key_down.set_flags(CGEventFlags::CGEventFlagCommand);
 
-        // Create key up event for 'v' with Command modifier
-        let key_up = CGEvent::new_keyboard_event(source, KEY_V, false)
+        // Create key up event with Command modifier
+        let key_up = CGEvent::new_keyboard_event(source, mac_key, false)
             .map_err(|_| "Failed to create key up event")?;
         key_up.set_flags(CGEventFlags::CGEventFlagCommand);
 
@@ -44,9 +56,45 @@ pub fn simulate_paste() -> Result<(), String> {
             .map_err(|e| format!("Failed to create Enigo: {}", e))?;
 
         enigo.key(Key::Control, Press).map_err(|e| e.to_string())?;
-        enigo.key(Key::Unicode('v'), Click).map_err(|e| e.to_string())?;
+        enigo.key(Key::Unicode(key), Click).map_err(|e| e.to_string())?;
         enigo.key(Key::Control, Release).map_err(|e| e.to_string())?;
     }
 
     Ok(())
 }
+
+/// Simulate pressing Enter (Return), e.g. to send a chat message after pasting
+pub fn simulate_enter() -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        use core_graphics::event::{CGEvent, CGKeyCode, CGEventTapLocation};
+        use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+        // Key code for Return on macOS
+        const KEY_RETURN: CGKeyCode = 36;
+
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| "Failed to create event source")?;
+
+        let key_down = CGEvent::new_keyboard_event(source.clone(), KEY_RETURN, true)
+            .map_err(|_| "Failed to create key down event")?;
+        let key_up = CGEvent::new_keyboard_event(source, KEY_RETURN, false)
+            .map_err(|_| "Failed to create key up event")?;
+
+        key_down.post(CGEventTapLocation::AnnotatedSession);
+        thread::sleep(Duration::from_millis(10));
+        key_up.post(CGEventTapLocation::AnnotatedSession);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use enigo::{Direction::Click, Enigo, Key, Keyboard, Settings};
+
+        let mut enigo = Enigo::new(&Settings::default())
+            .map_err(|e| format!("Failed to create Enigo: {}", e))?;
+
+        enigo.key(Key::Return, Click).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+// TTP - Talk To Paste
+// Per-application profiles
+//
+// A profile overrides a few settings while its application is frontmost:
+// polish on/off and style, spoken language, and how the text is delivered
+// (auto-paste, trailing newline or Enter, correction detection). The
+// frontmost app is looked up once when the pipeline starts, right after the
+// recording stops, so the profile matches the app the text is pasted into.
+
+use crate::logging::log_info;
+use crate::paste::frontmost_app;
+use crate::settings::{AfterPaste, AppProfile, Settings};
+
+/// How the pipeline delivers the final text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasteBehavior {
+    pub auto_paste: bool,
+    pub after_paste: AfterPaste,
+    pub detect_corrections: bool,
+}
+
+impl Default for PasteBehavior {
+    fn default() -> Self {
+        Self {
+            auto_paste: true,
+            after_paste: AfterPaste::Nothing,
+            detect_corrections: true,
+        }
+    }
+}
+
+/// Profile for an application name (case-insensitive)
+pub fn find_profile<'a>(profiles: &'a [AppProfile], app: &str) -> Option<&'a AppProfile> {
+    profiles
+        .iter()
+        .find(|p| p.app.trim().eq_ignore_ascii_case(app.trim()))
+}
+
+/// Apply a profile's overrides to `settings` and return its paste behavior
+pub fn apply_profile(settings: &mut Settings, profile: &AppProfile) -> PasteBehavior {
+    if let Some(enabled) = profile.polish_enabled {
+        settings.ai_polish_enabled = enabled;
+    }
+    if let Some(style) = profile.polish_style.as_ref().filter(|s| !s.is_empty()) {
+        settings.polish_style = style.clone();
+    }
+    if let Some(language) = profile.language.as_ref().filter(|l| !l.is_empty()) {
+        settings.transcription.language = Some(language.clone());
+    }
+
+    PasteBehavior {
+        auto_paste: profile.auto_paste,
+        after_paste: profile.after_paste,
+        detect_corrections: profile.detect_corrections,
+    }
+}
+
+/// Apply the profile of the frontmost application, if there is one
+///
+/// Skips the lookup entirely when no profile is configured.
+pub fn apply_frontmost_profile(settings: &mut Settings) -> PasteBehavior {
+    if settings.app_profiles.is_empty() {
+        return PasteBehavior::default();
+    }
+    let Some(app) = frontmost_app() else {
+        return PasteBehavior::default();
+    };
+    let Some(profile) = find_profile(&settings.app_profiles, &app).cloned() else {
+        return PasteBehavior::default();
+    };
+
+    log_info(&format!("Using the \"{}\" app profile", profile.app));
+    apply_profile(settings, &profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(json: &str) -> AppProfile {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn profiles_match_app_names_case_insensitively() {
+        let profiles = vec![profile(r#"{"app": "Terminal"}"#), profile(r#"{"app": " Mail "}"#)];
+        assert_eq!(find_profile(&profiles, "terminal").unwrap().app, "Terminal");
+        assert!(find_profile(&profiles, "Mail").is_some());
+        assert!(find_profile(&profiles, "Terminal Pro").is_none());
+    }
+
+    #[test]
+    fn unset_fields_keep_global_settings() {
+        let mut settings = Settings::default();
+        let terminal = profile(r#"{"app": "Terminal", "polish_enabled": false, "after_paste": "enter"}"#);
+
+        let behavior = apply_profile(&mut settings, &terminal);
+        assert!(!settings.ai_polish_enabled);
+        assert_eq!(settings.polish_style, "light");
+        assert_eq!(settings.transcription.language, None);
+        assert_eq!(behavior.after_paste, AfterPaste::Enter);
+        assert!(behavior.auto_paste && behavior.detect_corrections);
+    }
+}
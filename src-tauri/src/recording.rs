@@ -1,8 +1,46 @@
 // TTP - Talk To Paste
-// Audio recording module - manages recording paths and context
+// Audio recording module - native microphone recorder, recording paths and context
+//
+// Each recording owns a single cpal input stream on a dedicated thread.
+// Captured buffers are written to a 16-bit WAV with hound, their RMS drives
+// the pill's wave bars, and the PCM feeds streaming transcription when it
+// is enabled. When the recording stops, the file goes straight to
+// `process_recording` — the webview is not involved. If the microphone is
+// unplugged mid-recording, what was captured so far is kept and processed.
 
+use crate::audio_devices::select_input_device;
+use crate::audio_monitor::{emit_level, rms_f32, rms_i16};
+use crate::logging::{log_error, log_info, log_warn};
+use crate::settings::{get_settings, PipelineMode};
+use crate::state::{AppState, RecordingState};
+use cpal::traits::{DeviceTrait, StreamTrait};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::fs::File;
+use std::io::BufWriter;
 use std::path::PathBuf;
-use tauri::Manager;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Recordings shorter than this are treated as accidental taps
+const MIN_RECORDING_SECS: f32 = 0.3;
+
+/// How often the recorder thread writes buffered samples and emits the level (~30fps)
+const TICK: Duration = Duration::from_millis(33);
+
+/// No samples for this long means the device went away without an error
+/// (some backends just stop calling back when a USB/Bluetooth mic is unplugged)
+const DEVICE_SILENCE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Stop signal values for the recorder thread
+const RUNNING: u8 = 0;
+const FINISH: u8 = 1;
+const DISCARD: u8 = 2;
+
+/// Stop signal of the recording in progress
+static CONTROL: Mutex<Option<Arc<AtomicU8>>> = Mutex::new(None);
 
 /// Context for tracking current recording state
 pub struct RecordingContext {
@@ -15,6 +53,21 @@ impl Default for RecordingContext {
     }
 }
 
+/// What the recorder thread captured
+struct Captured {
+    duration: f32,
+    /// The input device disconnected before the recording was stopped
+    device_lost: bool,
+}
+
+/// Recording handed to the pipeline (sent to the frontend as `recording-complete`)
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingResult {
+    pub file_path: String,
+    pub duration: f32,
+}
+
 /// Get the directory where recordings are stored
 pub fn get_recording_dir(app: &tauri::AppHandle) -> PathBuf {
     app.path()
@@ -31,6 +84,274 @@ pub fn generate_recording_path(app: &tauri::AppHandle) -> PathBuf {
     dir.join(format!("recording_{}.wav", timestamp))
 }
 
+/// Start recording from the preferred input device (system default as fallback)
+///
+/// Spawns the recorder thread and returns immediately. Safe to call multiple
+/// times — subsequent calls are no-ops while a recording is running. `mode`
+/// is handed to the pipeline with the finished recording.
+pub fn start(app: &AppHandle, mode: PipelineMode) {
+    let Ok(mut control) = CONTROL.lock() else {
+        return;
+    };
+    if control.is_some() {
+        return; // Already recording
+    }
+    let signal = Arc::new(AtomicU8::new(RUNNING));
+    *control = Some(signal.clone());
+    drop(control);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let path = generate_recording_path(&app);
+        let result = record(&app, &path, &signal);
+        clear_control(&signal);
+
+        match result {
+            Ok(captured) if captured.device_lost => {
+                log_warn(&format!(
+                    "Microphone disconnected, keeping {:.1}s captured so far",
+                    captured.duration
+                ));
+                app.emit("recording-device-lost", captured.duration).ok();
+                stop_after_device_loss(&app);
+                finish_recording(&app, path, captured.duration, FINISH, mode);
+            }
+            Ok(captured) => finish_recording(&app, path, captured.duration, signal.load(Ordering::SeqCst), mode),
+            Err(e) => {
+                log_error(&format!("Recording failed: {}", e));
+                let _ = std::fs::remove_file(&path);
+                app.emit("recording-error", &e).ok();
+                return_to_idle(&app);
+            }
+        }
+    });
+}
+
+/// Stop the recording and send it to the transcription pipeline
+pub fn stop() {
+    signal_stop(FINISH);
+}
+
+/// Stop the recording and delete it
+pub fn cancel() {
+    signal_stop(DISCARD);
+}
+
+fn signal_stop(value: u8) {
+    if let Some(signal) = CONTROL.lock().ok().and_then(|mut c| c.take()) {
+        signal.store(value, Ordering::SeqCst);
+    }
+}
+
+/// Forget the stop signal if it still belongs to this recording
+fn clear_control(signal: &Arc<AtomicU8>) {
+    if let Ok(mut control) = CONTROL.lock() {
+        if control.as_ref().is_some_and(|c| Arc::ptr_eq(c, signal)) {
+            *control = None;
+        }
+    }
+}
+
+/// Recorder thread: capture until stopped or the device disappears
+fn record(app: &AppHandle, path: &PathBuf, signal: &AtomicU8) -> Result<Captured, String> {
+    let device = select_input_device(get_settings().input_device.as_deref())?;
+    let device_name = device.name().unwrap_or_else(|_| "unknown".to_string());
+    let config = device
+        .default_input_config()
+        .map_err(|e| format!("No input config: {}", e))?;
+
+    let channels = config.channels().max(1);
+    let sample_rate = config.sample_rate().0;
+    let spec = WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(path, spec)
+        .map_err(|e| format!("Failed to create recording file: {}", e))?;
+
+    // Shared RMS level (f32 stored as u32 bits for atomic access)
+    let level = Arc::new(AtomicU32::new(0f32.to_bits()));
+    let device_lost = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel::<Vec<i16>>();
+    let stream = build_stream(&device, &config, level.clone(), device_lost.clone(), sender)?;
+    stream.play().map_err(|e| format!("Failed to start microphone: {}", e))?;
+    log_info(&format!("Recording from '{}' ({}ch, {}Hz)", device_name, channels, sample_rate));
+
+    // Mono PCM for streaming transcription (dropped below = end of recording)
+    let pcm_sink = crate::transcription::streaming::start(app, sample_rate);
+    let mut frames = 0u64;
+    let mut last_data = Instant::now();
+
+    while signal.load(Ordering::SeqCst) == RUNNING {
+        let written = write_pending(&receiver, &mut writer, channels, pcm_sink.as_ref())?;
+        if written > 0 {
+            frames += written;
+            last_data = Instant::now();
+        } else if last_data.elapsed() > DEVICE_SILENCE_TIMEOUT {
+            device_lost.store(true, Ordering::SeqCst);
+        }
+        if device_lost.load(Ordering::SeqCst) {
+            break;
+        }
+        emit_level(app, f32::from_bits(level.load(Ordering::Relaxed)));
+        std::thread::sleep(TICK);
+    }
+
+    // Stream is dropped here, stopping capture; keep what was already buffered
+    drop(stream);
+    frames += write_pending(&receiver, &mut writer, channels, pcm_sink.as_ref())?;
+    drop(pcm_sink);
+    emit_level(app, 0.0);
+
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize recording: {}", e))?;
+    Ok(Captured {
+        duration: frames as f32 / sample_rate as f32,
+        device_lost: device_lost.load(Ordering::SeqCst),
+    })
+}
+
+/// Open the input stream; the callback only converts and forwards samples
+fn build_stream(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    level: Arc<AtomicU32>,
+    device_lost: Arc<AtomicBool>,
+    sender: Sender<Vec<i16>>,
+) -> Result<cpal::Stream, String> {
+    let on_error = move |e: cpal::StreamError| {
+        eprintln!("[Recorder] Stream error: {}", e);
+        if matches!(e, cpal::StreamError::DeviceNotAvailable) {
+            device_lost.store(true, Ordering::SeqCst);
+        }
+    };
+
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => device
+            .build_input_stream(
+                &config.config(),
+                move |data: &[f32], _| {
+                    level.store(rms_f32(data).to_bits(), Ordering::Relaxed);
+                    let samples = data
+                        .iter()
+                        .map(|s| (s * 32767.0).clamp(-32768.0, 32767.0) as i16)
+                        .collect();
+                    let _ = sender.send(samples);
+                },
+                on_error,
+                None,
+            )
+            .map_err(|e| format!("Failed to build F32 stream: {}", e))?,
+        cpal::SampleFormat::I16 => device
+            .build_input_stream(
+                &config.config(),
+                move |data: &[i16], _| {
+                    level.store(rms_i16(data).to_bits(), Ordering::Relaxed);
+                    let _ = sender.send(data.to_vec());
+                },
+                on_error,
+                None,
+            )
+            .map_err(|e| format!("Failed to build I16 stream: {}", e))?,
+        format => return Err(format!("Unsupported sample format: {:?}", format)),
+    };
+    Ok(stream)
+}
+
+/// Write everything captured since the last tick, returns the number of frames written
+fn write_pending(
+    receiver: &Receiver<Vec<i16>>,
+    writer: &mut WavWriter<BufWriter<File>>,
+    channels: u16,
+    pcm_sink: Option<&Sender<Vec<i16>>>,
+) -> Result<u64, String> {
+    let channels = channels as usize;
+    let mut frames = 0u64;
+    for samples in receiver.try_iter() {
+        for sample in &samples {
+            writer
+                .write_sample(*sample)
+                .map_err(|e| format!("Failed to write recording: {}", e))?;
+        }
+        frames += (samples.len() / channels) as u64;
+
+        if let Some(sink) = pcm_sink {
+            let mono = samples
+                .chunks(channels)
+                .map(|frame| (frame.iter().map(|&s| s as i32).sum::<i32>() / frame.len() as i32) as i16)
+                .collect();
+            let _ = sink.send(mono);
+        }
+    }
+    Ok(frames)
+}
+
+/// Hand a stopped recording to the pipeline, or drop it
+fn finish_recording(app: &AppHandle, path: PathBuf, duration: f32, signal: u8, mode: PipelineMode) {
+    if signal == DISCARD {
+        let _ = std::fs::remove_file(&path);
+        return;
+    }
+
+    // Skip very short recordings - likely accidental
+    if duration < MIN_RECORDING_SECS {
+        log_info(&format!("Recording too short ({:.2}s), discarded", duration));
+        let _ = std::fs::remove_file(&path);
+        return_to_idle(app);
+        return;
+    }
+
+    let file_path = path.to_string_lossy().to_string();
+    app.emit(
+        "recording-complete",
+        &RecordingResult {
+            file_path: file_path.clone(),
+            duration,
+        },
+    )
+    .ok();
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // Failures are reported to the pill and analytics by the pipeline itself
+        let _ = crate::transcription::process_recording(&app, file_path, mode).await;
+    });
+}
+
+/// Move to Processing after the microphone disappeared mid-recording
+///
+/// Mirrors what stopping with the shortcut does, so a push-to-talk release or
+/// a second hands-free press afterwards is a no-op.
+fn stop_after_device_loss(app: &AppHandle) {
+    if let Some(state) = app.try_state::<Mutex<AppState>>() {
+        if let Ok(mut guard) = state.lock() {
+            if guard.recording_state == RecordingState::Recording {
+                guard.set_state(RecordingState::Processing, app);
+                guard.hands_free_mode = get_settings().hands_free_mode;
+                crate::tray::set_recording_icon(app, false);
+            }
+        }
+    }
+}
+
+/// Reset state to Idle after a recording that won't be processed
+///
+/// Runs on the recorder thread, so it can wait for the state lock held by
+/// the shortcut handler that stopped the recording.
+fn return_to_idle(app: &AppHandle) {
+    if let Some(state) = app.try_state::<Mutex<AppState>>() {
+        if let Ok(mut guard) = state.lock() {
+            if guard.recording_state != RecordingState::Idle {
+                guard.set_state(RecordingState::Idle, app);
+                crate::tray::set_recording_icon(app, false);
+            }
+        }
+    }
+}
+
 /// Get the path to the most recent recording (for debugging/testing)
 #[tauri::command]
 pub fn get_recordings_dir(app: tauri::AppHandle) -> Result<String, String> {
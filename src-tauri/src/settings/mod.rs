@@ -3,7 +3,12 @@
 
 pub mod store;
 
-pub use store::{get_settings, reset_settings, set_settings, Settings};
+pub use store::{
+    get_settings, reset_settings, set_settings, AfterPaste, AppProfile, AudioPreprocessing,
+    EditSettings, LlmProviderKind, LlmSettings, PipelineMode, Settings, TranscriptionMode,
+    TranscriptionProviderKind, TranscriptionSettings, TranslationEngine, TranslationSettings,
+    UploadFormat,
+};
 
 use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
 use tauri::command;
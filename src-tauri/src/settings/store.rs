@@ -11,6 +11,12 @@ use tauri::{AppHandle, Emitter};
 pub struct Settings {
     /// Whether to run AI polish on transcriptions (removes filler words, fixes grammar)
     pub ai_polish_enabled: bool,
+    /// Polish style id (see `transcription::styles`)
+    #[serde(default = "default_polish_style")]
+    pub polish_style: String,
+    /// Apply spoken formatting commands ("new line", "full stop", ...) to the text
+    #[serde(default = "default_true")]
+    pub voice_commands: bool,
     /// Global keyboard shortcut for recording (e.g., "Alt+Space", "Ctrl+Shift+R")
     #[serde(default = "default_shortcut")]
     pub shortcut: String,
@@ -27,6 +33,319 @@ pub struct Settings {
     /// Hide the recording indicator pill when not recording
     #[serde(default)]
     pub hide_pill_when_inactive: bool,
+    /// Preferred microphone (device name); the system default is used when it isn't connected
+    #[serde(default)]
+    pub input_device: Option<String>,
+    /// Keep each transcription's audio with its history entry so it can be re-transcribed
+    #[serde(default)]
+    pub keep_history_audio: bool,
+    /// Speech-to-text backend (provider, endpoint and model)
+    #[serde(default)]
+    pub transcription: TranscriptionSettings,
+    /// Chat backend used for AI polish
+    #[serde(default)]
+    pub polish_llm: LlmSettings,
+    /// Chat backend used to classify dictionary corrections (small fast model is enough)
+    #[serde(default)]
+    pub classify_llm: LlmSettings,
+    /// Translation mode (second shortcut / tray item)
+    #[serde(default)]
+    pub translation: TranslationSettings,
+    /// Command mode (edit the selection by voice)
+    #[serde(default)]
+    pub edit: EditSettings,
+    /// Per-application overrides, picked by the frontmost app (see `profiles`)
+    #[serde(default)]
+    pub app_profiles: Vec<AppProfile>,
+}
+
+/// What to do after pasting
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AfterPaste {
+    /// Leave the cursor after the text
+    #[default]
+    Nothing,
+    /// Paste a trailing newline with the text
+    Newline,
+    /// Press Enter (sends chat messages, runs terminal commands)
+    Enter,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Overrides applied while a given application is frontmost
+///
+/// `None` fields keep the global setting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppProfile {
+    /// Application name as the OS reports it ("Terminal", "Mail", "Google Chrome")
+    pub app: String,
+    /// Turn AI polish on or off for this app
+    #[serde(default)]
+    pub polish_enabled: Option<bool>,
+    /// Polish style id
+    #[serde(default)]
+    pub polish_style: Option<String>,
+    /// Spoken language (ISO 639-1, "auto" = auto-detect)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Paste automatically; when off the text is only copied to the clipboard
+    #[serde(default = "default_true")]
+    pub auto_paste: bool,
+    #[serde(default)]
+    pub after_paste: AfterPaste,
+    /// Watch the field after pasting to learn dictionary corrections
+    #[serde(default = "default_true")]
+    pub detect_corrections: bool,
+}
+
+/// What the pipeline does with a recording
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineMode {
+    /// Paste what was said (polished when AI polish is on)
+    #[default]
+    Dictate,
+    /// Paste what was said, translated into `translation.target_language`
+    Translate,
+    /// Command mode: what was said is an instruction applied to the selected text
+    Edit,
+}
+
+/// How translation mode translates
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationEngine {
+    /// The polish LLM translates (and cleans up) the transcript; any target language
+    #[default]
+    Llm,
+    /// Whisper's translate task; English only, falls back to the LLM for other targets
+    Whisper,
+}
+
+fn default_target_language() -> String {
+    "en".to_string()
+}
+
+/// Translation mode settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationSettings {
+    /// Global shortcut that records in translation mode (unset = tray only)
+    #[serde(default)]
+    pub shortcut: Option<String>,
+    /// Language to paste in (ISO 639-1)
+    #[serde(default = "default_target_language")]
+    pub target_language: String,
+    #[serde(default)]
+    pub engine: TranslationEngine,
+}
+
+impl Default for TranslationSettings {
+    fn default() -> Self {
+        Self {
+            shortcut: None,
+            target_language: default_target_language(),
+            engine: TranslationEngine::default(),
+        }
+    }
+}
+
+/// Command mode settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EditSettings {
+    /// Global shortcut that records an instruction for the selected text (unset = off)
+    #[serde(default)]
+    pub shortcut: Option<String>,
+}
+
+/// Which speech-to-text API the pipeline sends audio to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionProviderKind {
+    /// Groq Whisper (default, uses the Groq API key)
+    #[default]
+    Groq,
+    /// OpenAI Whisper API
+    #[serde(rename = "openai")]
+    OpenAi,
+    /// Any server exposing `/audio/transcriptions` (faster-whisper-server, LocalAI, ...)
+    #[serde(rename = "openai_compatible")]
+    OpenAiCompatible,
+    /// Deepgram `/listen` API (or a self-hosted Deepgram-style endpoint)
+    Deepgram,
+}
+
+impl TranscriptionProviderKind {
+    /// Display name for user-facing messages
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Groq => "Groq",
+            Self::OpenAi => "OpenAI",
+            Self::OpenAiCompatible => "custom endpoint",
+            Self::Deepgram => "Deepgram",
+        }
+    }
+}
+
+/// Where speech-to-text runs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionMode {
+    /// Send audio to the configured cloud provider
+    #[default]
+    Cloud,
+    /// Run whisper.cpp on-device only (works offline)
+    Local,
+    /// Try on-device first, fall back to the cloud provider on failure
+    LocalWithCloudFallback,
+}
+
+/// Audio encoding used for cloud uploads
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadFormat {
+    /// Best format the provider accepts (Opus for Groq/OpenAI/Deepgram, WAV for custom servers)
+    #[default]
+    Auto,
+    /// Uncompressed 16-bit PCM
+    Wav,
+    /// Lossless, roughly half the size of WAV
+    Flac,
+    /// Lossy speech codec in an Ogg container, ~1/8 the size of WAV
+    Opus,
+}
+
+fn default_local_model() -> String {
+    "base".to_string()
+}
+
+/// Transcription backend settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionSettings {
+    /// Cloud, on-device, or on-device with cloud fallback
+    #[serde(default)]
+    pub mode: TranscriptionMode,
+    /// Provider to transcribe with
+    #[serde(default)]
+    pub provider: TranscriptionProviderKind,
+    /// API base URL override (e.g. "http://localhost:8000/v1"); required for OpenAI-compatible
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Model override (defaults to the provider's recommended model)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// On-device whisper.cpp model id (e.g. "base", "small", "large-v3-turbo")
+    #[serde(default = "default_local_model")]
+    pub local_model: String,
+    /// Encoding for cloud uploads (local transcription always reads WAV)
+    #[serde(default)]
+    pub upload_format: UploadFormat,
+    /// Transcribe in segments while still recording (partial text shown in the pill)
+    #[serde(default)]
+    pub streaming: bool,
+    /// Cleanup applied to the audio before the upload file is written
+    #[serde(default)]
+    pub preprocessing: AudioPreprocessing,
+    /// Spoken language as an ISO 639-1 code ("de", "es", ...); unset or "auto" detects it
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl TranscriptionSettings {
+    /// Fixed language code, or `None` to auto-detect
+    pub fn language(&self) -> Option<&str> {
+        self.language
+            .as_deref()
+            .map(str::trim)
+            .filter(|code| !code.is_empty() && !code.eq_ignore_ascii_case("auto"))
+    }
+}
+
+/// Optional audio cleanup stages (all off by default)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AudioPreprocessing {
+    /// Remove DC offset and low-frequency rumble (desk bumps, HVAC)
+    #[serde(default)]
+    pub high_pass: bool,
+    /// RNNoise-based background noise suppression (open offices, cafés)
+    #[serde(default)]
+    pub noise_suppression: bool,
+    /// Automatic gain control: bring quiet or distant speakers to a steady level
+    #[serde(default)]
+    pub auto_gain: bool,
+    /// Scale the finished file so its peak sits just below full scale
+    #[serde(default)]
+    pub normalize: bool,
+}
+
+impl AudioPreprocessing {
+    /// Whether any stage is turned on
+    pub fn any_enabled(&self) -> bool {
+        self.high_pass || self.noise_suppression || self.auto_gain || self.normalize
+    }
+}
+
+impl Default for TranscriptionSettings {
+    fn default() -> Self {
+        Self {
+            mode: TranscriptionMode::default(),
+            provider: TranscriptionProviderKind::default(),
+            base_url: None,
+            model: None,
+            local_model: default_local_model(),
+            upload_format: UploadFormat::default(),
+            streaming: false,
+            preprocessing: AudioPreprocessing::default(),
+            language: None,
+        }
+    }
+}
+
+/// Which chat API an LLM task talks to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmProviderKind {
+    /// Groq chat completions (default, uses the Groq API key)
+    #[default]
+    Groq,
+    /// OpenAI chat completions
+    #[serde(rename = "openai")]
+    OpenAi,
+    /// Any server exposing `/chat/completions` (vLLM, LocalAI, LM Studio, ...)
+    #[serde(rename = "openai_compatible")]
+    OpenAiCompatible,
+    /// Ollama native `/api/chat`
+    Ollama,
+    /// Anthropic Messages API
+    Anthropic,
+}
+
+/// Chat backend settings for one LLM task (polish or classify)
+///
+/// Unset fields fall back to the task's defaults in `llm::resolve_task`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LlmSettings {
+    #[serde(default)]
+    pub provider: LlmProviderKind,
+    /// API base URL override (e.g. "http://ollama.internal:11434")
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Total attempts per request (retries on network errors, 429 and 5xx)
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+}
+
+fn default_polish_style() -> String {
+    crate::transcription::styles::DEFAULT_STYLE.to_string()
 }
 
 fn default_shortcut() -> String {
@@ -46,6 +365,8 @@ impl Default for Settings {
     fn default() -> Self {
         Self {
             ai_polish_enabled: true,
+            polish_style: default_polish_style(),
+            voice_commands: true,
             shortcut: default_shortcut(),
             #[cfg(target_os = "macos")]
             fn_key_enabled: true,
@@ -54,6 +375,14 @@ impl Default for Settings {
             telemetry_enabled: false,
             hands_free_mode: false,
             hide_pill_when_inactive: false,
+            input_device: None,
+            keep_history_audio: false,
+            transcription: TranscriptionSettings::default(),
+            polish_llm: LlmSettings::default(),
+            classify_llm: LlmSettings::default(),
+            translation: TranslationSettings::default(),
+            edit: EditSettings::default(),
+            app_profiles: Vec::new(),
         }
     }
 }
@@ -1,7 +1,11 @@
 // TTP - Talk To Paste
 // Global keyboard shortcut handling with push-to-talk and double-tap toggle
+//
+// The main shortcut (or the Fn key) dictates; the optional translation and
+// command mode shortcuts record the same way but run the pipeline in their
+// mode.
 
-use crate::settings::{get_settings, set_settings};
+use crate::settings::{get_settings, set_settings, PipelineMode, Settings};
 use crate::sounds::{play_start_sound, play_stop_sound};
 use crate::state::{AppState, RecordingState};
 use crate::tray::{set_recording_icon, should_show_pill, show_pill, hide_pill};
@@ -13,6 +17,9 @@ use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 /// Double-tap detection threshold in milliseconds
 const DOUBLE_TAP_THRESHOLD_MS: u128 = 300;
 
+/// Modes started from their own optional shortcut
+const MODES_WITH_SHORTCUT: [PipelineMode; 2] = [PipelineMode::Translate, PipelineMode::Edit];
+
 /// Persist hands_free_mode to settings when it changes
 fn persist_hands_free_mode(app: &AppHandle, hands_free_mode: bool) {
     let mut settings = get_settings();
@@ -25,6 +32,8 @@ pub fn setup_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>
     let settings = get_settings();
     let shortcut_str = settings.shortcut;
 
+    register_mode_shortcuts(app);
+
     // "FnKey" is handled by the fnkey module, not global shortcuts
     if shortcut_str == "FnKey" {
         return Ok(());
@@ -54,11 +63,82 @@ pub fn update_shortcut(app: &AppHandle, new_shortcut: &str) -> Result<(), String
     // Use register() - handler is set in Builder
     global_shortcut.register(shortcut)
         .map_err(|e| format!("Failed to register '{}': {}", new_shortcut, e))?;
+    register_mode_shortcuts(app);
+    Ok(())
+}
+
+/// Shortcut assigned to a pipeline mode in settings, if set and valid
+///
+/// Dictation uses the main shortcut (see `setup_shortcuts`), so it has none here.
+fn mode_shortcut(settings: &Settings, mode: PipelineMode) -> Option<Shortcut> {
+    let shortcut = match mode {
+        PipelineMode::Dictate => None,
+        PipelineMode::Translate => settings.translation.shortcut.as_ref(),
+        PipelineMode::Edit => settings.edit.shortcut.as_ref(),
+    };
+    shortcut
+        .filter(|s| !s.trim().is_empty())
+        .and_then(|s| s.parse::<Shortcut>().ok())
+}
+
+/// Register the translation and command mode shortcuts from settings (after an `unregister_all`)
+pub fn register_mode_shortcuts(app: &AppHandle) {
+    let settings = get_settings();
+    for mode in MODES_WITH_SHORTCUT {
+        let Some(shortcut) = mode_shortcut(&settings, mode) else {
+            continue;
+        };
+        if let Err(e) = app.global_shortcut().register(shortcut) {
+            eprintln!("[Shortcuts] Failed to register {:?} shortcut: {}", mode, e);
+        }
+    }
+}
+
+/// Replace the shortcut of a translation or command mode at runtime (`None` turns it off)
+///
+/// Called before the new value is saved, so settings still hold the old one.
+pub fn update_mode_shortcut(app: &AppHandle, mode: PipelineMode, new_shortcut: Option<&str>) -> Result<(), String> {
+    let new_shortcut = new_shortcut.filter(|s| !s.trim().is_empty());
+    let new = match new_shortcut {
+        Some(s) => Some(s.parse::<Shortcut>().map_err(|e| format!("Invalid shortcut '{}': {}", s, e))?),
+        None => None,
+    };
+
+    let settings = get_settings();
+    if new.is_some() {
+        if settings.shortcut.parse::<Shortcut>().ok() == new {
+            return Err("This shortcut is already used for recording".to_string());
+        }
+        let taken = MODES_WITH_SHORTCUT
+            .into_iter()
+            .any(|other| other != mode && mode_shortcut(&settings, other) == new);
+        if taken {
+            return Err("This shortcut is already used by another mode".to_string());
+        }
+    }
+
+    let global_shortcut = app.global_shortcut();
+    if let Some(old) = mode_shortcut(&settings, mode) {
+        let _ = global_shortcut.unregister(old);
+    }
+    if let (Some(shortcut), Some(name)) = (new, new_shortcut) {
+        global_shortcut.register(shortcut)
+            .map_err(|e| format!("Failed to register '{}': {}", name, e))?;
+    }
     Ok(())
 }
 
+/// Pipeline mode of a pressed global shortcut
+pub fn mode_for_shortcut(shortcut: &Shortcut) -> PipelineMode {
+    let settings = get_settings();
+    MODES_WITH_SHORTCUT
+        .into_iter()
+        .find(|mode| mode_shortcut(&settings, *mode).as_ref() == Some(shortcut))
+        .unwrap_or(PipelineMode::Dictate)
+}
+
 /// Handle shortcut event - dispatches to press/release handlers (public for Builder handler)
-pub fn handle_shortcut_event_public(app: &AppHandle, shortcut_state: ShortcutState) {
+pub fn handle_shortcut_event_public(app: &AppHandle, mode: PipelineMode, shortcut_state: ShortcutState) {
     let state = app.state::<Mutex<AppState>>();
 
     let Ok(mut app_state) = state.try_lock() else {
@@ -66,7 +146,7 @@ pub fn handle_shortcut_event_public(app: &AppHandle, shortcut_state: ShortcutSta
     };
 
     match shortcut_state {
-        ShortcutState::Pressed => handle_shortcut_pressed(&mut app_state, app),
+        ShortcutState::Pressed => handle_shortcut_pressed(&mut app_state, app, mode),
         ShortcutState::Released => handle_shortcut_released(&mut app_state, app),
     }
 }
@@ -82,6 +162,7 @@ pub fn handle_fn_double_tap(app: &AppHandle) {
     match app_state.recording_state {
         RecordingState::Idle => {
             app_state.hands_free_mode = true;
+            app_state.pipeline_mode = PipelineMode::Dictate;
             start_recording(&mut app_state, app);
         }
         RecordingState::Recording if app_state.hands_free_mode => {
@@ -93,7 +174,9 @@ pub fn handle_fn_double_tap(app: &AppHandle) {
 }
 
 /// Handle shortcut key press - implements double-tap detection and settings-based toggle mode
-fn handle_shortcut_pressed(state: &mut AppState, app: &AppHandle) {
+///
+/// `mode` only matters when the press starts a recording.
+fn handle_shortcut_pressed(state: &mut AppState, app: &AppHandle, mode: PipelineMode) {
     let now = Instant::now();
     let settings = get_settings();
     let settings_hands_free = settings.hands_free_mode;
@@ -112,6 +195,7 @@ fn handle_shortcut_pressed(state: &mut AppState, app: &AppHandle) {
                 if !settings_hands_free {
                     persist_hands_free_mode(app, true);
                 }
+                state.pipeline_mode = mode;
                 start_recording(state, app);
             }
             RecordingState::Recording if state.hands_free_mode => {
@@ -127,6 +211,7 @@ fn handle_shortcut_pressed(state: &mut AppState, app: &AppHandle) {
         if state.is_idle() {
             // When settings has hands-free enabled, use toggle mode on single press
             state.hands_free_mode = settings_hands_free;
+            state.pipeline_mode = mode;
             start_recording(state, app);
         } else if state.is_recording() && state.hands_free_mode {
             // Single press while recording in hands-free mode → stop
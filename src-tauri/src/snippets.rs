@@ -0,0 +1,172 @@
+// TTP - Talk To Paste
+// Snippets: spoken triggers that paste stored text blocks
+//
+// Stored in ~/.config/ttp/snippets.json, next to the dictionary. A snippet
+// fires only when the whole utterance is its trigger ("insert my address"),
+// ignoring case and the punctuation Whisper adds; its text then replaces the
+// transcript and skips polish. Placeholders are filled at paste time:
+// {date}, {time}, {weekday} and {clipboard}.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// A spoken trigger and the text it expands to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    /// Phrase to say, as a whole utterance
+    pub trigger: String,
+    /// Text to paste (may contain placeholders)
+    pub text: String,
+    /// Unix timestamp when the snippet was created
+    pub created_at: i64,
+}
+
+/// Get the path to the snippets JSON file
+/// Location: ~/.config/ttp/snippets.json (cross-platform via dirs crate)
+fn get_snippets_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| "Could not determine config directory".to_string())?;
+
+    let ttp_dir = config_dir.join("ttp");
+    if !ttp_dir.exists() {
+        fs::create_dir_all(&ttp_dir)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    Ok(ttp_dir.join("snippets.json"))
+}
+
+/// Load all snippets; missing or unreadable files count as empty
+#[tauri::command]
+pub fn get_snippets() -> Vec<Snippet> {
+    let path = match get_snippets_path() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[Snippets] Failed to get path: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    if contents.trim().is_empty() {
+        return Vec::new();
+    }
+
+    match serde_json::from_str(&contents) {
+        Ok(snippets) => snippets,
+        Err(e) => {
+            eprintln!("[Snippets] Failed to parse JSON: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn save_snippets(snippets: &[Snippet]) -> Result<(), String> {
+    let path = get_snippets_path()?;
+    let json = serde_json::to_string_pretty(snippets)
+        .map_err(|e| format!("Failed to serialize snippets: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write snippets file: {}", e))
+}
+
+/// Tauri command: add a snippet, replacing one with the same trigger
+#[tauri::command]
+pub fn add_snippet(trigger: String, text: String) -> Result<(), String> {
+    let key = normalize(&trigger);
+    if key.is_empty() {
+        return Err("The trigger needs at least one word".to_string());
+    }
+    if text.trim().is_empty() {
+        return Err("The snippet text is empty".to_string());
+    }
+
+    let mut snippets = get_snippets();
+    let snippet = Snippet {
+        trigger: trigger.trim().to_string(),
+        text,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    match snippets.iter().position(|s| normalize(&s.trigger) == key) {
+        Some(idx) => snippets[idx] = snippet,
+        None => snippets.push(snippet),
+    }
+    save_snippets(&snippets)
+}
+
+/// Tauri command: delete a snippet by its trigger
+#[tauri::command]
+pub fn delete_snippet(trigger: String) -> Result<(), String> {
+    let key = normalize(&trigger);
+    let mut snippets = get_snippets();
+    let before = snippets.len();
+    snippets.retain(|s| normalize(&s.trigger) != key);
+    if snippets.len() == before {
+        return Err(format!("Snippet not found: {}", trigger));
+    }
+    save_snippets(&snippets)
+}
+
+/// Lowercase words without punctuation: "Insert my address." → "insert my address"
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Snippet whose trigger is the whole utterance
+pub fn match_snippet<'a>(utterance: &str, snippets: &'a [Snippet]) -> Option<&'a Snippet> {
+    let spoken = normalize(utterance);
+    if spoken.is_empty() {
+        return None;
+    }
+    snippets.iter().find(|s| normalize(&s.trigger) == spoken)
+}
+
+/// Fill the placeholders of a snippet's text
+///
+/// Unknown placeholders are left as they are; `{clipboard}` becomes empty
+/// when the clipboard holds no text.
+pub fn expand_snippet(text: &str, now: DateTime<Local>, clipboard: Option<&str>) -> String {
+    text.replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H:%M").to_string())
+        .replace("{weekday}", &now.format("%A").to_string())
+        .replace("{clipboard}", clipboard.unwrap_or(""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn snippet(trigger: &str, text: &str) -> Snippet {
+        Snippet {
+            trigger: trigger.to_string(),
+            text: text.to_string(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn triggers_match_whole_utterances_only() {
+        let snippets = vec![snippet("Insert my address", "1 Main St"), snippet("signature", "Sam")];
+        assert_eq!(match_snippet("Insert my address.", &snippets).unwrap().text, "1 Main St");
+        assert_eq!(match_snippet(" Signature! ", &snippets).unwrap().text, "Sam");
+        assert!(match_snippet("Please insert my address", &snippets).is_none());
+        assert!(match_snippet("...", &snippets).is_none());
+    }
+
+    #[test]
+    fn placeholders_are_filled() {
+        let now = Local.with_ymd_and_hms(2026, 3, 2, 9, 5, 0).unwrap();
+        assert_eq!(
+            expand_snippet("Standup {date} ({weekday}) {time}: {clipboard} {unknown}", now, Some("PR #12")),
+            "Standup 2026-03-02 (Monday) 09:05: PR #12 {unknown}"
+        );
+        assert_eq!(expand_snippet("Link: {clipboard}", now, None), "Link: ");
+    }
+}
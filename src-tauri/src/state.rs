@@ -1,6 +1,7 @@
 // TTP - Talk To Paste
 // Application state management
 
+use crate::settings::PipelineMode;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use tauri::{AppHandle, Emitter};
@@ -16,6 +17,8 @@ pub struct AppState {
     pub recording_state: RecordingState,
     pub hands_free_mode: bool,
     pub last_shortcut_time: Option<Instant>,
+    /// What the next (or current) recording is for; set before starting it
+    pub pipeline_mode: PipelineMode,
 }
 
 impl Default for AppState {
@@ -24,6 +27,7 @@ impl Default for AppState {
             recording_state: RecordingState::Idle,
             hands_free_mode: false,
             last_shortcut_time: None,
+            pipeline_mode: PipelineMode::Dictate,
         }
     }
 }
@@ -33,10 +37,15 @@ impl AppState {
         let old_state = self.recording_state.clone();
         self.recording_state = state.clone();
 
-        // Start/stop audio level monitoring for pill wave visualization
+        // Start/stop the microphone recorder (also drives the pill wave visualization)
         match &state {
-            RecordingState::Recording => crate::audio_monitor::start(app.clone()),
-            _ => crate::audio_monitor::stop(),
+            RecordingState::Recording => crate::recording::start(app, self.pipeline_mode),
+            RecordingState::Processing => crate::recording::stop(),
+            RecordingState::Idle => {
+                // Recording discarded (too short, cancelled) — nothing will process it
+                crate::recording::cancel();
+                crate::transcription::streaming::cancel();
+            }
         }
 
         // Handle pill visibility when transitioning to Idle
@@ -82,3 +82,34 @@ pub fn scrub_breadcrumb_pii(breadcrumb: &mut sentry::protocol::Breadcrumb) {
     }
     breadcrumb.data.retain(|k, _| !is_sensitive_key(k));
 }
+
+/// Record an API request attempt as a Sentry breadcrumb (registered as an `llm` trace hook).
+///
+/// Only the host is kept from the URL; error bodies go through the same scrubbing.
+pub fn http_breadcrumb(event: &crate::llm::TraceEvent) {
+    let host = reqwest::Url::parse(&event.url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_default();
+
+    let mut data = sentry::protocol::Map::new();
+    data.insert("host".into(), host.into());
+    data.insert("attempt".into(), event.attempt.into());
+    data.insert("elapsed_ms".into(), (event.elapsed.as_millis() as u64).into());
+    if let Some(status) = event.status {
+        data.insert("status_code".into(), status.into());
+    }
+
+    sentry::add_breadcrumb(sentry::Breadcrumb {
+        ty: "http".into(),
+        category: Some(format!("api.{}", event.label.to_lowercase())),
+        message: event.error.as_deref().map(scrub_string),
+        level: if event.error.is_some() {
+            sentry::Level::Warning
+        } else {
+            sentry::Level::Info
+        },
+        data,
+        ..Default::default()
+    });
+}
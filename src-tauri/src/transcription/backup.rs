@@ -3,12 +3,14 @@
 //
 // Provides backup-before-transcribe, stale backup cleanup, and WAV
 // header validation. These functions are wired into the pipeline by
-// pipeline.rs and into app startup by lib.rs.
+// pipeline.rs and into app startup by lib.rs. Backups of failed
+// transcriptions are handed to the retry queue in retry.rs.
 
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use hound::WavReader;
+use super::error::PipelineError;
 use tauri::{AppHandle, Manager};
 
 /// Maximum age for backup files before they are cleaned up (24 hours).
@@ -28,19 +30,19 @@ pub fn backup_dir(app: &AppHandle) -> PathBuf {
 ///
 /// Creates the backup directory if it does not exist. The backup filename
 /// matches the source filename. Returns the full path to the backup file.
-pub fn backup_audio(app: &AppHandle, audio_path: &str) -> Result<PathBuf, String> {
+pub fn backup_audio(app: &AppHandle, audio_path: &str) -> Result<PathBuf, PipelineError> {
     let dir = backup_dir(app);
     std::fs::create_dir_all(&dir)
-        .map_err(|e| format!("Failed to create backup dir: {}", e))?;
+        .map_err(|e| PipelineError::Io(format!("Failed to create backup dir: {}", e)))?;
 
     let source = Path::new(audio_path);
     let filename = source
         .file_name()
-        .ok_or_else(|| "Invalid audio path: no filename".to_string())?;
+        .ok_or_else(|| PipelineError::Io("Invalid audio path: no filename".to_string()))?;
     let backup_path = dir.join(filename);
 
     std::fs::copy(audio_path, &backup_path)
-        .map_err(|e| format!("Failed to backup audio: {}", e))?;
+        .map_err(|e| PipelineError::Io(format!("Failed to backup audio: {}", e)))?;
 
     sentry::add_breadcrumb(sentry::Breadcrumb {
         message: Some("Audio backed up".into()),
@@ -98,9 +100,10 @@ pub fn remove_backup(backup_path: &Path) {
 
 /// Delete backup files older than 24 hours.
 ///
-/// Called once during app startup in `setup()`. Logs the count of cleaned
-/// files but never fails or panics -- if the backup directory does not
-/// exist, returns silently.
+/// Called once during app startup in `setup()`. Recordings waiting in the
+/// retry queue (and the queue file itself) are kept regardless of age. Logs
+/// the count of cleaned files but never fails or panics -- if the backup
+/// directory does not exist, returns silently.
 pub fn cleanup_stale_backups(app: &AppHandle) {
     let dir = backup_dir(app);
     let Ok(entries) = std::fs::read_dir(&dir) else {
@@ -109,8 +112,13 @@ pub fn cleanup_stale_backups(app: &AppHandle) {
 
     let now = SystemTime::now();
     let mut cleaned = 0u32;
+    let queued = super::retry::queued_files(app);
 
     for entry in entries.flatten() {
+        let path = entry.path();
+        if queued.contains(&path) || path.extension().is_none_or(|ext| ext != "wav") {
+            continue;
+        }
         let Ok(metadata) = entry.metadata() else {
             continue;
         };
@@ -122,7 +130,7 @@ pub fn cleanup_stale_backups(app: &AppHandle) {
         };
 
         if age > BACKUP_MAX_AGE {
-            if std::fs::remove_file(entry.path()).is_ok() {
+            if std::fs::remove_file(&path).is_ok() {
                 cleaned += 1;
             }
         }
@@ -140,31 +148,31 @@ pub fn cleanup_stale_backups(app: &AppHandle) {
 ///
 /// Uses `hound::WavReader::open()` to parse the RIFF/WAVE header and fmt
 /// chunk. Returns `Ok(())` if the header is valid with reasonable channel
-/// count and sample rate. Returns `Err` with a user-facing error message
-/// if the file is corrupt, unreadable, or unsupported.
-pub fn validate_wav(path: &str) -> Result<(), String> {
+/// count and sample rate. Returns `PipelineError::CorruptAudio` with a
+/// user-facing message if the file is corrupt, unreadable, or unsupported.
+pub fn validate_wav(path: &str) -> Result<(), PipelineError> {
     match WavReader::open(path) {
         Ok(reader) => {
             let spec = reader.spec();
             if spec.channels == 0 {
-                return Err("Corrupt audio: no channels".to_string());
+                return Err(PipelineError::CorruptAudio("Corrupt audio: no channels".to_string()));
             }
             if spec.sample_rate == 0 {
-                return Err("Corrupt audio: invalid sample rate".to_string());
+                return Err(PipelineError::CorruptAudio("Corrupt audio: invalid sample rate".to_string()));
             }
             Ok(())
         }
         Err(hound::Error::FormatError(msg)) => {
-            Err(format!("Corrupt audio file: {}", msg))
+            Err(PipelineError::CorruptAudio(format!("Corrupt audio file: {}", msg)))
         }
         Err(hound::Error::IoError(e)) => {
-            Err(format!("Cannot read audio file: {}", e))
+            Err(PipelineError::CorruptAudio(format!("Cannot read audio file: {}", e)))
         }
         Err(hound::Error::Unsupported) => {
-            Err("Unsupported audio format".to_string())
+            Err(PipelineError::CorruptAudio("Unsupported audio format".to_string()))
         }
         Err(e) => {
-            Err(format!("Invalid audio file: {}", e))
+            Err(PipelineError::CorruptAudio(format!("Invalid audio file: {}", e)))
         }
     }
 }
@@ -0,0 +1,346 @@
+// TTP - Talk To Paste
+// Chunked transcription for recordings over the upload limit
+//
+// Long recordings are cut at the quietest point (same RMS measure as the
+// pill's level meter in `audio_monitor`) near each size boundary, with a
+// short overlap so no word is lost at a cut. Chunks are transcribed in
+// parallel and the texts are stitched back together, dropping the words
+// the overlap made both chunks hear.
+
+use super::error::PipelineError;
+use super::provider::TranscriptionTask;
+use super::transcribe_audio;
+use crate::audio_monitor::rms_i16;
+use crate::settings::TranscriptionSettings;
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Size budget per chunk (headroom under the 25MB API limit)
+const MAX_CHUNK_BYTES: u64 = 20_000_000;
+
+/// Give up beyond this many chunks (~2 hours of mono 16kHz audio)
+const MAX_CHUNKS: usize = 12;
+
+/// Audio repeated at the start of each chunk from the end of the previous one
+const OVERLAP_SECS: f32 = 2.0;
+
+/// How far before the hard size boundary to look for a pause
+const SILENCE_SEARCH_SECS: f32 = 30.0;
+
+/// RMS analysis frame length
+const FRAME_MS: u32 = 30;
+
+/// Concurrent uploads (keeps us clear of provider rate limits)
+const MAX_PARALLEL: usize = 3;
+
+/// Longest run of words considered when de-duplicating an overlap
+const MAX_OVERLAP_WORDS: usize = 20;
+
+/// Shorter matches are too likely to be coincidence ("and the")
+const MIN_OVERLAP_WORDS: usize = 2;
+
+/// Words at the start of a chunk that may be a cut-off fragment
+const MAX_LEADING_SKIP: usize = 2;
+
+/// One piece of a split recording
+#[derive(Debug, Clone)]
+pub struct AudioChunk {
+    pub path: PathBuf,
+    /// Offset of the chunk start in the original recording
+    pub start_secs: f32,
+}
+
+/// Open a WAV for reading
+pub(super) fn open_wav(path: &str) -> Result<WavReader<BufReader<File>>, PipelineError> {
+    WavReader::open(path).map_err(|e| PipelineError::CorruptAudio(format!("Failed to read WAV: {}", e)))
+}
+
+/// Stream any PCM WAV as interleaved i16 samples
+///
+/// Integer samples are scaled by their bit depth (8-bit shifted up, 24/32-bit
+/// shifted down), so levels match `convert::normalized_samples`. Unreadable
+/// samples become silence.
+pub(super) fn samples_i16(reader: WavReader<BufReader<File>>) -> Box<dyn Iterator<Item = i16>> {
+    let spec = reader.spec();
+    match spec.sample_format {
+        SampleFormat::Float => Box::new(
+            reader
+                .into_samples::<f32>()
+                .map(|s| (s.unwrap_or(0.0) * 32767.0).clamp(-32768.0, 32767.0) as i16),
+        ),
+        SampleFormat::Int => {
+            let bits = spec.bits_per_sample.clamp(1, 32) as u32;
+            Box::new(reader.into_samples::<i32>().map(move |s| {
+                let value = s.unwrap_or(0);
+                if bits <= 16 {
+                    (value << (16 - bits)) as i16
+                } else {
+                    (value >> (bits - 16)) as i16
+                }
+            }))
+        }
+    }
+}
+
+/// Read any PCM WAV as interleaved i16 samples
+pub(super) fn read_samples_i16(path: &str) -> Result<(WavSpec, Vec<i16>), PipelineError> {
+    let reader = open_wav(path)?;
+    let spec = reader.spec();
+    Ok((spec, samples_i16(reader).collect()))
+}
+
+/// Pick cut points (in frames) so every chunk plus its overlap fits the size budget
+fn find_cut_points(samples: &[i16], channels: usize, sample_rate: u32, max_chunk_frames: usize) -> Vec<usize> {
+    let total_frames = samples.len() / channels;
+    let overlap_frames = (OVERLAP_SECS * sample_rate as f32) as usize;
+    let search_frames = (SILENCE_SEARCH_SECS * sample_rate as f32) as usize;
+    let rms_frame = (sample_rate * FRAME_MS / 1000).max(1) as usize;
+
+    // Room for new audio in each chunk once the overlap is prepended
+    let step = max_chunk_frames.saturating_sub(overlap_frames).max(rms_frame);
+
+    let mut cuts = Vec::new();
+    let mut start = 0usize;
+    while total_frames - start > step {
+        let hard_end = start + step;
+        let search_start = hard_end.saturating_sub(search_frames).max(start + (step / 2).max(1));
+
+        // Quietest analysis frame in the search window
+        let mut best = hard_end;
+        let mut best_rms = f32::MAX;
+        let mut pos = search_start;
+        while pos + rms_frame <= hard_end {
+            let rms = rms_i16(&samples[pos * channels..(pos + rms_frame) * channels]);
+            if rms < best_rms {
+                best_rms = rms;
+                best = pos + rms_frame / 2;
+            }
+            pos += rms_frame;
+        }
+
+        cuts.push(best);
+        start = best;
+    }
+    cuts
+}
+
+/// Split a WAV into overlapping chunks that each fit the upload limit
+///
+/// Chunks are written next to the input (`<name>.chunkN.wav`); the caller
+/// removes them with `remove_chunks`. Returns `AudioTooLarge` when the
+/// recording would need more than `MAX_CHUNKS` pieces.
+pub fn split_on_silence(path: &str) -> Result<Vec<AudioChunk>, PipelineError> {
+    let (spec, samples) = read_samples_i16(path)?;
+    let channels = spec.channels.max(1) as usize;
+    let sample_rate = spec.sample_rate.max(1);
+
+    // 44-byte header, 16-bit output samples
+    let max_chunk_frames = ((MAX_CHUNK_BYTES - 44) / (2 * channels as u64)) as usize;
+    let cuts = find_cut_points(&samples, channels, sample_rate, max_chunk_frames);
+
+    if cuts.len() + 1 > MAX_CHUNKS {
+        let size_mb = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0) as f64 / 1_000_000.0;
+        return Err(PipelineError::AudioTooLarge { size_mb });
+    }
+
+    let out_spec = WavSpec {
+        channels: channels as u16,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let total_frames = samples.len() / channels;
+    let overlap_frames = (OVERLAP_SECS * sample_rate as f32) as usize;
+
+    let mut bounds = vec![0usize];
+    bounds.extend(&cuts);
+    bounds.push(total_frames);
+
+    let mut chunks = Vec::with_capacity(bounds.len() - 1);
+    for (index, window) in bounds.windows(2).enumerate() {
+        let start = if index == 0 { 0 } else { window[0].saturating_sub(overlap_frames) };
+        let end = window[1];
+
+        let chunk_path = Path::new(path).with_extension(format!("chunk{}.wav", index));
+        let mut writer = WavWriter::create(&chunk_path, out_spec)
+            .map_err(|e| PipelineError::Io(format!("Failed to create chunk WAV: {}", e)))?;
+        for sample in &samples[start * channels..end * channels] {
+            writer
+                .write_sample(*sample)
+                .map_err(|e| PipelineError::Io(format!("Failed to write chunk sample: {}", e)))?;
+        }
+        writer
+            .finalize()
+            .map_err(|e| PipelineError::Io(format!("Failed to finalize chunk WAV: {}", e)))?;
+
+        chunks.push(AudioChunk {
+            path: chunk_path,
+            start_secs: start as f32 / sample_rate as f32,
+        });
+    }
+
+    Ok(chunks)
+}
+
+/// Delete chunk files written by `split_on_silence`
+pub fn remove_chunks(chunks: &[AudioChunk]) {
+    for chunk in chunks {
+        let _ = std::fs::remove_file(&chunk.path);
+    }
+}
+
+/// Transcribe chunks in parallel and stitch the results in order
+///
+/// Each chunk goes through `transcribe_audio` (and its retries); the first
+/// chunk that still fails fails the whole recording.
+pub async fn transcribe_chunks(
+    settings: &TranscriptionSettings,
+    api_key: Option<&str>,
+    chunks: &[AudioChunk],
+    prompt: Option<&str>,
+    task: TranscriptionTask,
+) -> Result<String, PipelineError> {
+    let semaphore = Arc::new(Semaphore::new(MAX_PARALLEL));
+    let mut tasks = JoinSet::new();
+
+    for (index, chunk) in chunks.iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let settings = settings.clone();
+        let api_key = api_key.map(|k| k.to_string());
+        let prompt = prompt.map(|p| p.to_string());
+        let path = chunk.path.to_string_lossy().to_string();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = transcribe_audio(&settings, api_key.as_deref(), &path, prompt.as_deref(), task).await;
+            (index, result)
+        });
+    }
+
+    let mut parts = vec![String::new(); chunks.len()];
+    while let Some(joined) = tasks.join_next().await {
+        let (index, result) =
+            joined.map_err(|e| PipelineError::Io(format!("Chunk transcription task failed: {}", e)))?;
+        // Returning drops the JoinSet, which aborts the remaining uploads
+        parts[index] = result?;
+    }
+
+    Ok(stitch_transcripts(&parts))
+}
+
+/// Lowercase alphanumeric form of a word for overlap comparison
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Number of leading words of `next` that repeat the end of `prev`
+fn overlap_len(prev: &[&str], next: &[&str]) -> usize {
+    let max = MAX_OVERLAP_WORDS.min(prev.len()).min(next.len());
+    for k in (MIN_OVERLAP_WORDS..=max).rev() {
+        let tail: Vec<String> = prev[prev.len() - k..].iter().map(|w| normalize_word(w)).collect();
+        // The chunk may open on a fragment of a word cut by the overlap start
+        for offset in 0..=MAX_LEADING_SKIP {
+            if offset + k > next.len() {
+                break;
+            }
+            let matches = next[offset..offset + k]
+                .iter()
+                .zip(&tail)
+                .all(|(word, expected)| normalize_word(word) == *expected);
+            if matches {
+                return offset + k;
+            }
+        }
+    }
+    0
+}
+
+/// Join chunk transcripts, dropping words repeated by the overlap
+pub fn stitch_transcripts(parts: &[String]) -> String {
+    let mut words: Vec<&str> = Vec::new();
+    for part in parts {
+        let next: Vec<&str> = part.split_whitespace().collect();
+        let skip = overlap_len(&words, &next);
+        words.extend_from_slice(&next[skip..]);
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stitch_drops_repeated_overlap() {
+        let parts = vec![
+            "We should ship the release on Friday.".to_string(),
+            "on Friday. Then we start the next sprint.".to_string(),
+        ];
+        assert_eq!(
+            stitch_transcripts(&parts),
+            "We should ship the release on Friday. Then we start the next sprint."
+        );
+    }
+
+    #[test]
+    fn stitch_skips_cut_off_leading_fragment() {
+        let parts = vec![
+            "the quarterly numbers look good".to_string(),
+            "ood numbers look good overall".to_string(),
+        ];
+        assert_eq!(
+            stitch_transcripts(&parts),
+            "the quarterly numbers look good overall"
+        );
+    }
+
+    #[test]
+    fn stitch_keeps_text_without_overlap() {
+        let parts = vec!["first part".to_string(), "second part".to_string()];
+        assert_eq!(stitch_transcripts(&parts), "first part second part");
+    }
+
+    #[test]
+    fn cuts_land_in_silence() {
+        // 1s loud, 0.1s silence, 1s loud at 1kHz mono; budget forces one cut
+        let rate = 1000;
+        let mut samples = vec![8000i16; rate as usize];
+        samples.extend(vec![0i16; 100]);
+        samples.extend(vec![8000i16; rate as usize]);
+
+        let cuts = find_cut_points(&samples, 1, rate, 1500 + (OVERLAP_SECS * rate as f32) as usize);
+        assert_eq!(cuts.len(), 1);
+        assert!((1000..1100).contains(&cuts[0]), "cut at {}", cuts[0]);
+    }
+
+    #[test]
+    fn samples_are_scaled_by_bit_depth() {
+        for (bits, peak) in [(8u16, 100i32), (16, 25_600), (24, 6_553_600)] {
+            let path = std::env::temp_dir().join(format!("ttp_chunking_{}_{}bit.wav", std::process::id(), bits));
+            let path = path.to_string_lossy().to_string();
+            let spec = WavSpec {
+                channels: 1,
+                sample_rate: 16_000,
+                bits_per_sample: bits,
+                sample_format: SampleFormat::Int,
+            };
+            let mut writer = WavWriter::create(&path, spec).unwrap();
+            for sample in [peak, -peak, 0] {
+                writer.write_sample(sample).unwrap();
+            }
+            writer.finalize().unwrap();
+
+            let (_, samples) = read_samples_i16(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+            assert_eq!(samples, vec![25_600, -25_600, 0], "{}-bit", bits);
+        }
+    }
+}
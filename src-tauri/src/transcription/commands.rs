@@ -0,0 +1,340 @@
+// TTP - Talk To Paste
+// Spoken formatting commands ("new line", "full stop", "à la ligne", ...)
+//
+// A deterministic pass over the final text, run before the dictionary is
+// applied and whether or not AI polish is on. Commands are whole spoken
+// phrases; punctuation Whisper (or the polish LLM) attached to them is
+// dropped, so "Hello, full stop, world." becomes "Hello. World." Text without
+// any command is returned untouched, whitespace included. Words that are
+// also everyday words ("period", "colon", "point") are not commands: the pass
+// runs on every dictation by default and must not rewrite ordinary sentences.
+
+/// What a spoken command does
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    /// Punctuation mark attached to the previous word (French typography
+    /// puts a space before `? ! : ;`)
+    Punct { mark: &'static str, space_before: bool },
+    NewLine,
+    NewParagraph,
+    /// Start a "- " item on its own line
+    Bullet,
+    OpenQuote(&'static str),
+    CloseQuote(&'static str),
+    /// Remove everything back to the end of the previous sentence
+    DeleteSentence,
+    CapsOn,
+    CapsOff,
+}
+
+/// A command phrase (lowercase words) in one language
+struct Command {
+    language: &'static str,
+    phrase: &'static str,
+    action: Action,
+}
+
+const fn punct(mark: &'static str) -> Action {
+    Action::Punct { mark, space_before: false }
+}
+
+const fn punct_fr(mark: &'static str) -> Action {
+    Action::Punct { mark, space_before: true }
+}
+
+const COMMANDS: &[Command] = &[
+    // English (bare "comma", "period", "colon", "semicolon", "unquote" and
+    // "newline" are left alone: "grace period", "a colon in the URL")
+    Command { language: "en", phrase: "new line", action: Action::NewLine },
+    Command { language: "en", phrase: "new paragraph", action: Action::NewParagraph },
+    Command { language: "en", phrase: "bullet point", action: Action::Bullet },
+    Command { language: "en", phrase: "insert comma", action: punct(",") },
+    Command { language: "en", phrase: "full stop", action: punct(".") },
+    Command { language: "en", phrase: "question mark", action: punct("?") },
+    Command { language: "en", phrase: "exclamation mark", action: punct("!") },
+    Command { language: "en", phrase: "exclamation point", action: punct("!") },
+    Command { language: "en", phrase: "insert colon", action: punct(":") },
+    Command { language: "en", phrase: "insert semicolon", action: punct(";") },
+    Command { language: "en", phrase: "open quote", action: Action::OpenQuote("\"") },
+    Command { language: "en", phrase: "close quote", action: Action::CloseQuote("\"") },
+    Command { language: "en", phrase: "end quote", action: Action::CloseQuote("\"") },
+    Command { language: "en", phrase: "delete last sentence", action: Action::DeleteSentence },
+    Command { language: "en", phrase: "scratch that", action: Action::DeleteSentence },
+    Command { language: "en", phrase: "all caps", action: Action::CapsOn },
+    Command { language: "en", phrase: "end caps", action: Action::CapsOff },
+    // French (bare "point", "virgule" and "deux points" are left alone:
+    // "un point important", "trois virgule cinq", "deux points communs")
+    Command { language: "fr", phrase: "à la ligne", action: Action::NewLine },
+    Command { language: "fr", phrase: "retour à la ligne", action: Action::NewLine },
+    Command { language: "fr", phrase: "nouvelle ligne", action: Action::NewLine },
+    Command { language: "fr", phrase: "nouveau paragraphe", action: Action::NewParagraph },
+    Command { language: "fr", phrase: "nouvelle puce", action: Action::Bullet },
+    Command { language: "fr", phrase: "insère une virgule", action: punct(",") },
+    Command { language: "fr", phrase: "point final", action: punct(".") },
+    Command { language: "fr", phrase: "point d'interrogation", action: punct_fr("?") },
+    Command { language: "fr", phrase: "point d'exclamation", action: punct_fr("!") },
+    Command { language: "fr", phrase: "insère deux points", action: punct_fr(":") },
+    Command { language: "fr", phrase: "insère deux-points", action: punct_fr(":") },
+    Command { language: "fr", phrase: "insère un point-virgule", action: punct_fr(";") },
+    Command { language: "fr", phrase: "insère un point virgule", action: punct_fr(";") },
+    Command { language: "fr", phrase: "ouvrez les guillemets", action: Action::OpenQuote("« ") },
+    Command { language: "fr", phrase: "ouvrir les guillemets", action: Action::OpenQuote("« ") },
+    Command { language: "fr", phrase: "fermez les guillemets", action: Action::CloseQuote(" »") },
+    Command { language: "fr", phrase: "fermer les guillemets", action: Action::CloseQuote(" »") },
+    Command { language: "fr", phrase: "supprime la dernière phrase", action: Action::DeleteSentence },
+    Command { language: "fr", phrase: "efface la dernière phrase", action: Action::DeleteSentence },
+    Command { language: "fr", phrase: "tout en majuscules", action: Action::CapsOn },
+    Command { language: "fr", phrase: "fin des majuscules", action: Action::CapsOff },
+];
+
+/// Punctuation transcribers attach to words, ignored when matching commands
+const ATTACHED_PUNCT: &[char] = &['.', ',', ';', ':', '!', '?', '"', '«', '»', '…'];
+
+/// Lowercase a token and strip attached punctuation, for matching
+fn normalize(token: &str) -> String {
+    token
+        .trim_matches(|c: char| ATTACHED_PUNCT.contains(&c))
+        .replace('’', "'")
+        .to_lowercase()
+}
+
+/// Commands for a language setting: a fixed language with commands uses
+/// only its own, auto-detect (or any other language) uses all of them
+fn active_commands(language: Option<&str>) -> Vec<&'static Command> {
+    let fixed = language.filter(|l| COMMANDS.iter().any(|c| c.language.eq_ignore_ascii_case(l)));
+    let mut commands: Vec<&Command> = COMMANDS
+        .iter()
+        .filter(|c| fixed.is_none_or(|l| c.language.eq_ignore_ascii_case(l)))
+        .collect();
+    // Longest phrase first, so "retour à la ligne" wins over "à la ligne"
+    commands.sort_by_key(|c| std::cmp::Reverse(c.phrase.split(' ').count()));
+    commands
+}
+
+/// The command starting at `words[i]`, with the number of words it spans
+fn match_command(commands: &[&Command], words: &[String], i: usize) -> Option<(Action, usize)> {
+    commands.iter().find_map(|command| {
+        let phrase: Vec<&str> = command.phrase.split(' ').collect();
+        let end = i + phrase.len();
+        (end <= words.len() && words[i..end].iter().zip(&phrase).all(|(w, p)| w == p))
+            .then_some((command.action, phrase.len()))
+    })
+}
+
+/// Split text into (leading whitespace, token) pairs
+fn tokenize(text: &str) -> Vec<(&str, &str)> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let word_start = rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len());
+        if word_start == rest.len() {
+            break;
+        }
+        let word_len = rest[word_start..]
+            .find(char::is_whitespace)
+            .unwrap_or(rest.len() - word_start);
+        tokens.push((&rest[..word_start], &rest[word_start..word_start + word_len]));
+        rest = &rest[word_start + word_len..];
+    }
+    tokens
+}
+
+/// Uppercase the first letter of a word
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Drop trailing spaces and punctuation the transcriber put before a command
+fn trim_trailing_punct(out: &mut String) {
+    let keep = out
+        .trim_end_matches(|c: char| c == ' ' || (ATTACHED_PUNCT.contains(&c) && c != '"' && c != '»'))
+        .len();
+    out.truncate(keep);
+}
+
+/// Drop trailing spaces (not newlines)
+fn trim_trailing_spaces(out: &mut String) {
+    let keep = out.trim_end_matches(' ').len();
+    out.truncate(keep);
+}
+
+/// Start a new line unless the output is empty or already on one
+fn break_line(out: &mut String) {
+    trim_trailing_spaces(out);
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// Remove the last sentence (or line) of the output
+fn delete_last_sentence(out: &mut String) {
+    let body = out.trim_end().trim_end_matches(['.', '!', '?']);
+    let cut = body.rfind(['.', '!', '?', '\n']).map_or(0, |i| i + 1);
+    out.truncate(cut);
+}
+
+/// Apply spoken formatting commands to a transcript
+///
+/// `language` is the spoken language setting (`None` = auto-detect, which
+/// recognizes every language's commands).
+pub fn apply_voice_commands(text: &str, language: Option<&str>) -> String {
+    let tokens = tokenize(text);
+    let words: Vec<String> = tokens.iter().map(|(_, token)| normalize(token)).collect();
+    let commands = active_commands(language);
+
+    let mut out = String::with_capacity(text.len());
+    let mut matched = false;
+    let mut caps = false;
+    let mut capitalize_next = false;
+    let mut glue_next = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if let Some((action, len)) = match_command(&commands, &words, i) {
+            matched = true;
+            i += len;
+            match action {
+                Action::Punct { mark, space_before } => {
+                    trim_trailing_punct(&mut out);
+                    if space_before && !out.is_empty() {
+                        out.push(' ');
+                    }
+                    out.push_str(mark);
+                    capitalize_next = matches!(mark, "." | "?" | "!");
+                    glue_next = false;
+                }
+                Action::NewLine => {
+                    break_line(&mut out);
+                    capitalize_next = true;
+                    glue_next = true;
+                }
+                Action::NewParagraph => {
+                    break_line(&mut out);
+                    if !out.is_empty() && !out.ends_with("\n\n") {
+                        out.push('\n');
+                    }
+                    capitalize_next = true;
+                    glue_next = true;
+                }
+                Action::Bullet => {
+                    break_line(&mut out);
+                    out.push_str("- ");
+                    capitalize_next = true;
+                    glue_next = true;
+                }
+                Action::OpenQuote(mark) => {
+                    if !out.is_empty() && !out.ends_with([' ', '\n']) && !glue_next {
+                        out.push(' ');
+                    }
+                    out.push_str(mark);
+                    glue_next = true;
+                }
+                Action::CloseQuote(mark) => {
+                    trim_trailing_spaces(&mut out);
+                    out.push_str(mark);
+                    glue_next = false;
+                }
+                Action::DeleteSentence => {
+                    delete_last_sentence(&mut out);
+                    glue_next = false;
+                }
+                Action::CapsOn => caps = true,
+                Action::CapsOff => caps = false,
+            }
+            continue;
+        }
+
+        let (space, token) = tokens[i];
+        if !out.is_empty() && !glue_next {
+            // Keep the original spacing (the LLM's line breaks), at least one space
+            out.push_str(if space.is_empty() { " " } else { space });
+        }
+        let word = if caps {
+            token.to_uppercase()
+        } else if capitalize_next {
+            capitalize(token)
+        } else {
+            token.to_string()
+        };
+        out.push_str(&word);
+        capitalize_next = false;
+        glue_next = false;
+        i += 1;
+    }
+
+    if matched {
+        out.trim_end().to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_without_commands_is_untouched() {
+        let text = "Shopping list:\n\n1. Milk\n2. Eggs  ";
+        assert_eq!(apply_voice_commands(text, None), text);
+    }
+
+    #[test]
+    fn punctuation_replaces_transcriber_punctuation() {
+        assert_eq!(apply_voice_commands("Hello insert comma, world full stop", Some("en")), "Hello, world.");
+        assert_eq!(apply_voice_commands("is it done question mark yes.", Some("en")), "is it done? Yes.");
+        assert_eq!(apply_voice_commands("Vraiment point d'exclamation", Some("fr")), "Vraiment !");
+        assert_eq!(
+            apply_voice_commands("Bonjour insère une virgule voici la liste insère deux points", Some("fr")),
+            "Bonjour, voici la liste :"
+        );
+    }
+
+    #[test]
+    fn lines_paragraphs_and_bullets() {
+        assert_eq!(
+            apply_voice_commands("Dear Sam, new paragraph. thanks new line. bye", Some("en")),
+            "Dear Sam,\n\nThanks\nBye"
+        );
+        assert_eq!(
+            apply_voice_commands("Courses à la ligne nouvelle puce lait nouvelle puce œufs", Some("fr")),
+            "Courses\n- Lait\n- Œufs"
+        );
+    }
+
+    #[test]
+    fn quotes_caps_and_deletion() {
+        assert_eq!(
+            apply_voice_commands("he said open quote hi close quote", None),
+            "he said \"hi\""
+        );
+        assert_eq!(apply_voice_commands("this is all caps very end caps important", None), "this is VERY important");
+        assert_eq!(
+            apply_voice_commands("First one. Second one. Scratch that. Third.", None),
+            "First one. Third."
+        );
+    }
+
+    #[test]
+    fn fixed_language_only_uses_its_commands() {
+        assert_eq!(apply_voice_commands("une virgule", Some("en")), "une virgule");
+        assert_eq!(apply_voice_commands("a question mark", Some("fr")), "a question mark");
+        assert_eq!(apply_voice_commands("a question mark", Some("de")), "a?");
+    }
+
+    #[test]
+    fn everyday_words_are_not_commands() {
+        for text in ["the grace period ends Friday", "a colon in the URL", "add a comma here", "strip the newline, unquote it"] {
+            assert_eq!(apply_voice_commands(text, Some("en")), text);
+            assert_eq!(apply_voice_commands(text, None), text);
+        }
+        for text in ["trois virgule cinq pour cent", "nous avons deux points communs", "un point-virgule suffit"] {
+            assert_eq!(apply_voice_commands(text, Some("fr")), text);
+            assert_eq!(apply_voice_commands(text, None), text);
+        }
+    }
+}
@@ -1,92 +1,68 @@
 // TTP - Talk To Paste
-// Audio conversion: stereo 48kHz WAV → mono 16kHz WAV for Groq Whisper API
+// Audio conversion: any PCM WAV → mono 16kHz 16-bit WAV for Whisper API upload
 //
 // Whisper natively expects 16kHz mono audio. Converting before upload
 // reduces file size ~6x compared to the original stereo 48kHz WAV.
+//
+// The file is streamed in fixed-size blocks (decode → downmix → resample →
+// preprocess → write), so memory stays flat however long the recording is.
+// Resampling uses a polyphase windowed-sinc filter, which handles any rate
+// ratio (44.1k, 22.05k, 96k, ...) with proper anti-aliasing.
 
+use super::error::PipelineError;
+use super::preprocess::{normalize_peak, Preprocessor};
+use crate::logging::log_warn;
+use crate::settings::AudioPreprocessing;
 use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use std::fs::File;
+use std::io::BufReader;
 use std::path::Path;
 
 /// Target sample rate for Whisper (16kHz)
 const TARGET_SAMPLE_RATE: u32 = 16_000;
 
+/// Input frames decoded per block
+const BLOCK_FRAMES: usize = 4096;
+
+/// Sinc zero crossings on each side of the filter center (quality vs. speed)
+const ZERO_CROSSINGS: f64 = 16.0;
+
+/// Filter cutoff as a fraction of the lower Nyquist frequency; the rest is
+/// the transition band (16kHz output: flat to ~7.4kHz, stopband from 8kHz)
+const ROLLOFF: f64 = 0.92;
+
+/// Kaiser window shape (~80 dB stopband attenuation)
+const KAISER_BETA: f64 = 8.0;
+
 /// Convert a WAV file to mono 16kHz for optimal Whisper API upload.
 ///
 /// Returns the path to the converted file (same directory, `_16k.wav` suffix).
 /// The original file is NOT deleted — caller handles cleanup.
-pub fn convert_to_mono_16khz(input_path: &str) -> Result<String, String> {
+/// Enabled `preprocessing` stages run on the 16kHz signal before writing.
+pub fn convert_to_mono_16khz(
+    input_path: &str,
+    preprocessing: &AudioPreprocessing,
+) -> Result<String, PipelineError> {
     let reader = WavReader::open(input_path)
-        .map_err(|e| format!("Failed to read WAV: {}", e))?;
+        .map_err(|e| PipelineError::CorruptAudio(format!("Failed to read WAV: {}", e)))?;
     let spec = reader.spec();
 
-    let channels = spec.channels as usize;
+    let channels = spec.channels.max(1) as usize;
     let sample_rate = spec.sample_rate;
+    if sample_rate == 0 {
+        return Err(PipelineError::CorruptAudio("WAV has a sample rate of 0".to_string()));
+    }
 
-    // Already mono 16kHz — no conversion needed
-    if channels == 1 && sample_rate == TARGET_SAMPLE_RATE {
+    // Already mono 16kHz 16-bit and nothing to clean up — no conversion needed
+    if channels == 1
+        && sample_rate == TARGET_SAMPLE_RATE
+        && spec.sample_format == SampleFormat::Int
+        && spec.bits_per_sample == 16
+        && !preprocessing.any_enabled()
+    {
         return Ok(input_path.to_string());
     }
 
-    // Read all samples as i16
-    let samples: Vec<i16> = match spec.sample_format {
-        SampleFormat::Int => {
-            reader.into_samples::<i16>()
-                .map(|s| s.unwrap_or(0))
-                .collect()
-        }
-        SampleFormat::Float => {
-            reader.into_samples::<f32>()
-                .map(|s| {
-                    let v = s.unwrap_or(0.0);
-                    (v * 32767.0).clamp(-32768.0, 32767.0) as i16
-                })
-                .collect()
-        }
-    };
-
-    // Stereo → mono: average channels
-    let mono_samples: Vec<i16> = if channels > 1 {
-        samples
-            .chunks(channels)
-            .map(|chunk| {
-                let sum: i32 = chunk.iter().map(|&s| s as i32).sum();
-                (sum / channels as i32) as i16
-            })
-            .collect()
-    } else {
-        samples
-    };
-
-    // Anti-aliasing low-pass filter before downsampling
-    // Cutoff at Nyquist of target rate (8kHz) to prevent aliasing artifacts
-    let filtered = if sample_rate > TARGET_SAMPLE_RATE {
-        low_pass_filter(&mono_samples, sample_rate, TARGET_SAMPLE_RATE / 2)
-    } else {
-        mono_samples.clone()
-    };
-
-    // Resample to 16kHz using linear interpolation (safe after anti-aliasing filter)
-    let ratio = TARGET_SAMPLE_RATE as f64 / sample_rate as f64;
-    let output_len = (filtered.len() as f64 * ratio) as usize;
-    let resampled: Vec<i16> = (0..output_len)
-        .map(|i| {
-            let src_pos = i as f64 / ratio;
-            let idx = src_pos as usize;
-            let frac = src_pos - idx as f64;
-
-            if idx + 1 < filtered.len() {
-                let a = filtered[idx] as f64;
-                let b = filtered[idx + 1] as f64;
-                (a + (b - a) * frac) as i16
-            } else if idx < filtered.len() {
-                filtered[idx]
-            } else {
-                0
-            }
-        })
-        .collect();
-
-    // Write converted WAV
     let output_path = Path::new(input_path)
         .with_extension("16k.wav")
         .to_string_lossy()
@@ -100,69 +76,322 @@ pub fn convert_to_mono_16khz(input_path: &str) -> Result<String, String> {
     };
 
     let mut writer = WavWriter::create(&output_path, out_spec)
-        .map_err(|e| format!("Failed to create converted WAV: {}", e))?;
+        .map_err(|e| PipelineError::Io(format!("Failed to create converted WAV: {}", e)))?;
 
-    for sample in &resampled {
-        writer
-            .write_sample(*sample)
-            .map_err(|e| format!("Failed to write sample: {}", e))?;
+    let mut resampler = Resampler::new(sample_rate, TARGET_SAMPLE_RATE);
+    let mut preprocessor = Preprocessor::new(preprocessing, TARGET_SAMPLE_RATE);
+
+    let mut frame: Vec<f32> = Vec::with_capacity(channels);
+    let mut mono: Vec<f32> = Vec::with_capacity(BLOCK_FRAMES);
+    let mut resampled: Vec<f32> = Vec::new();
+
+    let mut flush_block = |mono: &mut Vec<f32>, finish: bool| -> Result<(), PipelineError> {
+        resampled.clear();
+        resampler.process(mono, &mut resampled);
+        if finish {
+            resampler.finish(&mut resampled);
+        }
+        mono.clear();
+
+        preprocessor.process(&mut resampled);
+        for &value in resampled.iter() {
+            writer
+                .write_sample((value * 32768.0).clamp(-32768.0, 32767.0) as i16)
+                .map_err(|e| PipelineError::Io(format!("Failed to write sample: {}", e)))?;
+        }
+        Ok(())
+    };
+
+    for sample in normalized_samples(reader) {
+        // A truncated file (crash-recovered backup) still converts up to the damage
+        let value = match sample {
+            Ok(value) => value,
+            Err(e) => {
+                log_warn(&format!("WAV decode stopped early: {}", e));
+                break;
+            }
+        };
+
+        // Downmix: average channels
+        frame.push(value);
+        if frame.len() == channels {
+            mono.push(frame.iter().sum::<f32>() / channels as f32);
+            frame.clear();
+        }
+
+        if mono.len() == BLOCK_FRAMES {
+            flush_block(&mut mono, false)?;
+        }
     }
+    flush_block(&mut mono, true)?;
 
     writer
         .finalize()
-        .map_err(|e| format!("Failed to finalize WAV: {}", e))?;
+        .map_err(|e| PipelineError::Io(format!("Failed to finalize WAV: {}", e)))?;
+
+    if preprocessing.normalize {
+        normalize_peak(&output_path)?;
+    }
 
     Ok(output_path)
 }
 
-/// Simple windowed-sinc low-pass filter to prevent aliasing before downsampling.
-/// Uses a Kaiser-windowed sinc kernel for good stopband attenuation.
-fn low_pass_filter(samples: &[i16], sample_rate: u32, cutoff_hz: u32) -> Vec<i16> {
-    let fc = cutoff_hz as f64 / sample_rate as f64; // Normalized cutoff frequency
-    let kernel_size = 63; // Odd number for symmetric kernel
-    let half = kernel_size / 2;
-
-    // Build windowed-sinc kernel
-    let mut kernel: Vec<f64> = (0..kernel_size)
-        .map(|i| {
-            let n = i as f64 - half as f64;
-            let sinc = if n.abs() < 1e-10 {
-                2.0 * std::f64::consts::PI * fc
-            } else {
-                (2.0 * std::f64::consts::PI * fc * n).sin() / n
-            };
-            // Blackman window for good stopband attenuation
-            let window = 0.42
-                - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / (kernel_size - 1) as f64).cos()
-                + 0.08 * (4.0 * std::f64::consts::PI * i as f64 / (kernel_size - 1) as f64).cos();
-            sinc * window
-        })
-        .collect();
-
-    // Normalize kernel so sum = 1
-    let sum: f64 = kernel.iter().sum();
-    for k in &mut kernel {
-        *k /= sum;
-    }
-
-    // Apply convolution
-    let len = samples.len();
-    let mut output = Vec::with_capacity(len);
-    for i in 0..len {
-        let mut acc: f64 = 0.0;
-        for (j, &k) in kernel.iter().enumerate() {
-            let idx = i as isize + j as isize - half as isize;
-            let sample = if idx < 0 {
-                samples[0] as f64
-            } else if idx >= len as isize {
-                samples[len - 1] as f64
-            } else {
-                samples[idx as usize] as f64
-            };
-            acc += sample * k;
+/// Decode any integer bit depth (8/16/24/32) or float WAV to -1.0..1.0 samples
+fn normalized_samples(
+    reader: WavReader<BufReader<File>>,
+) -> Box<dyn Iterator<Item = Result<f32, hound::Error>>> {
+    let spec = reader.spec();
+    match spec.sample_format {
+        SampleFormat::Float => Box::new(reader.into_samples::<f32>()),
+        SampleFormat::Int => {
+            let scale = 1.0 / (1u64 << (spec.bits_per_sample.clamp(1, 32) - 1)) as f32;
+            Box::new(reader.into_samples::<i32>().map(move |s| s.map(|v| v as f32 * scale)))
+        }
+    }
+}
+
+/// Streaming polyphase windowed-sinc resampler (mono)
+///
+/// The rate ratio is reduced to `up / down`; output sample `n` sits at input
+/// position `n * down / up`, whose fractional part selects one of `up`
+/// precomputed filter phases. Input before the start and after the end is
+/// treated as silence, and the output has `input_len * up / down` samples.
+struct Resampler {
+    up: u64,
+    down: u64,
+    /// `phases[p]` holds the taps for fractional position `p / up`
+    phases: Vec<Vec<f32>>,
+    /// Taps on each side of the center
+    half_taps: usize,
+    /// Input not yet fully consumed; `history[0]` is input sample `history_start`
+    history: Vec<f32>,
+    history_start: i64,
+    /// Input samples seen so far
+    consumed: u64,
+    /// Index of the next output sample
+    next_output: u64,
+}
+
+impl Resampler {
+    fn new(input_rate: u32, output_rate: u32) -> Self {
+        let divisor = gcd(input_rate.max(1) as u64, output_rate.max(1) as u64);
+        let up = output_rate.max(1) as u64 / divisor;
+        let down = input_rate.max(1) as u64 / divisor;
+
+        // Cutoff in cycles per input sample, below the lower of the two Nyquists
+        let cutoff = 0.5 * (up as f64 / down as f64).min(1.0) * ROLLOFF;
+        let half_width = ZERO_CROSSINGS / (2.0 * cutoff);
+        let half_taps = if up == down { 0 } else { half_width.ceil() as usize };
+        let taps = 2 * half_taps;
+
+        let phases = if up == down {
+            Vec::new()
+        } else {
+            (0..up)
+                .map(|p| {
+                    let frac = p as f64 / up as f64;
+                    let mut kernel: Vec<f64> = (0..taps)
+                        .map(|j| {
+                            // Distance from the output position to input tap j
+                            let t = (j as f64 - half_taps as f64 + 1.0) - frac;
+                            2.0 * cutoff * sinc(2.0 * cutoff * t) * kaiser(t / half_width)
+                        })
+                        .collect();
+                    // Unity gain at DC for every phase
+                    let sum: f64 = kernel.iter().sum();
+                    if sum.abs() > 1e-12 {
+                        kernel.iter_mut().for_each(|k| *k /= sum);
+                    }
+                    kernel.into_iter().map(|k| k as f32).collect()
+                })
+                .collect()
+        };
+
+        Self {
+            up,
+            down,
+            phases,
+            half_taps,
+            // Implicit silence before the first sample
+            history: vec![0.0; half_taps.saturating_sub(1)],
+            history_start: -(half_taps.saturating_sub(1) as i64),
+            consumed: 0,
+            next_output: 0,
+        }
+    }
+
+    /// Feed input samples, appending every output sample that is now complete
+    fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        self.consumed += input.len() as u64;
+        if self.up == self.down {
+            output.extend_from_slice(input);
+            return;
+        }
+        self.history.extend_from_slice(input);
+        self.drain(output, u64::MAX);
+    }
+
+    /// Flush the filter tail once all input has been fed
+    fn finish(&mut self, output: &mut Vec<f32>) {
+        if self.up == self.down {
+            return;
+        }
+        let total = self.consumed * self.up / self.down;
+        self.history.extend(std::iter::repeat_n(0.0, 2 * self.half_taps));
+        self.drain(output, total);
+    }
+
+    /// Compute outputs while their taps are all buffered (and below `limit`)
+    fn drain(&mut self, output: &mut Vec<f32>, limit: u64) {
+        let taps = 2 * self.half_taps;
+        let buffered_end = self.history_start + self.history.len() as i64;
+
+        while self.next_output < limit {
+            let position = self.next_output * self.down;
+            let center = (position / self.up) as i64;
+            let first = center - self.half_taps as i64 + 1;
+            if first + taps as i64 > buffered_end {
+                break;
+            }
+
+            let phase = &self.phases[(position % self.up) as usize];
+            let offset = (first - self.history_start) as usize;
+            let window = &self.history[offset..offset + taps];
+            output.push(window.iter().zip(phase).map(|(x, h)| x * h).sum());
+            self.next_output += 1;
+        }
+
+        // Drop input no future output can reach
+        let next_center = (self.next_output * self.down / self.up) as i64;
+        let keep_from = next_center - self.half_taps as i64 + 1;
+        let drop = (keep_from - self.history_start).clamp(0, self.history.len() as i64) as usize;
+        if drop > 0 {
+            self.history.drain(..drop);
+            self.history_start += drop as i64;
+        }
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-10 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Kaiser window at `x` in -1..1 (zero outside)
+fn kaiser(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    bessel_i0(KAISER_BETA * (1.0 - x * x).sqrt()) / bessel_i0(KAISER_BETA)
+}
+
+/// Zeroth-order modified Bessel function (power series)
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half = x / 2.0;
+    for k in 1..50 {
+        term *= half / k as f64;
+        sum += term * term;
+        if term * term < sum * 1e-16 {
+            break;
         }
-        output.push(acc.clamp(-32768.0, 32767.0) as i16);
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(freq: f32, rate: u32, secs: f32) -> Vec<f32> {
+        (0..(rate as f32 * secs) as usize)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin() * 0.5)
+            .collect()
+    }
+
+    /// Resample in uneven blocks, as the converter does with partial last blocks
+    fn resample(input: &[f32], from: u32, to: u32) -> Vec<f32> {
+        let mut resampler = Resampler::new(from, to);
+        let mut output = Vec::new();
+        for block in input.chunks(1000) {
+            resampler.process(block, &mut output);
+        }
+        resampler.finish(&mut output);
+        output
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
     }
 
-    output
+    #[test]
+    fn output_length_matches_ratio() {
+        for from in [8_000, 22_050, 44_100, 48_000, 96_000] {
+            let input = vec![0.0; from as usize * 2 + 123];
+            let expected = input.len() as u64 * 16_000 / from as u64;
+            assert_eq!(resample(&input, from, 16_000).len() as u64, expected, "from {}", from);
+        }
+    }
+
+    #[test]
+    fn speech_band_tone_is_preserved() {
+        for from in [22_050, 44_100, 96_000] {
+            let output = resample(&tone(1_000.0, from, 1.0), from, 16_000);
+            // Skip the filter edges
+            let level = rms(&output[2_000..14_000]);
+            assert!((level - 0.354).abs() < 0.01, "from {}: rms {}", from, level);
+        }
+    }
+
+    #[test]
+    fn tone_above_target_nyquist_is_rejected() {
+        let output = resample(&tone(12_000.0, 44_100, 1.0), 44_100, 16_000);
+        let level = rms(&output[2_000..14_000]);
+        assert!(level < 0.001, "aliased rms {}", level);
+    }
+
+    #[test]
+    fn converts_24_bit_input() {
+        let path = std::env::temp_dir().join(format!("ttp_convert_test_{}.wav", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 24,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        for value in tone(440.0, 48_000, 0.5) {
+            let sample = (value * 8_388_607.0) as i32;
+            writer.write_sample(sample).unwrap();
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let converted = convert_to_mono_16khz(&path, &AudioPreprocessing::default()).unwrap();
+        let reader = WavReader::open(&converted).unwrap();
+        assert_eq!(reader.spec().sample_rate, TARGET_SAMPLE_RATE);
+        let samples: Vec<f32> = reader
+            .into_samples::<i16>()
+            .map(|s| s.unwrap() as f32 / 32768.0)
+            .collect();
+        let level = rms(&samples[1_000..7_000]);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&converted);
+        assert!((level - 0.354).abs() < 0.01, "rms {}", level);
+    }
 }
@@ -0,0 +1,96 @@
+// TTP - Talk To Paste
+// Deepgram-style transcription API client
+//
+// Sends the raw audio bytes (WAV, FLAC or Opus/OGG) as the request body and reads the transcript
+// from `results.channels[0].alternatives[0].transcript`.
+
+use super::encode::AudioFormat;
+use super::error::PipelineError;
+use crate::llm::{send_with_retry, RetryPolicy};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::fs;
+
+/// Base request timeout in seconds (scales up with file size)
+const BASE_TIMEOUT_SECS: u64 = 30;
+
+/// Top-level `/listen` response
+#[derive(Debug, Deserialize)]
+struct ListenResponse {
+    results: ListenResults,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenResults {
+    channels: Vec<ListenChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenChannel {
+    alternatives: Vec<ListenAlternative>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenAlternative {
+    transcript: String,
+}
+
+/// Transcribe an audio file with a Deepgram-style `/listen` endpoint
+///
+/// Retries network errors, 429 and 5xx with the standard `RetryPolicy`.
+pub async fn transcribe_deepgram(
+    api_key: Option<&str>,
+    audio_path: &str,
+    listen_url: &str,
+    model: &str,
+    language: Option<&str>,
+) -> Result<String, PipelineError> {
+    let audio_bytes = fs::read(audio_path)
+        .await
+        .map_err(|e| PipelineError::AudioNotFound(e.to_string()))?;
+
+    // Scale timeout based on file size: base + 2s per MB
+    let file_mb = audio_bytes.len() as u64 / (1024 * 1024);
+    let timeout_secs = BASE_TIMEOUT_SECS + file_mb * 2;
+
+    let mime_type = AudioFormat::from_path(audio_path).mime_type();
+
+    let response = send_with_retry(
+        "Deepgram",
+        RetryPolicy::standard(),
+        Duration::from_secs(timeout_secs),
+        |client| {
+            let mut request = client
+                .post(listen_url)
+                .query(&[("model", model), ("smart_format", "true"), ("punctuate", "true")])
+                // Deepgram assumes English unless asked to detect the language
+                .query(&[match language {
+                    Some(code) => ("language", code),
+                    None => ("detect_language", "true"),
+                }])
+                .header("Content-Type", mime_type)
+                .body(audio_bytes.clone());
+
+            if let Some(key) = api_key {
+                request = request.header("Authorization", format!("Token {}", key));
+            }
+            Ok(request)
+        },
+    )
+    .await?;
+
+    let parsed: ListenResponse = response
+        .json()
+        .await
+        .map_err(|e| PipelineError::InvalidResponse(format!("Deepgram: {}", e)))?;
+
+    let transcript = parsed
+        .results
+        .channels
+        .into_iter()
+        .next()
+        .and_then(|c| c.alternatives.into_iter().next())
+        .map(|a| a.transcript.trim().to_string())
+        .unwrap_or_default();
+    Ok(transcript)
+}
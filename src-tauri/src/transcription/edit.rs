@@ -0,0 +1,38 @@
+// TTP - Talk To Paste
+// Command mode: edit the selected text with a spoken instruction
+//
+// The selection is read when the recording stops (see `paste::selection`),
+// the recording is transcribed as an instruction ("make this more polite",
+// "turn into bullet points"), and the polish LLM rewrites the selection,
+// which the pipeline then pastes over it.
+
+use super::error::PipelineError;
+use crate::llm::{self, resolve_task, LlmTask};
+use crate::settings::Settings;
+
+/// System prompt for command mode
+pub const EDIT_SYSTEM_PROMPT: &str = r#"You are a text editor. You receive a piece of TEXT and a spoken INSTRUCTION describing how to change it. Output ONLY the edited text.
+
+RULES:
+1. Apply the instruction to the text (rewrite, shorten, translate, reformat, fix, ...)
+2. Keep everything the instruction doesn't ask to change
+3. Keep the text's language unless the instruction asks for a translation
+4. The instruction was dictated: ignore filler words and self-corrections in it
+5. If the instruction makes no sense for the text, return the text unchanged
+
+CRITICAL: Your entire response must be the edited text. Do NOT wrap it in quotes or code fences. Do NOT prefix it with anything. Do NOT explain your changes."#;
+
+/// Build the user message for an edit
+pub fn build_edit_message(selection: &str, instruction: &str) -> String {
+    format!("INSTRUCTION: {}\n\nTEXT:\n{}", instruction.trim(), selection)
+}
+
+/// Apply a spoken instruction to the selected text with the polish LLM
+pub async fn edit_selection(settings: &Settings, selection: &str, instruction: &str) -> Result<String, PipelineError> {
+    let config = resolve_task(&settings.polish_llm, LlmTask::Polish).map_err(PipelineError::Config)?;
+    let text = llm::complete(&config, EDIT_SYSTEM_PROMPT, &build_edit_message(selection, instruction)).await?;
+    if text.trim().is_empty() {
+        return Err(PipelineError::InvalidResponse("Edit came back empty".to_string()));
+    }
+    Ok(text.trim().to_string())
+}
@@ -0,0 +1,352 @@
+// TTP - Talk To Paste
+// Upload encoding: 16-bit PCM WAV → FLAC or Opus/OGG
+//
+// Runs just before a cloud upload, on the mono 16kHz WAV produced by
+// `convert`. FLAC is lossless at roughly half the size; Opus at speech
+// bitrates is ~1/8 the size and transcribes just as well. Local
+// transcription keeps reading the WAV directly.
+
+use super::error::PipelineError;
+use hound::{SampleFormat, WavReader};
+use std::path::Path;
+
+/// Container/codec of an upload file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Wav,
+    Flac,
+    Opus,
+}
+
+impl AudioFormat {
+    /// MIME type for the multipart part / request body
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            Self::Flac => "audio/flac",
+            Self::Opus => "audio/ogg",
+        }
+    }
+
+    /// File extension written by `encode_for_upload`
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+            Self::Opus => "ogg",
+        }
+    }
+
+    /// Detect the format from a file extension (defaults to WAV)
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("flac") => Self::Flac,
+            Some("ogg") | Some("opus") => Self::Opus,
+            _ => Self::Wav,
+        }
+    }
+
+    /// Conservative encoded size relative to 16-bit PCM (for upload limit checks)
+    pub fn size_ratio(&self) -> f64 {
+        match self {
+            Self::Wav => 1.0,
+            Self::Flac => 0.8, // Usually ~0.5 for speech; noisy rooms compress worse
+            Self::Opus => 0.15,
+        }
+    }
+
+    /// Whether this build can produce the format
+    pub fn is_available(&self) -> bool {
+        *self != Self::Opus || cfg!(feature = "opus-upload")
+    }
+}
+
+/// Encode a WAV for upload, returning the path of the file to send
+///
+/// Returns the input path unchanged for `AudioFormat::Wav`. Otherwise
+/// writes `<name>.flac` / `<name>.ogg` next to the input; the caller
+/// deletes it after the upload. Runs on a blocking thread.
+pub async fn encode_for_upload(wav_path: &str, format: AudioFormat) -> Result<String, PipelineError> {
+    if format == AudioFormat::Wav {
+        return Ok(wav_path.to_string());
+    }
+
+    let input = wav_path.to_string();
+    tokio::task::spawn_blocking(move || {
+        let (samples, channels, sample_rate) = read_pcm_i16(&input)?;
+        let output = Path::new(&input)
+            .with_extension(format.extension())
+            .to_string_lossy()
+            .to_string();
+
+        match format {
+            AudioFormat::Flac => flac::encode(&samples, channels, sample_rate, &output)?,
+            AudioFormat::Opus => opus_ogg::encode(&samples, channels, sample_rate, &output)?,
+            AudioFormat::Wav => unreachable!(),
+        }
+        Ok(output)
+    })
+    .await
+    .map_err(|e| PipelineError::Io(format!("Encoder task failed: {}", e)))?
+}
+
+/// Read a WAV as interleaved i16 samples
+fn read_pcm_i16(path: &str) -> Result<(Vec<i16>, u16, u32), PipelineError> {
+    let reader = WavReader::open(path)
+        .map_err(|e| PipelineError::CorruptAudio(format!("Failed to read WAV: {}", e)))?;
+    let spec = reader.spec();
+    if spec.sample_format != SampleFormat::Int || spec.bits_per_sample != 16 {
+        return Err(PipelineError::Io(format!(
+            "Encoder expects 16-bit PCM, got {:?} {}-bit",
+            spec.sample_format, spec.bits_per_sample
+        )));
+    }
+    let samples = reader.into_samples::<i16>().map(|s| s.unwrap_or(0)).collect();
+    Ok((samples, spec.channels, spec.sample_rate))
+}
+
+mod flac {
+    use super::PipelineError;
+    use flacenc::component::BitRepr;
+    use flacenc::error::Verify;
+
+    pub fn encode(samples: &[i16], channels: u16, sample_rate: u32, output: &str) -> Result<(), PipelineError> {
+        let config = flacenc::config::Encoder::default()
+            .into_verified()
+            .map_err(|(_, e)| PipelineError::Io(format!("Invalid FLAC config: {}", e)))?;
+
+        let samples: Vec<i32> = samples.iter().map(|&s| s as i32).collect();
+        let source = flacenc::source::MemSource::from_samples(
+            &samples,
+            channels as usize,
+            16,
+            sample_rate as usize,
+        );
+
+        let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+            .map_err(|e| PipelineError::Io(format!("FLAC encoding failed: {:?}", e)))?;
+
+        let mut sink = flacenc::bitsink::ByteSink::new();
+        stream
+            .write(&mut sink)
+            .map_err(|_| PipelineError::Io("FLAC write failed".to_string()))?;
+
+        std::fs::write(output, sink.as_slice())
+            .map_err(|e| PipelineError::Io(format!("Failed to write FLAC file: {}", e)))
+    }
+}
+
+#[cfg(feature = "opus-upload")]
+mod opus_ogg {
+    use super::PipelineError;
+    use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+
+    /// Speech bitrate — transparent for Whisper at 16kHz mono
+    const BITRATE: i32 = 32_000;
+
+    /// 20ms frames
+    const FRAME_MS: usize = 20;
+
+    /// Encoder lookahead at 48kHz (libopus default for VOIP), written as pre-skip
+    const PRE_SKIP: u16 = 312;
+
+    /// Ogg logical stream serial (single stream per file)
+    const SERIAL: u32 = 0x5454_5001;
+
+    pub fn encode(samples: &[i16], channels: u16, sample_rate: u32, output: &str) -> Result<(), PipelineError> {
+        if !matches!(sample_rate, 8_000 | 12_000 | 16_000 | 24_000 | 48_000) {
+            return Err(PipelineError::Io(format!("Opus does not support {}Hz input", sample_rate)));
+        }
+        let opus_channels = match channels {
+            1 => opus::Channels::Mono,
+            2 => opus::Channels::Stereo,
+            n => return Err(PipelineError::Io(format!("Opus does not support {} channels", n))),
+        };
+
+        let mut encoder = opus::Encoder::new(sample_rate, opus_channels, opus::Application::Voip)
+            .map_err(|e| PipelineError::Io(format!("Failed to create Opus encoder: {}", e)))?;
+        encoder
+            .set_bitrate(opus::Bitrate::Bits(BITRATE))
+            .map_err(|e| PipelineError::Io(format!("Failed to set Opus bitrate: {}", e)))?;
+
+        let file = File::create(output)
+            .map_err(|e| PipelineError::Io(format!("Failed to create OGG file: {}", e)))?;
+        let mut writer = PacketWriter::new(BufWriter::new(file));
+        let io_err = |e: std::io::Error| PipelineError::Io(format!("Failed to write OGG page: {}", e));
+
+        // Identification and comment headers each sit on their own page
+        writer
+            .write_packet(opus_head(channels, sample_rate).into_boxed_slice(), SERIAL, PacketWriteEndInfo::EndPage, 0)
+            .map_err(io_err)?;
+        writer
+            .write_packet(opus_tags().into_boxed_slice(), SERIAL, PacketWriteEndInfo::EndPage, 0)
+            .map_err(io_err)?;
+
+        let frame_len = sample_rate as usize * FRAME_MS / 1000 * channels as usize;
+        let granule_step = (48_000 * FRAME_MS / 1000) as u64; // Granules are always 48kHz
+        let frame_count = samples.len().div_ceil(frame_len).max(1);
+        let mut packet = vec![0u8; 4000];
+        let mut frame = vec![0i16; frame_len];
+
+        for index in 0..frame_count {
+            let start = index * frame_len;
+            let end = (start + frame_len).min(samples.len());
+            frame.fill(0); // Last frame is zero-padded
+            frame[..end - start].copy_from_slice(&samples[start..end]);
+
+            let len = encoder
+                .encode(&frame, &mut packet)
+                .map_err(|e| PipelineError::Io(format!("Opus encoding failed: {}", e)))?;
+
+            // The final granule position marks the real end so decoders drop the padding
+            let (end_info, granule) = if index + 1 == frame_count {
+                let total = (samples.len() / channels as usize) as u64 * 48_000 / sample_rate as u64;
+                (PacketWriteEndInfo::EndStream, PRE_SKIP as u64 + total)
+            } else {
+                (PacketWriteEndInfo::NormalPacket, PRE_SKIP as u64 + (index as u64 + 1) * granule_step)
+            };
+            writer
+                .write_packet(packet[..len].into(), SERIAL, end_info, granule)
+                .map_err(io_err)?;
+        }
+
+        writer.into_inner().flush().map_err(io_err)
+    }
+
+    /// `OpusHead` identification header (RFC 7845 §5.1)
+    fn opus_head(channels: u16, sample_rate: u32) -> Vec<u8> {
+        let mut head = Vec::with_capacity(19);
+        head.extend_from_slice(b"OpusHead");
+        head.push(1); // Version
+        head.push(channels as u8);
+        head.extend_from_slice(&PRE_SKIP.to_le_bytes());
+        head.extend_from_slice(&sample_rate.to_le_bytes());
+        head.extend_from_slice(&0i16.to_le_bytes()); // Output gain
+        head.push(0); // Mapping family 0 (mono/stereo)
+        head
+    }
+
+    /// `OpusTags` comment header (RFC 7845 §5.2)
+    fn opus_tags() -> Vec<u8> {
+        let vendor = b"TTP";
+        let mut tags = Vec::with_capacity(16 + vendor.len());
+        tags.extend_from_slice(b"OpusTags");
+        tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+        tags.extend_from_slice(vendor);
+        tags.extend_from_slice(&0u32.to_le_bytes()); // No user comments
+        tags
+    }
+}
+
+#[cfg(not(feature = "opus-upload"))]
+mod opus_ogg {
+    use super::PipelineError;
+
+    pub fn encode(_samples: &[i16], _channels: u16, _sample_rate: u32, _output: &str) -> Result<(), PipelineError> {
+        Err(PipelineError::Config("Opus encoding is not available in this build".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 440Hz tone, interleaved when stereo
+    fn tone(frames: usize, channels: u16, sample_rate: u32) -> Vec<i16> {
+        (0..frames)
+            .flat_map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                let value = ((2.0 * std::f32::consts::PI * 440.0 * t).sin() * 8_000.0) as i16;
+                std::iter::repeat_n(value, channels as usize)
+            })
+            .collect()
+    }
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("ttp_encode_test_{}_{}", std::process::id(), name))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Sample rate, channels, bits per sample and total frames from STREAMINFO
+    fn flac_stream_info(bytes: &[u8]) -> (u32, u16, u16, u64) {
+        assert_eq!(&bytes[..4], b"fLaC");
+        assert_eq!(bytes[4] & 0x7f, 0, "first metadata block is STREAMINFO");
+        let packed = u64::from_be_bytes(bytes[18..26].try_into().unwrap());
+        let sample_rate = (packed >> 44) as u32;
+        let channels = ((packed >> 41) & 0x7) as u16 + 1;
+        let bits = ((packed >> 36) & 0x1f) as u16 + 1;
+        let frames = packed & 0xf_ffff_ffff;
+        (sample_rate, channels, bits, frames)
+    }
+
+    #[test]
+    fn flac_keeps_every_sample() {
+        // Neither length is a multiple of the block size
+        for (channels, frames) in [(1u16, 20_800usize), (2, 5_001)] {
+            let output = temp_path(&format!("{}ch.flac", channels));
+            flac::encode(&tone(frames, channels, 16_000), channels, 16_000, &output).unwrap();
+            let bytes = std::fs::read(&output).unwrap();
+            let _ = std::fs::remove_file(&output);
+
+            assert_eq!(flac_stream_info(&bytes), (16_000, channels, 16, frames as u64));
+            assert!(bytes.len() < frames * channels as usize * 2, "FLAC larger than PCM");
+        }
+    }
+
+    #[test]
+    fn wav_is_uploaded_as_is() {
+        let path = temp_path("as_is.wav");
+        let upload = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(encode_for_upload(&path, AudioFormat::Wav))
+            .unwrap();
+        assert_eq!(upload, path);
+    }
+
+    #[cfg(feature = "opus-upload")]
+    #[test]
+    fn ogg_opus_granules_cover_the_input() {
+        use ogg::reading::PacketReader;
+
+        let frames = 16_160; // 50.5 frames of 20ms: the last one is padded
+        let output = temp_path("mono.ogg");
+        opus_ogg::encode(&tone(frames, 1, 16_000), 1, 16_000, &output).unwrap();
+        let file = std::fs::File::open(&output).unwrap();
+        let mut reader = PacketReader::new(std::io::BufReader::new(file));
+        let mut packets = Vec::new();
+        while let Some(packet) = reader.read_packet().unwrap() {
+            packets.push(packet);
+        }
+        let _ = std::fs::remove_file(&output);
+
+        let head = &packets[0].data;
+        assert_eq!(&head[..8], b"OpusHead");
+        assert_eq!(head[9], 1, "channel count");
+        let pre_skip = u16::from_le_bytes([head[10], head[11]]) as u64;
+        assert_eq!(pre_skip, 312);
+        assert_eq!(u32::from_le_bytes(head[12..16].try_into().unwrap()), 16_000);
+        assert_eq!(packets[0].absgp_page(), 0);
+        assert_eq!(&packets[1].data[..8], b"OpusTags");
+        assert_eq!(packets[1].absgp_page(), 0);
+
+        // One packet per 20ms frame, granules in 48kHz units and never decreasing
+        let audio = &packets[2..];
+        assert_eq!(audio.len(), frames.div_ceil(320));
+        assert!(audio.windows(2).all(|w| w[0].absgp_page() <= w[1].absgp_page()));
+
+        // The end granule trims the padding: pre-skip plus the real input length
+        let last = audio.last().unwrap();
+        assert!(last.last_in_stream());
+        assert_eq!(last.absgp_page(), pre_skip + frames as u64 * 3);
+    }
+}
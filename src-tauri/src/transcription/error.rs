@@ -0,0 +1,239 @@
+// TTP - Talk To Paste
+// Typed pipeline errors
+//
+// Every stage (validate, convert, backup, transcribe, polish, paste) reports
+// a `PipelineError`. The variant decides what the user sees, which
+// `error_category` analytics records, and whether the audio is worth keeping
+// for a retry. `Display` starts with the category so `ttp.log` lines can be
+// grepped by failure kind.
+
+use crate::llm::LlmError;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub enum PipelineError {
+    /// Recording file missing or unreadable
+    AudioNotFound(String),
+    /// WAV header / samples could not be parsed
+    CorruptAudio(String),
+    /// Recording too long even for chunked upload (MB)
+    AudioTooLarge { size_mb: f64 },
+    /// Upload over the API limit (413, or the encoded file was still too big)
+    PayloadTooLarge,
+    /// Cloud provider needs a key that isn't configured
+    MissingApiKey { provider: String },
+    /// Invalid provider / endpoint configuration
+    Config(String),
+    /// Connection / DNS / TLS failure
+    Network(String),
+    /// Request timed out
+    Timeout,
+    /// 429 after retries were exhausted
+    RateLimited { retry_after: Option<Duration> },
+    /// 401 / 403 — key rejected by the provider
+    Unauthorized(String),
+    /// Any other non-success HTTP status
+    Api { status: u16, message: String },
+    /// Provider answered with something we couldn't parse
+    InvalidResponse(String),
+    /// On-device whisper.cpp failed
+    LocalEngine(String),
+    /// Local file operation failed (conversion output, backup copy)
+    Io(String),
+    /// Transcription came back empty
+    NoSpeech,
+    /// Transcription matched a known hallucination (reason for the log)
+    Hallucination(String),
+    /// Command mode started without any text selected
+    NoSelection,
+    /// Could not write the result to the clipboard
+    ClipboardFailed(String),
+    /// Text is on the clipboard but simulating the paste failed
+    PasteFailed(String),
+}
+
+impl PipelineError {
+    /// Analytics `error_category` value
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::AudioNotFound(_) | Self::Io(_) => "io_error",
+            Self::CorruptAudio(_) => "corrupt_audio",
+            Self::AudioTooLarge { .. } | Self::PayloadTooLarge => "too_long",
+            Self::MissingApiKey { .. } => "missing_key",
+            Self::Config(_) => "config",
+            Self::Network(_) | Self::Timeout => "network",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Api { .. } | Self::InvalidResponse(_) => "api_error",
+            Self::LocalEngine(_) => "local_engine",
+            Self::NoSpeech | Self::Hallucination(_) => "no_speech",
+            Self::NoSelection => "no_selection",
+            Self::ClipboardFailed(_) => "clipboard",
+            Self::PasteFailed(_) => "paste_failed",
+        }
+    }
+
+    /// Short message for the pill / progress event
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::AudioNotFound(_) => "Audio file not found".to_string(),
+            Self::CorruptAudio(msg) => msg.clone(),
+            Self::AudioTooLarge { size_mb } => {
+                format!("Recording too long ({:.0}MB). Max ~2 hours.", size_mb)
+            }
+            Self::PayloadTooLarge => "Recording too long — try a shorter recording".to_string(),
+            Self::MissingApiKey { provider } => format!("No {} API key configured", provider),
+            Self::Config(msg) => msg.clone(),
+            Self::Network(_) => "Transcription failed: no connection".to_string(),
+            Self::Timeout => "Transcription failed: request timed out".to_string(),
+            Self::RateLimited { .. } => "Rate limited — try again in a moment".to_string(),
+            Self::Unauthorized(_) => "API key rejected — check it in Settings".to_string(),
+            Self::Api { status, .. } => format!("Transcription failed (HTTP {})", status),
+            Self::InvalidResponse(_) => "Transcription failed: unexpected response".to_string(),
+            Self::LocalEngine(msg) => format!("Local transcription failed: {}", msg),
+            Self::Io(_) => "Failed to process audio file".to_string(),
+            Self::NoSpeech | Self::Hallucination(_) => "No speech detected".to_string(),
+            Self::NoSelection => "Select the text to edit first".to_string(),
+            Self::ClipboardFailed(_) => "Failed to write to clipboard".to_string(),
+            Self::PasteFailed(_) => "Paste failed — Cmd+V to paste".to_string(),
+        }
+    }
+
+    /// Whether the same audio could succeed later (network back, key fixed, quota reset)
+    ///
+    /// Decides if a failed recording goes to the retry queue (its backup is
+    /// kept either way). Problems with the audio itself (corrupt, too long,
+    /// silent) will fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Network(_)
+            | Self::Timeout
+            | Self::RateLimited { .. }
+            | Self::Unauthorized(_)
+            | Self::MissingApiKey { .. }
+            | Self::Config(_)
+            | Self::InvalidResponse(_)
+            | Self::LocalEngine(_)
+            | Self::Io(_) => true,
+            Self::Api { status, .. } => *status >= 500,
+            Self::AudioNotFound(_)
+            | Self::CorruptAudio(_)
+            | Self::AudioTooLarge { .. }
+            | Self::PayloadTooLarge
+            | Self::NoSpeech
+            | Self::Hallucination(_)
+            | Self::NoSelection
+            | Self::ClipboardFailed(_)
+            | Self::PasteFailed(_) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] ", self.category())?;
+        match self {
+            Self::AudioNotFound(msg) => write!(f, "Audio file error: {}", msg),
+            Self::CorruptAudio(msg) => write!(f, "{}", msg),
+            Self::AudioTooLarge { size_mb } => {
+                write!(f, "Audio too large: {:.1}MB exceeds chunked upload limit", size_mb)
+            }
+            Self::PayloadTooLarge => write!(f, "API rejected upload: 413 Payload Too Large"),
+            Self::MissingApiKey { provider } => write!(f, "No {} API key configured", provider),
+            Self::Config(msg) => write!(f, "Configuration error: {}", msg),
+            Self::Network(msg) => write!(f, "Network error: {}", msg),
+            Self::Timeout => write!(f, "Request timed out"),
+            Self::RateLimited {
+                retry_after: Some(wait),
+            } => write!(f, "Rate limited (429), retry after {}s", wait.as_secs()),
+            Self::RateLimited { retry_after: None } => write!(f, "Rate limited (429)"),
+            Self::Unauthorized(body) => write!(f, "Unauthorized (401/403): {}", body),
+            Self::Api { status, message } => write!(f, "API error {}: {}", status, message),
+            Self::InvalidResponse(msg) => write!(f, "Invalid API response: {}", msg),
+            Self::LocalEngine(msg) => write!(f, "Local engine error: {}", msg),
+            Self::Io(msg) => write!(f, "{}", msg),
+            Self::NoSpeech => write!(f, "No speech detected"),
+            Self::Hallucination(reason) => write!(f, "No speech detected ({})", reason),
+            Self::NoSelection => write!(f, "Command mode: no text selected"),
+            Self::ClipboardFailed(msg) => write!(f, "Clipboard write failed: {}", msg),
+            Self::PasteFailed(msg) => write!(f, "Paste failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+impl From<LlmError> for PipelineError {
+    fn from(e: LlmError) -> Self {
+        match e {
+            LlmError::Config(msg) => Self::Config(msg),
+            LlmError::Network(msg) => Self::Network(msg),
+            LlmError::Timeout => Self::Timeout,
+            LlmError::RateLimited { retry_after } => Self::RateLimited { retry_after },
+            LlmError::Unauthorized { body, .. } => Self::Unauthorized(body),
+            LlmError::Http { status, .. } if status.as_u16() == 413 => Self::PayloadTooLarge,
+            LlmError::Http { status, body } => Self::Api {
+                status: status.as_u16(),
+                message: body,
+            },
+            LlmError::InvalidResponse(msg) => Self::InvalidResponse(msg),
+            LlmError::EmptyResponse => Self::InvalidResponse("empty response".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn http(status: u16) -> PipelineError {
+        LlmError::Http {
+            status: StatusCode::from_u16(status).unwrap(),
+            body: String::new(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn llm_errors_map_to_pipeline_errors() {
+        let unauthorized: PipelineError = LlmError::Unauthorized {
+            status: StatusCode::UNAUTHORIZED,
+            body: "bad key".to_string(),
+        }
+        .into();
+        assert!(matches!(unauthorized, PipelineError::Unauthorized(ref body) if body == "bad key"));
+        assert!(matches!(http(413), PipelineError::PayloadTooLarge));
+        assert!(matches!(
+            PipelineError::from(LlmError::RateLimited { retry_after: None }),
+            PipelineError::RateLimited { retry_after: None }
+        ));
+        assert!(matches!(http(503), PipelineError::Api { status: 503, .. }));
+        assert!(matches!(
+            PipelineError::from(LlmError::EmptyResponse),
+            PipelineError::InvalidResponse(_)
+        ));
+    }
+
+    #[test]
+    fn only_failures_that_could_pass_later_are_retryable() {
+        assert!(PipelineError::Unauthorized(String::new()).is_retryable());
+        assert!(PipelineError::RateLimited { retry_after: None }.is_retryable());
+        assert!(PipelineError::Timeout.is_retryable());
+        assert!(http(500).is_retryable() && http(503).is_retryable());
+        assert!(!http(400).is_retryable() && !http(404).is_retryable());
+        assert!(!http(413).is_retryable());
+        assert!(!PipelineError::NoSpeech.is_retryable());
+    }
+
+    #[test]
+    fn categories() {
+        assert_eq!(http(413).category(), "too_long");
+        assert_eq!(http(502).category(), "api_error");
+        assert_eq!(PipelineError::Unauthorized(String::new()).category(), "unauthorized");
+        assert_eq!(PipelineError::RateLimited { retry_after: None }.category(), "rate_limited");
+        assert_eq!(PipelineError::Timeout.category(), "network");
+        assert_eq!(PipelineError::Hallucination("x".to_string()).category(), "no_speech");
+        assert!(PipelineError::Timeout.to_string().starts_with("[network] "));
+    }
+}
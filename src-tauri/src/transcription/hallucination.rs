@@ -0,0 +1,323 @@
+// TTP - Talk To Paste
+// Hallucination filter driven by data files
+//
+// Whisper invents text on silent or near-silent audio ("Thank you.",
+// subtitle credits, ...). The phrases come from two files with the same
+// format: the built-in list compiled into the app (data/hallucinations.json)
+// and a user list (~/.config/ttp/hallucinations.json) that grows when a
+// history entry is marked as a hallucination. Rules are grouped by language
+// ("*" applies to every language) and are exact phrases, substrings or
+// regular expressions, all matched against the lowercased transcript.
+// Regexes are compiled once: the built-in rules for the app's lifetime, the
+// user rules until their file changes.
+
+use crate::history::get_history_entry;
+use crate::logging::{log_info, log_warn};
+use crate::settings::get_settings;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// Built-in rules shipped with this release
+const BUILTIN_RULES: &str = include_str!("../../data/hallucinations.json");
+
+/// Current rule file format
+const RULES_VERSION: u32 = 1;
+
+/// Language key for rules that apply whatever the spoken language
+const ANY_LANGUAGE: &str = "*";
+
+/// Rule file contents
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HallucinationRules {
+    /// File format version
+    #[serde(default)]
+    pub version: u32,
+    /// Rules by ISO 639-1 code, or "*" for every language
+    #[serde(default)]
+    pub languages: BTreeMap<String, RuleSet>,
+}
+
+/// Rules for one language
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RuleSet {
+    /// Whole transcripts (also matched without a trailing period)
+    #[serde(default)]
+    pub exact: Vec<String>,
+    /// Substrings anywhere in the transcript (subtitle credits, URLs)
+    #[serde(default)]
+    pub contains: Vec<String>,
+    /// Regular expressions
+    #[serde(default)]
+    pub regex: Vec<String>,
+}
+
+/// Where a rule came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    BuiltIn,
+    User,
+}
+
+/// The rule that flagged a transcript, for the log
+#[derive(Debug, Clone, PartialEq)]
+pub struct HallucinationMatch {
+    kind: &'static str,
+    pattern: String,
+    language: String,
+    source: Source,
+}
+
+impl fmt::Display for HallucinationMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match self.source {
+            Source::BuiltIn => "built-in",
+            Source::User => "user",
+        };
+        write!(f, "{} rule \"{}\" [{}, {}]", self.kind, self.pattern, self.language, source)
+    }
+}
+
+/// Rules for one language with their regexes compiled
+struct CompiledSet {
+    code: String,
+    exact: Vec<String>,
+    contains: Vec<String>,
+    regex: Vec<(String, Regex)>,
+}
+
+/// Rule file ready to match
+struct CompiledRules {
+    source: Source,
+    sets: Vec<CompiledSet>,
+}
+
+impl CompiledRules {
+    /// Compile every regex, skipping (and logging) invalid ones
+    fn compile(rules: HallucinationRules, source: Source) -> Self {
+        let sets = rules
+            .languages
+            .into_iter()
+            .map(|(code, set)| CompiledSet {
+                code,
+                exact: set.exact,
+                contains: set.contains,
+                regex: set
+                    .regex
+                    .into_iter()
+                    .filter_map(|pattern| match Regex::new(&pattern) {
+                        Ok(re) => Some((pattern, re)),
+                        Err(e) => {
+                            log_warn(&format!("Skipping invalid hallucination regex \"{}\": {}", pattern, e));
+                            None
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        Self { source, sets }
+    }
+}
+
+/// Get the user rule file path (~/.config/ttp/hallucinations.json)
+fn get_user_rules_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("ttp").join("hallucinations.json"))
+}
+
+/// Parse the built-in rules (checked by a unit test, so never empty in a release)
+fn builtin_rules() -> HallucinationRules {
+    serde_json::from_str(BUILTIN_RULES).unwrap_or_else(|e| {
+        log_warn(&format!("Built-in hallucination rules are invalid: {}", e));
+        HallucinationRules::default()
+    })
+}
+
+/// Built-in rules, parsed and compiled on first use
+fn builtin_compiled() -> &'static CompiledRules {
+    static RULES: OnceLock<CompiledRules> = OnceLock::new();
+    RULES.get_or_init(|| CompiledRules::compile(builtin_rules(), Source::BuiltIn))
+}
+
+/// User rules, reloaded only when the file's modification time changes
+fn user_compiled() -> Arc<CompiledRules> {
+    static CACHE: Mutex<Option<(Option<SystemTime>, Arc<CompiledRules>)>> = Mutex::new(None);
+
+    let modified = get_user_rules_path()
+        .and_then(|path| fs::metadata(path).ok())
+        .and_then(|meta| meta.modified().ok());
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_at, rules)) = cache.as_ref() {
+        if *cached_at == modified {
+            return Arc::clone(rules);
+        }
+    }
+
+    let rules = Arc::new(CompiledRules::compile(load_user_rules(), Source::User));
+    *cache = Some((modified, Arc::clone(&rules)));
+    rules
+}
+
+/// Load the user rules; missing or unreadable files count as empty
+fn load_user_rules() -> HallucinationRules {
+    let Some(path) = get_user_rules_path() else {
+        return HallucinationRules::default();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return HallucinationRules::default();
+    };
+
+    match serde_json::from_str::<HallucinationRules>(&content) {
+        Ok(rules) => {
+            if rules.version > RULES_VERSION {
+                log_warn(&format!(
+                    "Hallucination rules file is version {} (this build reads {}), unknown fields are ignored",
+                    rules.version, RULES_VERSION
+                ));
+            }
+            rules
+        }
+        Err(e) => {
+            log_warn(&format!("Failed to parse {}: {}", path.display(), e));
+            HallucinationRules::default()
+        }
+    }
+}
+
+fn save_user_rules(rules: &HallucinationRules) -> Result<(), String> {
+    let path = get_user_rules_path().ok_or("Could not determine config directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(rules)
+        .map_err(|e| format!("Failed to serialize hallucination rules: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write hallucination rules: {}", e))
+}
+
+/// Lowercase and trim, the form every rule is written in
+fn normalize(text: &str) -> String {
+    text.trim().to_lowercase()
+}
+
+/// Find the first rule in `rules` that matches `lower`
+///
+/// `language` is the fixed spoken language; `None` (auto-detect) applies
+/// every language's rules.
+fn match_rules(rules: &CompiledRules, lower: &str, language: Option<&str>) -> Option<HallucinationMatch> {
+    let stripped = lower.trim_end_matches('.');
+
+    for set in &rules.sets {
+        let code = &set.code;
+        let applies = code == ANY_LANGUAGE || language.is_none_or(|l| code.eq_ignore_ascii_case(l));
+        if !applies {
+            continue;
+        }
+        let found = |kind: &'static str, pattern: &str| HallucinationMatch {
+            kind,
+            pattern: pattern.to_string(),
+            language: code.clone(),
+            source: rules.source,
+        };
+
+        if let Some(phrase) = set.exact.iter().find(|p| lower == p.as_str() || stripped == p.as_str()) {
+            return Some(found("exact", phrase));
+        }
+        if let Some(sub) = set.contains.iter().find(|s| !s.is_empty() && lower.contains(s.as_str())) {
+            return Some(found("contains", sub));
+        }
+        if let Some((pattern, _)) = set.regex.iter().find(|(_, re)| re.is_match(lower)) {
+            return Some(found("regex", pattern));
+        }
+    }
+
+    None
+}
+
+/// Check a transcript against the built-in and user rules
+///
+/// Returns the rule that fired, or `None` when the text looks like real speech.
+pub fn detect_hallucination(text: &str, language: Option<&str>) -> Option<HallucinationMatch> {
+    let lower = normalize(text);
+    match_rules(builtin_compiled(), &lower, language)
+        .or_else(|| match_rules(&user_compiled(), &lower, language))
+}
+
+/// Tauri command: mark a history entry as a hallucination
+///
+/// Adds its raw transcript to the user list (under the configured language,
+/// or "*" when auto-detecting). The entry stays in history; deleting it is
+/// a separate action (`delete_history_entry`).
+#[tauri::command]
+pub fn mark_history_hallucination(timestamp: i64) -> Result<(), String> {
+    let entry = get_history_entry(timestamp).ok_or("History entry not found")?;
+    if let Some(reason) = entry.rerun_blocker() {
+        return Err(reason.to_string());
+    }
+    let phrase = normalize(entry.raw_text.as_deref().unwrap_or(&entry.text))
+        .trim_end_matches('.')
+        .to_string();
+    if phrase.is_empty() {
+        return Err("Nothing to mark: the entry is empty".to_string());
+    }
+
+    let settings = get_settings();
+    let language = settings.transcription.language().unwrap_or(ANY_LANGUAGE).to_lowercase();
+
+    let mut rules = load_user_rules();
+    rules.version = RULES_VERSION;
+    let set = rules.languages.entry(language.clone()).or_default();
+    if !set.exact.contains(&phrase) {
+        set.exact.push(phrase.clone());
+        save_user_rules(&rules)?;
+    }
+    log_info(&format!("Marked \"{}\" as a hallucination [{}]", phrase, language));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(json: &str) -> CompiledRules {
+        CompiledRules::compile(serde_json::from_str(json).unwrap(), Source::User)
+    }
+
+    #[test]
+    fn builtin_rules_parse_and_compile() {
+        let rules: HallucinationRules = serde_json::from_str(BUILTIN_RULES).unwrap();
+        assert_eq!(rules.version, RULES_VERSION);
+        for set in rules.languages.values() {
+            for pattern in &set.regex {
+                assert!(Regex::new(pattern).is_ok(), "invalid regex {}", pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn builtin_rules_catch_known_phrases() {
+        let check = |text: &str, language| match_rules(builtin_compiled(), &normalize(text), language);
+
+        assert_eq!(check("Thank you.", None).unwrap().kind, "exact");
+        assert_eq!(check("Thanks for watching!", None).unwrap().kind, "regex");
+        assert_eq!(check("Untertitel im Auftrag des ZDF, 2017", Some("de")).unwrap().kind, "contains");
+        assert!(check("Thank you.", Some("de")).is_none());
+        assert!(check("Thank you for the review, I'll fix it today.", None).is_none());
+    }
+
+    #[test]
+    fn rule_scope_follows_language() {
+        let user = rules(r#"{"version": 1, "languages": {"de": {"exact": ["danke schön"]}, "*": {"exact": ["zzz"]}}}"#);
+
+        let hit = match_rules(&user, "danke schön.", Some("de")).unwrap();
+        assert_eq!(hit.to_string(), "exact rule \"danke schön\" [de, user]");
+        assert!(match_rules(&user, "danke schön", None).is_some());
+        assert!(match_rules(&user, "danke schön", Some("fr")).is_none());
+        assert!(match_rules(&user, "zzz", Some("fr")).is_some());
+    }
+}
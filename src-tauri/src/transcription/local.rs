@@ -0,0 +1,513 @@
+// TTP - Talk To Paste
+// On-device transcription with whisper.cpp (ggml models)
+//
+// Consumes the mono 16kHz WAV produced by `convert::convert_to_mono_16khz`.
+// Models are downloaded once into `app_data_dir/models/`, verified against
+// the SHA-1 checksums published by whisper.cpp, and can be deleted again
+// from the settings window. The engine itself is behind the `local-whisper`
+// cargo feature; without it, the model commands still work but
+// transcription returns an error so the cloud fallback can kick in.
+
+use super::error::PipelineError;
+use super::provider::{TranscriptionProvider, TranscriptionRequest, TranscriptionTask};
+use crate::logging::{log_error, log_info};
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::collections::HashSet;
+use std::future::Future;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::time::timeout;
+
+/// Base URL for the official ggml conversions of the Whisper models
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Time allowed for the model host to answer with headers
+const DOWNLOAD_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time allowed between two chunks before the download counts as stalled
+const DOWNLOAD_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Model ids being downloaded right now (one download per id at a time)
+static DOWNLOADS_IN_PROGRESS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// A downloadable whisper.cpp model
+pub struct LocalModel {
+    /// Id used in settings ("base", "small", ...)
+    pub id: &'static str,
+    /// File name on the model host and on disk
+    pub file_name: &'static str,
+    /// Approximate download size in MB (for the UI)
+    pub size_mb: u32,
+    /// SHA-1 checksum from the whisper.cpp models README
+    pub sha1: &'static str,
+}
+
+/// Models offered for download (multilingual only — TTP users mix languages)
+pub const LOCAL_MODELS: &[LocalModel] = &[
+    LocalModel {
+        id: "tiny",
+        file_name: "ggml-tiny.bin",
+        size_mb: 75,
+        sha1: "bd577a113a864445d4c299885e0cb97d4ba92b5f",
+    },
+    LocalModel {
+        id: "base",
+        file_name: "ggml-base.bin",
+        size_mb: 142,
+        sha1: "465707469ff3a37a2b9b8d8f89f2f99de7299dac",
+    },
+    LocalModel {
+        id: "small",
+        file_name: "ggml-small.bin",
+        size_mb: 466,
+        sha1: "55356645c2b361a969dfd0ef2c5a50d530afd8d5",
+    },
+    LocalModel {
+        id: "medium",
+        file_name: "ggml-medium.bin",
+        size_mb: 1500,
+        sha1: "fd9727b6e1217c2f614f9b698455c4ffd82463b4",
+    },
+    LocalModel {
+        id: "large-v3-turbo",
+        file_name: "ggml-large-v3-turbo.bin",
+        size_mb: 1500,
+        sha1: "4af2b29d7ec73d781377bfd1758ca957a807e941",
+    },
+    LocalModel {
+        id: "large-v3",
+        file_name: "ggml-large-v3.bin",
+        size_mb: 2900,
+        sha1: "ad82bf6a9043ceed055076d0fd39f5f186ff8062",
+    },
+];
+
+/// Model status returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalModelStatus {
+    pub id: String,
+    pub size_mb: u32,
+    pub downloaded: bool,
+}
+
+/// Download progress event payload (`local-model-progress`)
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelDownloadProgress {
+    pub id: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+}
+
+/// Look up a model in the catalog
+fn find_model(id: &str) -> Result<&'static LocalModel, String> {
+    LOCAL_MODELS
+        .iter()
+        .find(|m| m.id == id)
+        .ok_or_else(|| format!("Unknown local model: {}", id))
+}
+
+/// Get the models directory: `app_data_dir/models/`
+///
+/// Uses the same data-dir + bundle-id layout as `logging.rs` so it can be
+/// resolved without an `AppHandle`.
+pub fn models_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("com.ttp.desktop").join("models"))
+}
+
+/// Path of a model file on disk (whether or not it has been downloaded)
+pub fn model_path(id: &str) -> Result<PathBuf, String> {
+    let model = find_model(id)?;
+    let dir = models_dir().ok_or("Could not determine app data directory")?;
+    Ok(dir.join(model.file_name))
+}
+
+/// Temporary file a model is downloaded into before its checksum is verified
+fn part_path(final_path: &Path) -> PathBuf {
+    final_path.with_extension("bin.part")
+}
+
+/// Whether a model has been downloaded
+pub fn is_model_downloaded(id: &str) -> bool {
+    model_path(id).map(|p| p.exists()).unwrap_or(false)
+}
+
+/// Compute the SHA-1 of a file as lowercase hex
+fn sha1_file(path: &PathBuf) -> Result<String, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open model: {}", e))?;
+    let mut hasher = Sha1::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read model: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// List all known models with their download state
+#[tauri::command]
+pub fn list_local_models() -> Vec<LocalModelStatus> {
+    LOCAL_MODELS
+        .iter()
+        .map(|m| LocalModelStatus {
+            id: m.id.to_string(),
+            size_mb: m.size_mb,
+            downloaded: is_model_downloaded(m.id),
+        })
+        .collect()
+}
+
+/// Marks a model id as downloading until dropped
+struct DownloadSlot(String);
+
+impl DownloadSlot {
+    /// Claim `id`, or fail if another download of it is running
+    fn claim(id: &str) -> Result<Self, String> {
+        let mut guard = DOWNLOADS_IN_PROGRESS.lock().unwrap_or_else(|e| e.into_inner());
+        if !guard.get_or_insert_with(HashSet::new).insert(id.to_string()) {
+            return Err(format!("Model {} is already downloading", id));
+        }
+        Ok(Self(id.to_string()))
+    }
+}
+
+impl Drop for DownloadSlot {
+    fn drop(&mut self) {
+        let mut guard = DOWNLOADS_IN_PROGRESS.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(ids) = guard.as_mut() {
+            ids.remove(&self.0);
+        }
+    }
+}
+
+/// Download a model once, verifying its checksum before it becomes usable
+///
+/// Streams into a `.part` file, hashing as it goes, and only renames it to
+/// the final name when the SHA-1 matches; the `.part` file is removed on any
+/// failure. Only one download per model runs at a time. Emits
+/// `local-model-progress`.
+#[tauri::command]
+pub async fn download_local_model(app: AppHandle, id: String) -> Result<(), String> {
+    let model = find_model(&id)?;
+    let final_path = model_path(&id)?;
+    if final_path.exists() {
+        return Ok(());
+    }
+    let _slot = DownloadSlot::claim(&id)?;
+
+    let dir = models_dir().ok_or("Could not determine app data directory")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create models dir: {}", e))?;
+    let part_path = part_path(&final_path);
+
+    let result = fetch_model(&app, model, &part_path)
+        .await
+        .and_then(|(digest, downloaded_bytes)| {
+            if digest != model.sha1 {
+                log_error(&format!(
+                    "Checksum mismatch for model {}: expected {}, got {}",
+                    id, model.sha1, digest
+                ));
+                return Err("Downloaded model is corrupt (checksum mismatch)".to_string());
+            }
+            std::fs::rename(&part_path, &final_path)
+                .map_err(|e| format!("Failed to install model: {}", e))?;
+            Ok(downloaded_bytes)
+        });
+
+    match result {
+        Ok(downloaded_bytes) => {
+            log_info(&format!("Downloaded local model {} ({} bytes)", id, downloaded_bytes));
+            Ok(())
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&part_path);
+            Err(e)
+        }
+    }
+}
+
+/// Stream a model into `part_path`; returns its SHA-1 and size
+async fn fetch_model(app: &AppHandle, model: &LocalModel, part_path: &Path) -> Result<(String, u64), String> {
+    let url = format!("{}/{}", MODEL_BASE_URL, model.file_name);
+    let mut response = timeout(DOWNLOAD_CONNECT_TIMEOUT, crate::llm::client::shared_client().get(&url).send())
+        .await
+        .map_err(|_| "Model download failed: the server did not answer".to_string())?
+        .map_err(|e| format!("Model download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Model download failed: HTTP {}", response.status()));
+    }
+
+    let total_bytes = response.content_length();
+    let mut file = std::fs::File::create(part_path)
+        .map_err(|e| format!("Failed to create model file: {}", e))?;
+    let mut hasher = Sha1::new();
+    let mut downloaded_bytes = 0u64;
+    let mut last_emitted = 0u64;
+
+    while let Some(chunk) = timeout(DOWNLOAD_READ_TIMEOUT, response.chunk())
+        .await
+        .map_err(|_| "Model download stalled".to_string())?
+        .map_err(|e| format!("Model download interrupted: {}", e))?
+    {
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to write model file: {}", e))?;
+        downloaded_bytes += chunk.len() as u64;
+
+        // Throttle progress events to one per ~4MB
+        if downloaded_bytes - last_emitted >= 4 * 1024 * 1024 {
+            last_emitted = downloaded_bytes;
+            let _ = app.emit(
+                "local-model-progress",
+                ModelDownloadProgress {
+                    id: model.id.to_string(),
+                    downloaded_bytes,
+                    total_bytes,
+                },
+            );
+        }
+    }
+
+    Ok((to_hex(&hasher.finalize()), downloaded_bytes))
+}
+
+/// Re-check a downloaded model against its published checksum
+#[tauri::command]
+pub async fn verify_local_model(id: String) -> Result<bool, String> {
+    let model = find_model(&id)?;
+    let path = model_path(&id)?;
+    if !path.exists() {
+        return Ok(false);
+    }
+    let digest = tokio::task::spawn_blocking(move || sha1_file(&path))
+        .await
+        .map_err(|e| format!("Verification task failed: {}", e))??;
+    Ok(digest == model.sha1)
+}
+
+/// Delete a downloaded model
+#[tauri::command]
+pub fn delete_local_model(id: String) -> Result<(), String> {
+    let path = model_path(&id)?;
+    unload_model();
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to delete model: {}", e))?;
+    }
+    Ok(())
+}
+
+/// On-device whisper.cpp transcription
+pub struct LocalWhisperProvider {
+    model_id: String,
+}
+
+impl LocalWhisperProvider {
+    pub fn new(model_id: &str) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+        }
+    }
+}
+
+impl TranscriptionProvider for LocalWhisperProvider {
+    fn name(&self) -> &str {
+        "Local"
+    }
+
+    fn transcribe(
+        &self,
+        request: &TranscriptionRequest<'_>,
+    ) -> impl Future<Output = Result<String, PipelineError>> + Send {
+        let model_id = self.model_id.clone();
+        let audio_path = request.audio_path.to_string();
+        let prompt = request.prompt.map(|p| p.to_string());
+        let language = request.language.map(|l| l.to_lowercase());
+        let translate = request.task == TranscriptionTask::TranslateToEnglish;
+
+        async move {
+            let model_path = model_path(&model_id).map_err(PipelineError::LocalEngine)?;
+            if !model_path.exists() {
+                return Err(PipelineError::LocalEngine(format!(
+                    "Local model '{}' is not downloaded",
+                    model_id
+                )));
+            }
+
+            // Inference is CPU-bound — keep it off the async runtime threads
+            tokio::task::spawn_blocking(move || {
+                let samples = read_wav_f32(&audio_path)?;
+                engine::transcribe(&model_path, &samples, prompt.as_deref(), language.as_deref(), translate)
+            })
+            .await
+            .map_err(|e| PipelineError::LocalEngine(format!("Local transcription task failed: {}", e)))?
+            .map_err(PipelineError::LocalEngine)
+        }
+    }
+}
+
+/// Read a mono 16kHz 16-bit WAV as f32 samples in [-1, 1]
+fn read_wav_f32(path: &str) -> Result<Vec<f32>, String> {
+    let reader = hound::WavReader::open(path).map_err(|e| format!("Failed to read WAV: {}", e))?;
+    let spec = reader.spec();
+    if spec.channels != 1 || spec.sample_rate != 16_000 {
+        return Err("Local transcription requires mono 16kHz audio".to_string());
+    }
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Int => reader
+            .into_samples::<i16>()
+            .map(|s| s.unwrap_or(0) as f32 / 32768.0)
+            .collect(),
+        hound::SampleFormat::Float => reader
+            .into_samples::<f32>()
+            .map(|s| s.unwrap_or(0.0))
+            .collect(),
+    };
+    Ok(samples)
+}
+
+/// Drop the cached whisper context (e.g. before deleting its model file)
+pub fn unload_model() {
+    engine::unload();
+}
+
+#[cfg(feature = "local-whisper")]
+mod engine {
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+    use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+    /// Loaded model, kept between dictations (loading large models takes seconds)
+    static CONTEXT: Mutex<Option<(PathBuf, WhisperContext)>> = Mutex::new(None);
+
+    pub fn transcribe(
+        model_path: &Path,
+        samples: &[f32],
+        prompt: Option<&str>,
+        language: Option<&str>,
+        translate: bool,
+    ) -> Result<String, String> {
+        let mut guard = CONTEXT.lock().map_err(|_| "Local model lock poisoned".to_string())?;
+
+        let loaded = matches!(guard.as_ref(), Some((p, _)) if p == model_path);
+        if !loaded {
+            let path_str = model_path.to_str().ok_or("Invalid model path")?;
+            let ctx = WhisperContext::new_with_params(path_str, WhisperContextParameters::default())
+                .map_err(|e| format!("Failed to load local model: {}", e))?;
+            *guard = Some((model_path.to_path_buf(), ctx));
+        }
+        let (_, ctx) = guard.as_ref().ok_or("Local model not loaded")?;
+
+        let mut state = ctx
+            .create_state()
+            .map_err(|e| format!("Failed to create whisper state: {}", e))?;
+
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get().min(8) as i32)
+            .unwrap_or(4);
+        params.set_n_threads(threads);
+        params.set_language(Some(language.unwrap_or("auto")));
+        params.set_translate(translate);
+        params.set_no_context(true);
+        params.set_suppress_blank(true);
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        if let Some(prompt) = prompt {
+            params.set_initial_prompt(prompt);
+        }
+
+        state
+            .full(params, samples)
+            .map_err(|e| format!("Local transcription failed: {}", e))?;
+
+        let segments = state
+            .full_n_segments()
+            .map_err(|e| format!("Failed to read segments: {}", e))?;
+        let mut text = String::new();
+        for i in 0..segments {
+            let segment = state
+                .full_get_segment_text(i)
+                .map_err(|e| format!("Failed to read segment: {}", e))?;
+            text.push_str(&segment);
+        }
+
+        Ok(text.trim().to_string())
+    }
+
+    pub fn unload() {
+        if let Ok(mut guard) = CONTEXT.lock() {
+            *guard = None;
+        }
+    }
+}
+
+#[cfg(not(feature = "local-whisper"))]
+mod engine {
+    use std::path::Path;
+
+    pub fn transcribe(
+        _model_path: &Path,
+        _samples: &[f32],
+        _prompt: Option<&str>,
+        _language: Option<&str>,
+        _translate: bool,
+    ) -> Result<String, String> {
+        Err("Local transcription is not available in this build".to_string())
+    }
+
+    pub fn unload() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_ids_and_checksums_are_well_formed() {
+        for (i, model) in LOCAL_MODELS.iter().enumerate() {
+            assert_eq!(find_model(model.id).unwrap().file_name, model.file_name);
+            assert!(LOCAL_MODELS[i + 1..].iter().all(|m| m.id != model.id), "duplicate {}", model.id);
+            assert_eq!(model.sha1.len(), 40);
+            assert!(model.sha1.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+        }
+        assert!(find_model("huge").is_err());
+    }
+
+    #[test]
+    fn hex_is_lowercase_and_zero_padded() {
+        assert_eq!(to_hex(&[0x00, 0x0a, 0xab, 0xff]), "000aabff");
+        assert_eq!(to_hex(&[]), "");
+    }
+
+    #[test]
+    fn model_paths() {
+        let Some(dir) = models_dir() else { return };
+        let path = model_path("base").unwrap();
+        assert_eq!(path, dir.join("ggml-base.bin"));
+        assert_eq!(part_path(&path), dir.join("ggml-base.bin.part"));
+        assert!(model_path("huge").is_err());
+    }
+
+    #[test]
+    fn one_download_per_model() {
+        let slot = DownloadSlot::claim("test-model").unwrap();
+        assert!(DownloadSlot::claim("test-model").is_err());
+        assert!(DownloadSlot::claim("test-other").is_ok());
+        drop(slot);
+        assert!(DownloadSlot::claim("test-model").is_ok());
+    }
+}
@@ -1,12 +1,29 @@
 // TTP - Talk To Paste
-// Transcription module - Groq Whisper + AI polish
+// Transcription module - pluggable speech-to-text providers + AI polish
 
+pub mod chunking;
+pub mod commands;
 pub mod convert;
+pub mod deepgram;
+pub mod edit;
+pub mod encode;
+pub mod error;
+pub mod hallucination;
+pub mod local;
 pub mod pipeline;
 pub mod polish;
+pub mod preprocess;
+pub mod provider;
+pub mod rerun;
+pub mod retry;
+pub mod streaming;
+pub mod styles;
+pub mod translate;
+pub mod vad;
 pub mod whisper;
 pub mod backup;
 
+pub use error::PipelineError;
 pub use pipeline::{process_audio, process_recording};
 pub use polish::polish_text;
-pub use whisper::transcribe_audio;
+pub use provider::transcribe_audio;
@@ -4,69 +4,29 @@
 // This module ties together the recording completion with transcription,
 // text polishing, and auto-paste functionality.
 
-use crate::credentials::get_groq_api_key_internal;
+use crate::credentials::get_provider_api_key_internal;
 use crate::dictionary::detection::start_correction_window;
-use crate::dictionary::apply_dictionary;
+use crate::dictionary::{apply_dictionary, MatchMode};
 use crate::history::add_history_entry;
-use crate::paste::{check_accessibility, simulate_paste, ClipboardGuard};
+use crate::paste::{check_accessibility, read_selection, simulate_enter, simulate_paste, ClipboardGuard};
 // Pill stays visible - no hide needed
-use crate::settings::get_settings;
+use crate::settings::{get_settings, AfterPaste, PipelineMode, Settings, TranscriptionMode, TranscriptionProviderKind, TranscriptionSettings};
 use crate::state::{AppState, RecordingState};
 use std::sync::Mutex;
 use std::time::Duration;
 use std::path::Path;
 use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_notification::NotificationExt;
 use tokio::time::sleep;
 
-/// Maximum audio file size in bytes (25MB Groq API limit)
-const MAX_AUDIO_SIZE: u64 = 25_000_000;
-
-/// Common Whisper hallucinations on silent/empty audio
-const HALLUCINATIONS: &[&str] = &[
-    // English
-    "thank you",
-    "thanks for watching",
-    "thank you for watching",
-    "thanks for listening",
-    "thanks for watching please subscribe",
-    "bye",
-    "goodbye",
-    "see you",
-    "subscribe",
-    "like and subscribe",
-    "you",
-    "the end",
-    "so",
-    "the",
-    "oh",
-    "okay",
-    "uh",
-    "i'm sorry",
-    "hello everyone welcome to my channel",
-    // French
-    "merci d'avoir regarde cette video",
-    "je vous remercie",
-    // Subtitle attribution hallucinations (all languages)
-    "subtitles by the amara org community",
-    "sous-titres realises par la communaute d'amara.org",
-    "sous-titrage st' 501",
-    "transcription by castingwords",
-    ".",
-    "",
-];
-
-/// Substrings that indicate a hallucination (partial match)
-const HALLUCINATION_SUBSTRINGS: &[&str] = &[
-    "amara.org",
-    "sous-titr",
-    "subtitles by",
-    "transcription by",
-    "soustitreur.com",
-    "www.mooji.org",
-];
-
-use crate::logging::log_error;
+use crate::logging::{log_error, log_info};
+use super::error::PipelineError;
+use super::chunking::{remove_chunks, split_on_silence, transcribe_chunks};
+use super::commands::apply_voice_commands;
+use super::encode::AudioFormat;
+use super::hallucination::detect_hallucination;
+use super::provider::{upload_format, TranscriptionTask, MAX_AUDIO_SIZE};
 use super::{convert::convert_to_mono_16khz, polish_text, transcribe_audio};
 
 /// Progress event sent to frontend during transcription pipeline
@@ -123,20 +83,215 @@ fn set_state(app: &AppHandle, state: RecordingState) {
     }
 }
 
-/// Filter out common Whisper hallucinations
-fn is_hallucination(text: &str) -> bool {
-    let lower = text.trim().to_lowercase();
-    // Exact match (with/without trailing period)
-    let exact = HALLUCINATIONS
-        .iter()
-        .any(|h| lower == *h || lower.trim_end_matches('.') == *h);
-    if exact {
-        return true;
+/// Report a pipeline failure: log it, show it in the pill, track it, go back to Idle
+fn report_failure(app: &AppHandle, error: &PipelineError, started: std::time::Instant) {
+    match error {
+        PipelineError::NoSpeech | PipelineError::Hallucination(_) | PipelineError::NoSelection => {
+            log_info(&error.to_string())
+        }
+        _ => log_error(&error.to_string()),
+    }
+    emit_progress(app, "error", &error.user_message());
+    crate::telemetry::analytics::track(app, "transcription_failed", Some(serde_json::json!({
+        "error_category": error.category(),
+        "duration_seconds": started.elapsed().as_secs_f64()
+    })));
+    set_state(app, RecordingState::Idle);
+}
+
+/// Whisper prompt biasing transcription toward the spoken language and
+/// dictionary corrections
+pub(super) fn whisper_prompt(language: Option<&str>) -> Option<String> {
+    // Language hint helps Whisper keep to the expected language(s)
+    let mut prompt = crate::language::whisper_hint(language).unwrap_or_default().to_string();
+
+    let entries = crate::dictionary::active_entries(language);
+    if !entries.is_empty() {
+        // Collect unique correction values (regex replacements aren't words)
+        let mut corrections: Vec<String> = entries
+            .iter()
+            .filter(|e| e.match_mode != MatchMode::Regex)
+            .map(|e| e.correction.clone())
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+            .collect();
+        corrections.sort();
+
+        // Append glossary, staying under ~200 tokens (~800 chars conservative estimate)
+        if !prompt.is_empty() {
+            prompt.push(' ');
+        }
+        prompt.push_str("Glossary: ");
+        let mut first = true;
+        for word in &corrections {
+            let addition = if first {
+                word.len()
+            } else {
+                2 + word.len() // ", " + word
+            };
+            if prompt.len() + addition > 800 {
+                break;
+            }
+            if !first {
+                prompt.push_str(", ");
+            }
+            prompt.push_str(word);
+            first = false;
+        }
+    }
+
+    (!prompt.is_empty()).then_some(prompt)
+}
+
+/// Transcribe a converted file outside the live pipeline (retries, re-runs),
+/// splitting it on silence when it is over the upload limit
+pub(super) async fn transcribe_file(
+    settings: &TranscriptionSettings,
+    api_key: Option<&str>,
+    path: &str,
+    prompt: Option<&str>,
+    task: TranscriptionTask,
+) -> Result<String, PipelineError> {
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let ratio = if settings.mode == TranscriptionMode::Local {
+        AudioFormat::Wav.size_ratio()
+    } else {
+        upload_format(settings).size_ratio()
+    };
+    if (size as f64 * ratio) as u64 <= MAX_AUDIO_SIZE {
+        return transcribe_or_split(settings, api_key, path, prompt, task).await;
+    }
+
+    transcribe_split(settings, api_key, path, prompt, task).await
+}
+
+/// Transcribe a file whose estimated upload fits, splitting it on silence
+/// when the encoded file turns out to be over the limit after all
+async fn transcribe_or_split(
+    settings: &TranscriptionSettings,
+    api_key: Option<&str>,
+    path: &str,
+    prompt: Option<&str>,
+    task: TranscriptionTask,
+) -> Result<String, PipelineError> {
+    match transcribe_audio(settings, api_key, path, prompt, task).await {
+        Err(PipelineError::PayloadTooLarge) => {
+            log_info("Encoded upload over the limit, splitting on silence");
+            transcribe_split(settings, api_key, path, prompt, task).await
+        }
+        result => result,
+    }
+}
+
+/// Split a file on silence and transcribe the chunks
+async fn transcribe_split(
+    settings: &TranscriptionSettings,
+    api_key: Option<&str>,
+    path: &str,
+    prompt: Option<&str>,
+    task: TranscriptionTask,
+) -> Result<String, PipelineError> {
+    let chunks = split_on_silence(path)?;
+    let result = transcribe_chunks(settings, api_key, &chunks, prompt, task).await;
+    remove_chunks(&chunks);
+    result
+}
+
+/// Deterministic post-processing of the final text: spoken formatting
+/// commands (when enabled), then dictionary corrections
+///
+/// Only dictations get the commands: translations and edits are LLM output,
+/// which has no spoken commands left to apply. Dictionary entries are scoped
+/// to the language of the text: the target language for translations.
+pub(super) fn finish_text(settings: &Settings, text: &str, mode: PipelineMode) -> String {
+    let language = settings.transcription.language();
+    let text_language = match mode {
+        PipelineMode::Translate => Some(settings.translation.target_language.as_str()),
+        PipelineMode::Dictate | PipelineMode::Edit => language,
+    };
+    if !settings.voice_commands {
+        return apply_dictionary(text, text_language);
+    }
+    match mode {
+        PipelineMode::Dictate => apply_dictionary(&apply_voice_commands(text, language), text_language),
+        PipelineMode::Translate | PipelineMode::Edit => apply_dictionary(text, text_language),
+    }
+}
+
+/// Expanded text of the snippet triggered by the whole transcript, if any
+fn expand_matching_snippet(app: &AppHandle, transcript: &str) -> Option<String> {
+    let snippets = crate::snippets::get_snippets();
+    let snippet = crate::snippets::match_snippet(transcript, &snippets)?;
+    log_info(&format!("Snippet \"{}\" triggered", snippet.trigger));
+
+    // Read the clipboard only when the snippet uses it, before the paste overwrites it
+    let clipboard = if snippet.text.contains("{clipboard}") {
+        app.clipboard().read_text().ok()
+    } else {
+        None
+    };
+    Some(crate::snippets::expand_snippet(&snippet.text, chrono::Local::now(), clipboard.as_deref()))
+}
+
+/// Polish a transcript in a style, falling back to the raw text when the LLM
+/// fails or answers with something other than the cleaned text
+pub(super) async fn polish_transcript(
+    config: &crate::llm::LlmConfig,
+    raw_text: &str,
+    style: &str,
+    language: Option<&str>,
+) -> String {
+    match polish_text(config, raw_text, style, language).await {
+        Ok(text) => {
+            // Detect LLM help responses (happens when input is too minimal)
+            let lower = text.to_lowercase();
+            let is_llm_help = lower.contains("i'm here to help")
+                || lower.contains("please provide")
+                || lower.contains("i can help")
+                || lower.contains("could you please")
+                || lower.contains("i'm sorry")
+                || lower.contains("i can only process")
+                || lower.contains("feel free to share")
+                || lower.contains("if you have a")
+                || lower.contains("transcription you'd like")
+                || lower.contains("it seems like")
+                || lower.contains("i'd be happy to");
+
+            // Detect LLM showing "original → corrected" comparison format
+            let is_comparison = lower.contains("version corrigée")
+                || lower.contains("corrected version")
+                || lower.contains("cleaned version")
+                || lower.contains("here is the")
+                || lower.contains("voici la version")
+                || lower.contains("voici le texte")
+                || lower.contains("original:")
+                || lower.contains("corrected:")
+                || lower.contains("original text")
+                || lower.contains("cleaned text")
+                || (lower.contains("→") && lower.contains("\""));
+
+            // Also suspect if output is much longer than input (LLM adding content)
+            let length_ratio = text.len() as f32 / raw_text.len().max(1) as f32;
+            let is_too_long = length_ratio > 3.0 && text.len() > 50;
+
+            if is_llm_help || is_too_long {
+                eprintln!("[Pipeline] LLM returned suspicious response, using raw text");
+                raw_text.to_string()
+            } else if is_comparison {
+                // LLM returned a comparison format — try to extract just the cleaned part
+                // If the response starts with quotes or a label, strip it
+                let cleaned = strip_llm_wrapper(&text);
+                eprintln!("[Pipeline] LLM returned comparison format, extracted: {}", &cleaned[..cleaned.len().min(80)]);
+                cleaned
+            } else {
+                text
+            }
+        }
+        Err(e) => {
+            crate::logging::log_warn(&format!("Polish failed, using raw text: {}", e));
+            raw_text.to_string()
+        }
     }
-    // Substring match for common attribution/credit hallucinations
-    HALLUCINATION_SUBSTRINGS
-        .iter()
-        .any(|sub| lower.contains(sub))
 }
 
 /// Strip LLM wrapper/comparison format from polish output
@@ -192,12 +347,18 @@ fn strip_llm_wrapper(text: &str) -> String {
 /// Main pipeline function: process a completed recording
 ///
 /// Orchestrates the flow:
-/// 1. Transcribe audio via Groq Whisper
-/// 2. Polish text via Groq LLM (llama-3.3-70b-versatile)
+/// 1. Transcribe audio via the configured provider (Groq Whisper by default)
+/// 2. Polish text via the configured LLM (Groq llama-3.3-70b-versatile by default),
+///    translate it in `PipelineMode::Translate`, or apply it as an instruction
+///    to the selected text in `PipelineMode::Edit`
 /// 3. Paste into active app (or clipboard fallback)
 ///
 /// Emits progress events throughout for frontend updates.
-pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<String, String> {
+pub async fn process_recording(
+    app: &AppHandle,
+    audio_path: String,
+    mode: PipelineMode,
+) -> Result<String, PipelineError> {
     let pipeline_start = std::time::Instant::now();
 
     // Set state to Processing
@@ -208,85 +369,133 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
     let file_size = match std::fs::metadata(audio_file) {
         Ok(meta) => meta.len(),
         Err(e) => {
-            emit_progress(app, "error", "Audio file not found");
-            crate::telemetry::analytics::track(app, "transcription_failed", Some(serde_json::json!({"error_category": "api_error", "duration_seconds": pipeline_start.elapsed().as_secs_f64()})));
-            set_state(app, RecordingState::Idle);
-            return Err(format!("Audio file error: {}", e));
+            let error = PipelineError::AudioNotFound(e.to_string());
+            report_failure(app, &error, pipeline_start);
+            return Err(error);
         }
     };
 
     // AUDI-04: Validate WAV header before any processing
-    if let Err(msg) = super::backup::validate_wav(&audio_path) {
+    if let Err(error) = super::backup::validate_wav(&audio_path) {
         let _ = std::fs::remove_file(&audio_path);
-        emit_progress(app, "error", &msg);
-        crate::telemetry::analytics::track(app, "transcription_failed", Some(serde_json::json!({
-            "error_category": "corrupt_audio",
-            "duration_seconds": pipeline_start.elapsed().as_secs_f64()
-        })));
-        set_state(app, RecordingState::Idle);
-        return Err(msg);
+        report_failure(app, &error, pipeline_start);
+        return Err(error);
+    }
+
+    // Command mode: read the selection first, before anything can move the focus
+    let selection = if mode == PipelineMode::Edit {
+        let app_handle = app.clone();
+        match tokio::task::spawn_blocking(move || read_selection(&app_handle)).await {
+            Ok(Some(text)) => Some(text),
+            _ => {
+                let _ = std::fs::remove_file(&audio_path);
+                report_failure(app, &PipelineError::NoSelection, pipeline_start);
+                return Err(PipelineError::NoSelection);
+            }
+        }
+    } else {
+        None
+    };
+
+    // Voice activity detection: drop silent recordings before any API call,
+    // trim leading/trailing silence from the rest
+    let (speech, speech_path) = match super::vad::trim_silence(&audio_path) {
+        Ok((report, path)) => (Some(report), path),
+        Err(e) => {
+            crate::logging::log_warn(&format!("VAD failed, sending untrimmed audio: {}", e));
+            (None, audio_path.clone())
+        }
+    };
+    if let Some(report) = speech {
+        if !report.has_speech() {
+            let _ = std::fs::remove_file(&audio_path);
+            report_failure(app, &PipelineError::NoSpeech, pipeline_start);
+            return Err(PipelineError::NoSpeech);
+        }
+        log_info(&format!(
+            "VAD: {:.1}s speech in {:.1}s recording (speech {:.1}s-{:.1}s)",
+            report.speech_secs, report.total_secs, report.start_secs, report.end_secs
+        ));
     }
 
-    // Convert stereo 48kHz WAV → mono 16kHz WAV (reduces size ~6x)
-    let converted_path = match convert_to_mono_16khz(&audio_path) {
+    // Load settings, with the frontmost app's profile applied
+    let mut settings = get_settings();
+    let global_language = settings.transcription.language().map(str::to_string);
+    let mut behavior = crate::profiles::apply_frontmost_profile(&mut settings);
+    if mode == PipelineMode::Edit {
+        // The result replaces the selection as is, and isn't dictated text to learn from
+        behavior.after_paste = AfterPaste::Nothing;
+        behavior.detect_corrections = false;
+    }
+
+    // Translation mode: Whisper translates during transcription when it can,
+    // otherwise the LLM translates the transcript afterwards
+    let whisper_translates = mode == PipelineMode::Translate && super::translate::whisper_translates(&settings);
+    let task = if whisper_translates {
+        TranscriptionTask::TranslateToEnglish
+    } else {
+        TranscriptionTask::Transcribe
+    };
+
+    // Convert stereo 48kHz WAV → mono 16kHz WAV (reduces size ~6x),
+    // running the optional cleanup stages on the way
+    let converted_path = match convert_to_mono_16khz(&speech_path, &settings.transcription.preprocessing) {
         Ok(path) => path,
         Err(e) => {
             eprintln!("[Pipeline] Conversion failed: {} — sending original", e);
-            audio_path.clone()
+            speech_path.clone()
         }
     };
+    // The trimmed copy is only an intermediate once a converted file exists
+    if speech_path != audio_path && converted_path != speech_path {
+        let _ = std::fs::remove_file(&speech_path);
+    }
     let use_converted = converted_path != audio_path;
 
     let final_upload_path = converted_path.clone();
 
-    // AUDI-05: If conversion failed and original exceeds 25MB, reject early
-    if !use_converted && file_size > MAX_AUDIO_SIZE {
-        let original_mb = file_size as f64 / 1_000_000.0;
-        let _ = std::fs::remove_file(&audio_path);
-        emit_progress(
-            app,
-            "error",
-            &format!("Recording too long ({:.0}MB). Max ~14 min.", original_mb),
-        );
-        notify(app, "Recording too long -- max ~14 minutes");
-        crate::telemetry::analytics::track(app, "transcription_failed", Some(serde_json::json!({
-            "error_category": "too_long",
-            "duration_seconds": pipeline_start.elapsed().as_secs_f64()
-        })));
-        set_state(app, RecordingState::Idle);
-        log_error(&format!("Conversion failed and original too large: {:.1}MB exceeds {}MB limit", original_mb, MAX_AUDIO_SIZE / 1_000_000));
-        return Err(format!("Audio too large: {:.1}MB exceeds API limit", original_mb));
-    }
-
     // Check size AFTER conversion (raw WAV can be large but converts down)
     let final_size = std::fs::metadata(&final_upload_path)
         .map(|m| m.len())
         .unwrap_or(file_size);
     let final_mb = final_size as f64 / 1_000_000.0;
 
-    if final_size > MAX_AUDIO_SIZE {
-        let _ = std::fs::remove_file(&audio_path);
-        if use_converted { let _ = std::fs::remove_file(&converted_path); }
-
-
-        emit_progress(
-            app,
-            "error",
-            &format!("Recording too long ({:.0}MB). Max ~14 min.", final_mb),
-        );
-        notify(app, "Recording too long — max ~14 minutes");
-        crate::telemetry::analytics::track(app, "transcription_failed", Some(serde_json::json!({"error_category": "too_long", "duration_seconds": pipeline_start.elapsed().as_secs_f64()})));
-        set_state(app, RecordingState::Idle);
-        log_error(&format!("Audio too large after conversion: {:.1}MB exceeds {}MB limit", final_mb, MAX_AUDIO_SIZE / 1_000_000));
-        return Err(format!("Audio too large: {:.1}MB exceeds API limit", final_mb));
-    }
+    // Cloud uploads are encoded (FLAC/Opus) first, so the limit applies to the encoded size
+    let upload_as = if settings.transcription.mode == TranscriptionMode::Local {
+        AudioFormat::Wav
+    } else {
+        upload_format(&settings.transcription)
+    };
+    let estimated_upload_size = (final_size as f64 * upload_as.size_ratio()) as u64;
+
+    // AUDI-05: Over the API limit — split on silence and transcribe the pieces
+    let chunks = if estimated_upload_size > MAX_AUDIO_SIZE {
+        match split_on_silence(&final_upload_path) {
+            Ok(chunks) => {
+                log_info(&format!(
+                    "Audio {:.1}MB ({:?} upload) exceeds {}MB limit, split into {} chunks",
+                    final_mb,
+                    upload_as,
+                    MAX_AUDIO_SIZE / 1_000_000,
+                    chunks.len()
+                ));
+                chunks
+            }
+            Err(error) => {
+                let _ = std::fs::remove_file(&audio_path);
+                if use_converted { let _ = std::fs::remove_file(&converted_path); }
+                notify(app, "Recording too long");
+                report_failure(app, &error, pipeline_start);
+                return Err(error);
+            }
+        }
+    } else {
+        Vec::new()
+    };
 
     // Use the converted mono 16kHz WAV for transcription
     let transcription_path = &final_upload_path;
 
-    // Load settings
-    let settings = get_settings();
-
     // Read input mode from app state for analytics
     let input_mode = if let Some(state) = app.try_state::<Mutex<AppState>>() {
         if let Ok(guard) = state.try_lock() {
@@ -298,23 +507,28 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
         "unknown"
     };
 
-    // Get Groq API key
-    let groq_key = get_groq_api_key_internal(app)?.filter(|k| !k.is_empty());
+    // Resolve the API key for the configured transcription provider
+    let provider_kind = settings.transcription.provider;
+    let transcription_key = get_provider_api_key_internal(provider_kind.into());
 
-    let api_key = match groq_key {
-        Some(key) => key,
-        None => {
-            let _ = std::fs::remove_file(&audio_path);
-            if use_converted { let _ = std::fs::remove_file(&converted_path); }
-            emit_progress(app, "error", "No Groq API key configured");
+    // On-device modes can run without any key (fallback just fails if it's missing)
+    let needs_cloud_key = settings.transcription.mode == TranscriptionMode::Cloud
+        && super::provider::requires_api_key(provider_kind);
+
+    if transcription_key.is_none() && needs_cloud_key {
+        let _ = std::fs::remove_file(&audio_path);
+        if use_converted { let _ = std::fs::remove_file(&converted_path); }
+        remove_chunks(&chunks);
+        let error = PipelineError::MissingApiKey { provider: provider_kind.label().to_string() };
+        report_failure(app, &error, pipeline_start);
+        if provider_kind == TranscriptionProviderKind::Groq {
             if let Some(window) = app.get_webview_window("setup") {
                 let _ = window.show();
                 let _ = window.set_focus();
             }
-            set_state(app, RecordingState::Idle);
-            return Err("No Groq API key configured".to_string());
         }
-    };
+        return Err(error);
+    }
 
     // AUDI-01: Backup original audio before API call
     let backup_path = match super::backup::backup_audio(app, &audio_path) {
@@ -322,85 +536,88 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
         Err(e) => {
             crate::logging::log_warn(&format!("Audio backup failed: {}", e));
             crate::telemetry::analytics::track(app, "backup_failed", Some(serde_json::json!({
-                "error": e
+                "error": e.to_string()
             })));
             None // Continue without backup -- don't block transcription
         }
     };
 
     // Build Whisper prompt from dictionary corrections to bias transcription
-    let whisper_prompt = {
-        // Bilingual hint helps Whisper narrow language detection to FR/EN
-        let mut prompt = "French and English bilingual speaker.".to_string();
-
-        let entries = crate::dictionary::store::get_dictionary();
-        if !entries.is_empty() {
-            // Collect unique correction values
-            let mut corrections: Vec<String> = entries
-                .iter()
-                .map(|e| e.correction.clone())
-                .collect::<std::collections::HashSet<_>>()
-                .into_iter()
-                .collect();
-            corrections.sort();
-
-            // Append glossary, staying under ~200 tokens (~800 chars conservative estimate)
-            prompt.push_str(" Glossary: ");
-            let mut first = true;
-            for word in &corrections {
-                let addition = if first {
-                    word.len()
-                } else {
-                    2 + word.len() // ", " + word
-                };
-                if prompt.len() + addition > 800 {
-                    break;
-                }
-                if !first {
-                    prompt.push_str(", ");
-                }
-                prompt.push_str(word);
-                first = false;
-            }
-        }
+    let whisper_prompt = whisper_prompt(settings.transcription.language());
+
+    // Stage 1: Transcribe audio via the configured provider
+    emit_progress(app, "transcribing", "Transcribing...");
 
-        Some(prompt)
+    // Streaming mode already transcribed everything up to the last pause while recording
+    // (untranslated and in the global language, so Whisper translation and a
+    // profile language send the full recording instead)
+    if whisper_translates || settings.transcription.language() != global_language.as_deref() {
+        super::streaming::cancel();
+    }
+    let streamed = match super::streaming::finish().await {
+        Some(Ok(text)) => Some(text),
+        Some(Err(e)) => {
+            crate::logging::log_warn(&format!("Streaming transcription failed, transcribing full recording: {}", e));
+            None
+        }
+        None => None,
     };
 
-    // Stage 1: Transcribe audio via Groq Whisper
-    emit_progress(app, "transcribing", "Transcribing...");
+    let transcription = if let Some(text) = streamed {
+        Ok(text)
+    } else if chunks.is_empty() {
+        transcribe_or_split(
+            &settings.transcription,
+            transcription_key.as_deref(),
+            transcription_path,
+            whisper_prompt.as_deref(),
+            task,
+        )
+        .await
+    } else {
+        transcribe_chunks(
+            &settings.transcription,
+            transcription_key.as_deref(),
+            &chunks,
+            whisper_prompt.as_deref(),
+            task,
+        )
+        .await
+    };
+    remove_chunks(&chunks);
 
-    let raw_text = match transcribe_audio(&api_key, transcription_path, whisper_prompt.as_deref()).await {
+    let raw_text = match transcription {
         Ok(text) => text,
         Err(e) => {
-            // AUDI-02: Do NOT delete the original audio on API failure.
-            // The backup (and original) remain on disk so the user can inspect or retry.
-            log_error(&format!("Transcription failed: {}", e));
-            crate::logging::log_info(&format!(
-                "Audio preserved in backup after API failure: {}",
-                backup_path.as_ref().map(|p| p.display().to_string()).unwrap_or_else(|| "no backup".into())
-            ));
-
-            // Classify error for analytics
-            let error_category = if e.contains("413") || e.to_lowercase().contains("entity too large") || e.to_lowercase().contains("too long") {
-                "too_long"
-            } else if e.to_lowercase().contains("timeout") || e.to_lowercase().contains("connect") || e.to_lowercase().contains("network") || e.to_lowercase().contains("dns") {
-                "network"
-            } else {
-                "api_error"
-            };
+            // AUDI-02: When a retry could succeed (network, rate limit, bad key...)
+            // the backup goes to the retry queue, which owns it from then on.
+            // Otherwise (413, wrong model or URL, edits whose selection is gone)
+            // it is only kept: a configuration mistake must not cost the dictation,
+            // and `cleanup_stale_backups` removes it after 24h
+            let queued = e.is_retryable()
+                && mode != PipelineMode::Edit
+                && backup_path.as_ref().is_some_and(|bp| match super::retry::enqueue(app, bp, &e, mode) {
+                    Ok(()) => true,
+                    Err(err) => {
+                        crate::logging::log_warn(&format!("Failed to queue recording for retry: {}", err));
+                        false
+                    }
+                });
 
-            // User-friendly message for 413 / payload too large
-            let user_msg = if error_category == "too_long" {
-                "Recording too long — try a shorter recording".to_string()
+            if queued {
+                log_info("Recording queued for automatic retry after API failure");
+                let _ = std::fs::remove_file(&audio_path);
+                if use_converted { let _ = std::fs::remove_file(&converted_path); }
+                notify(app, "Transcription failed — it will be retried automatically");
             } else {
-                format!("Transcription failed: {}", e)
-            };
+                log_info(&format!(
+                    "Audio preserved in backup after API failure: {}",
+                    backup_path.as_ref().map(|p| p.display().to_string()).unwrap_or_else(|| "no backup".into())
+                ));
+                notify(app, "Transcription failed");
+            }
 
-            emit_progress(app, "error", &user_msg);
-            notify(app, "Transcription failed");
-            crate::telemetry::analytics::track(app, "transcription_failed", Some(serde_json::json!({"error_category": error_category, "duration_seconds": pipeline_start.elapsed().as_secs_f64()})));
-            set_state(app, RecordingState::Idle);
+            report_failure(app, &e, pipeline_start);
             return Err(e);
         }
     };
@@ -412,18 +629,19 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
 
 
         if let Some(ref bp) = backup_path { super::backup::remove_backup(bp); }
-        emit_progress(app, "error", "No speech detected");
         notify(app, "No speech detected");
-        crate::telemetry::analytics::track(app, "transcription_failed", Some(serde_json::json!({"error_category": "no_speech", "duration_seconds": pipeline_start.elapsed().as_secs_f64()})));
-        set_state(app, RecordingState::Idle);
-        return Err("No speech detected".to_string());
+        report_failure(app, &PipelineError::NoSpeech, pipeline_start);
+        return Err(PipelineError::NoSpeech);
     }
 
     // Filter out dictionary-induced hallucinations: if the entire transcription
     // is just 1-2 words that all appear in the dictionary, Whisper likely
     // hallucinated a glossary word on silence rather than transcribing real speech.
     {
-        let dict_entries = crate::dictionary::store::get_dictionary();
+        let dict_entries: Vec<_> = crate::dictionary::active_entries(settings.transcription.language())
+            .into_iter()
+            .filter(|e| e.match_mode != MatchMode::Regex)
+            .collect();
         if !dict_entries.is_empty() {
             let words: Vec<&str> = raw_text.trim().split_whitespace().collect();
             if words.len() <= 2 {
@@ -438,90 +656,103 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
                     let _ = std::fs::remove_file(&audio_path);
                     if use_converted { let _ = std::fs::remove_file(&converted_path); }
                     if let Some(ref bp) = backup_path { super::backup::remove_backup(bp); }
-                    emit_progress(app, "error", "No speech detected");
-                    crate::telemetry::analytics::track(app, "transcription_failed", Some(serde_json::json!({"error_category": "no_speech", "duration_seconds": pipeline_start.elapsed().as_secs_f64()})));
-                    set_state(app, RecordingState::Idle);
-                    return Err("No speech detected (glossary ghost)".to_string());
+                    let error = PipelineError::Hallucination("glossary ghost".to_string());
+                    report_failure(app, &error, pipeline_start);
+                    return Err(error);
                 }
             }
         }
     }
 
     // Filter out common Whisper hallucinations on silent audio
-    if is_hallucination(&raw_text) {
+    if let Some(rule) = detect_hallucination(&raw_text, settings.transcription.language()) {
         let _ = std::fs::remove_file(&audio_path);
         if use_converted { let _ = std::fs::remove_file(&converted_path); }
 
 
         if let Some(ref bp) = backup_path { super::backup::remove_backup(bp); }
-        emit_progress(app, "error", "No speech detected");
-        crate::telemetry::analytics::track(app, "transcription_failed", Some(serde_json::json!({"error_category": "no_speech", "duration_seconds": pipeline_start.elapsed().as_secs_f64()})));
-        set_state(app, RecordingState::Idle);
-        return Err("No speech detected (filtered)".to_string());
+        let error = PipelineError::Hallucination(rule.to_string());
+        report_failure(app, &error, pipeline_start);
+        return Err(error);
     }
 
-    // Stage 2: Polish text (if enabled)
-    let final_text = if settings.ai_polish_enabled {
-        emit_progress(app, "polishing", "Processing...");
+    // Snippets: a whole-utterance trigger pastes its stored text instead
+    let snippet_text = if mode == PipelineMode::Dictate {
+        expand_matching_snippet(app, &raw_text)
+    } else {
+        None
+    };
+    let snippet_used = snippet_text.is_some();
+    if snippet_used {
+        // Stored text, not dictation: nothing to learn corrections from
+        behavior.detect_corrections = false;
+    }
 
-        match polish_text(&api_key, &raw_text).await {
-            Ok(text) => {
-                // Detect LLM help responses (happens when input is too minimal)
-                let lower = text.to_lowercase();
-                let is_llm_help = lower.contains("i'm here to help")
-                    || lower.contains("please provide")
-                    || lower.contains("i can help")
-                    || lower.contains("could you please")
-                    || lower.contains("i'm sorry")
-                    || lower.contains("i can only process")
-                    || lower.contains("feel free to share")
-                    || lower.contains("if you have a")
-                    || lower.contains("transcription you'd like")
-                    || lower.contains("it seems like")
-                    || lower.contains("i'd be happy to");
-
-                // Detect LLM showing "original → corrected" comparison format
-                let is_comparison = lower.contains("version corrigée")
-                    || lower.contains("corrected version")
-                    || lower.contains("cleaned version")
-                    || lower.contains("here is the")
-                    || lower.contains("voici la version")
-                    || lower.contains("voici le texte")
-                    || lower.contains("original:")
-                    || lower.contains("corrected:")
-                    || lower.contains("original text")
-                    || lower.contains("cleaned text")
-                    || (lower.contains("→") && lower.contains("\""));
-
-                // Also suspect if output is much longer than input (LLM adding content)
-                let length_ratio = text.len() as f32 / raw_text.len().max(1) as f32;
-                let is_too_long = length_ratio > 3.0 && text.len() > 50;
-
-                if is_llm_help || is_too_long {
-                    eprintln!("[Pipeline] LLM returned suspicious response, using raw text");
-                    raw_text.clone()
-                } else if is_comparison {
-                    // LLM returned a comparison format — try to extract just the cleaned part
-                    // If the response starts with quotes or a label, strip it
-                    let cleaned = strip_llm_wrapper(&text);
-                    eprintln!("[Pipeline] LLM returned comparison format, extracted: {}", &cleaned[..cleaned.len().min(80)]);
-                    cleaned
-                } else {
-                    text
+    // Stage 2 (translation and command modes): the LLM rewrites the text in one call
+    let rewritten = if mode == PipelineMode::Translate && !whisper_translates {
+        emit_progress(app, "polishing", "Translating...");
+        match super::translate::translate_transcript(&settings, &raw_text).await {
+            Ok(text) => Some(text),
+            Err(e) => {
+                // Keep the transcript reachable: it is in history, the audio in the backup
+                if let Err(err) = add_history_entry(&raw_text, None, mode, false) {
+                    eprintln!("[Pipeline] Failed to save to history: {}", err);
                 }
+                let _ = std::fs::remove_file(&audio_path);
+                if use_converted { let _ = std::fs::remove_file(&converted_path); }
+                if let Some(ref bp) = backup_path { super::backup::remove_backup(bp); }
+                notify(app, "Translation failed — the transcript is in History");
+                report_failure(app, &e, pipeline_start);
+                return Err(e);
             }
+        }
+    } else if let Some(selection) = selection.as_deref() {
+        emit_progress(app, "polishing", "Editing...");
+        match super::edit::edit_selection(&settings, selection, &raw_text).await {
+            Ok(text) => Some(text),
             Err(e) => {
-                eprintln!("[Pipeline] Polish failed, using raw text: {}", e);
-                raw_text.clone()
+                let _ = std::fs::remove_file(&audio_path);
+                if use_converted { let _ = std::fs::remove_file(&converted_path); }
+                if let Some(ref bp) = backup_path { super::backup::remove_backup(bp); }
+                notify(app, "Edit failed — your selection is unchanged");
+                report_failure(app, &e, pipeline_start);
+                return Err(e);
             }
         }
+    } else {
+        None
+    };
+
+    // Stage 2: Polish text (if enabled)
+    let polish_llm = if settings.ai_polish_enabled && rewritten.is_none() && snippet_text.is_none() {
+        match crate::llm::resolve_task(&settings.polish_llm, crate::llm::LlmTask::Polish) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!("[Pipeline] Polish LLM unavailable, using raw text: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let final_text = if let Some(text) = rewritten {
+        text
+    } else if let Some(ref polish_config) = polish_llm {
+        emit_progress(app, "polishing", "Processing...");
+
+        polish_transcript(polish_config, &raw_text, &settings.polish_style, settings.transcription.language()).await
     } else {
         raw_text.clone()
     };
 
-    // Apply dictionary corrections as hard post-processing
-    // This guarantees dictionary entries are applied even if the LLM ignored them
-    let final_text = apply_dictionary(&final_text);
+    // Apply voice commands and dictionary corrections as hard post-processing
+    // This guarantees dictionary entries are applied even if the LLM ignored them.
+    // Snippet text is pasted exactly as stored.
+    let final_text = match snippet_text {
+        Some(text) => text,
+        None => finish_text(&settings, &final_text, mode),
+    };
 
     // Stage 3: Paste into active app
     emit_progress(app, "pasting", "");
@@ -530,18 +761,26 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
     let clipboard_guard = ClipboardGuard::new(app);
 
     // ALWAYS write to clipboard first (backup for manual paste)
-    if let Err(e) = clipboard_guard.write_text(&final_text) {
-        emit_progress(app, "error", "Failed to write to clipboard");
+    let paste_text = if behavior.after_paste == AfterPaste::Newline {
+        format!("{}\n", final_text)
+    } else {
+        final_text.clone()
+    };
+    if let Err(e) = clipboard_guard.write_text(&paste_text) {
+        let error = PipelineError::ClipboardFailed(e);
         notify(app, "Failed to copy text to clipboard");
-        set_state(app, RecordingState::Idle);
-        return Err(e);
+        report_failure(app, &error, pipeline_start);
+        return Err(error);
     }
 
     // Check accessibility permission and try to paste
-    let has_accessibility = check_accessibility();
+    let has_accessibility = behavior.auto_paste && check_accessibility();
 
     // Use spawn_blocking to run sync paste code safely in async context
-    let paste_success = if has_accessibility {
+    let paste_result: Result<(), PipelineError> = if !behavior.auto_paste {
+        // App profile without auto-paste: the text stays on the clipboard
+        Ok(())
+    } else if has_accessibility {
         let paste_result = tokio::task::spawn_blocking(|| {
             std::panic::catch_unwind(|| simulate_paste())
         })
@@ -552,28 +791,30 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
                 // Wait a bit for paste to complete before restoring clipboard
                 sleep(Duration::from_millis(150)).await;
 
+                if behavior.after_paste == AfterPaste::Enter {
+                    match tokio::task::spawn_blocking(simulate_enter).await {
+                        Ok(Ok(())) => {}
+                        Ok(Err(e)) => eprintln!("[Pipeline] Failed to press Enter: {}", e),
+                        Err(e) => eprintln!("[Pipeline] Enter task failed: {}", e),
+                    }
+                }
+
                 // Restore original clipboard content
                 if let Err(e) = clipboard_guard.restore() {
                     eprintln!("[Pipeline] Failed to restore clipboard: {}", e);
                 }
 
                 // Start correction detection window (10 seconds to detect user corrections)
-                start_correction_window(app, final_text.clone());
+                if behavior.detect_corrections {
+                    let language = settings.transcription.language().map(str::to_string);
+                    start_correction_window(app, final_text.clone(), language);
+                }
 
-                true
-            }
-            Ok(Ok(Err(e))) => {
-                eprintln!("[Pipeline] Paste simulation failed: {}", e);
-                false
-            }
-            Ok(Err(_)) => {
-                eprintln!("[Pipeline] Paste simulation panicked");
-                false
-            }
-            Err(e) => {
-                eprintln!("[Pipeline] Paste task failed: {}", e);
-                false
+                Ok(())
             }
+            Ok(Ok(Err(e))) => Err(PipelineError::PasteFailed(e)),
+            Ok(Err(_)) => Err(PipelineError::PasteFailed("paste simulation panicked".to_string())),
+            Err(e) => Err(PipelineError::PasteFailed(format!("paste task failed: {}", e))),
         }
     } else {
         eprintln!("[Pipeline] No accessibility permission - using clipboard fallback");
@@ -586,32 +827,38 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
                 .spawn();
         }
 
-        false
+        Err(PipelineError::PasteFailed("no accessibility permission".to_string()))
     };
 
     // Save to history (before completing)
     // Store both final and raw text so user can see the unpolished transcription
-    let raw_for_history = if settings.ai_polish_enabled {
+    let raw_for_history = if settings.ai_polish_enabled || mode != PipelineMode::Dictate || snippet_used {
         Some(raw_text.as_str())
     } else {
         None // No raw text if polish was disabled (they're the same)
     };
 
-    if let Err(e) = add_history_entry(&final_text, raw_for_history) {
-        eprintln!("[Pipeline] Failed to save to history: {}", e);
-    }
+    let history_id = match add_history_entry(&final_text, raw_for_history, mode, snippet_used) {
+        Ok(timestamp) => Some(timestamp),
+        Err(e) => {
+            eprintln!("[Pipeline] Failed to save to history: {}", e);
+            None
+        }
+    };
 
     // Complete with appropriate message
-    if paste_success {
-        emit_progress(app, "complete", "");
-    } else {
-        // Clipboard fallback - show error in pill + system notification
-        if !has_accessibility {
-            emit_progress(app, "error", "Enable Accessibility to auto-paste");
-            notify(app, "Add TTP to Accessibility in Settings, then paste with Cmd+V");
-        } else {
-            emit_progress(app, "error", "Paste failed — Cmd+V to paste");
-            notify(app, "Text copied - paste with Cmd+V");
+    match paste_result {
+        Ok(()) => emit_progress(app, "complete", ""),
+        Err(e) => {
+            // Clipboard fallback - show error in pill + system notification
+            log_error(&e.to_string());
+            if !has_accessibility {
+                emit_progress(app, "error", "Enable Accessibility to auto-paste");
+                notify(app, "Add TTP to Accessibility in Settings, then paste with Cmd+V");
+            } else {
+                emit_progress(app, "error", &e.user_message());
+                notify(app, "Text copied - paste with Cmd+V");
+            }
         }
     }
 
@@ -619,10 +866,23 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
     crate::telemetry::analytics::track(app, "transcription_success", Some(serde_json::json!({
         "duration_seconds": pipeline_start.elapsed().as_secs_f64(),
         "word_count": final_text.split_whitespace().count(),
+        "speech_seconds": speech.map(|r| r.speech_secs as f64),
         "polish_enabled": settings.ai_polish_enabled.to_string(),
-        "input_mode": input_mode
+        "input_mode": input_mode,
+        "pipeline_mode": match mode {
+            PipelineMode::Dictate => "dictate",
+            PipelineMode::Translate => "translate",
+            PipelineMode::Edit => "edit",
+        }
     })));
 
+    // Keep the upload audio with its history entry so it can be re-transcribed
+    if let (true, Some(timestamp)) = (settings.keep_history_audio, history_id) {
+        if let Err(e) = crate::history::retain_history_audio(timestamp, Path::new(&converted_path)) {
+            crate::logging::log_warn(&format!("Failed to keep audio for history: {}", e));
+        }
+    }
+
     // Clean up audio files after processing
     let _ = std::fs::remove_file(&audio_path);
     if use_converted { let _ = std::fs::remove_file(&converted_path); }
@@ -632,15 +892,19 @@ pub async fn process_recording(app: &AppHandle, audio_path: String) -> Result<St
         super::backup::remove_backup(bp);
     }
 
+    // The provider is reachable again: retry queued recordings now
+    super::retry::retry_pending_now(app);
+
     set_state(app, RecordingState::Idle);
     Ok(final_text)
 }
 
 /// Tauri command to process a completed recording
 ///
-/// Called by frontend after mic-recorder stops and returns the file path.
-/// Runs the full transcription pipeline asynchronously.
+/// Runs the full transcription pipeline (dictation mode) on an existing WAV
+/// file. Recordings made with the shortcut skip this: the recorder calls
+/// `process_recording` directly.
 #[tauri::command]
 pub async fn process_audio(app: AppHandle, audio_path: String) -> Result<String, String> {
-    process_recording(&app, audio_path).await
+    process_recording(&app, audio_path, PipelineMode::Dictate).await.map_err(|e| e.to_string())
 }
@@ -0,0 +1,297 @@
+// TTP - Talk To Paste
+// Audio preprocessing chain: high-pass, noise suppression, AGC, normalization
+//
+// Runs inside `convert` on the mono 16kHz signal, before the upload file is
+// written. Each stage is a stateful block processor, so the chain works on
+// fixed-size blocks and never needs the whole recording in memory. Peak
+// normalization needs the final peak, so it is a separate pass over the
+// written file.
+
+use super::error::PipelineError;
+use crate::logging::log_warn;
+use crate::settings::AudioPreprocessing;
+use hound::{WavReader, WavWriter};
+use nnnoiseless::DenoiseState;
+use std::collections::VecDeque;
+
+/// High-pass corner: below the voice fundamental, above mains hum and rumble
+const HIGH_PASS_HZ: f32 = 80.0;
+
+/// RNNoise runs on 48kHz frames
+const DENOISE_RATE: u32 = 48_000;
+
+/// AGC target level (-20 dBFS RMS, comfortable speech level for Whisper)
+const AGC_TARGET_RMS: f32 = 0.1;
+
+/// AGC gain range (+20 dB / -6 dB)
+const AGC_MAX_GAIN: f32 = 10.0;
+const AGC_MIN_GAIN: f32 = 0.5;
+
+/// Below this level the AGC holds its gain instead of boosting the noise floor
+const AGC_GATE_RMS: f32 = 0.003;
+
+/// AGC level detector and gain smoothing time constants
+const AGC_DETECTOR_MS: f32 = 100.0;
+const AGC_ATTACK_MS: f32 = 10.0;
+const AGC_RELEASE_MS: f32 = 500.0;
+
+/// Peak normalization target (-1 dBFS) and maximum boost (+20 dB)
+const NORMALIZE_PEAK: f32 = 0.89;
+const NORMALIZE_MAX_GAIN: f32 = 10.0;
+
+/// One stage of the chain; keeps its state between blocks
+pub trait BlockProcessor: Send {
+    /// Process mono samples (-1.0..1.0) in place
+    fn process(&mut self, block: &mut [f32]);
+}
+
+/// The enabled block stages, in order
+pub struct Preprocessor {
+    stages: Vec<Box<dyn BlockProcessor>>,
+}
+
+impl Preprocessor {
+    /// Build the chain for a mono stream at `sample_rate`
+    pub fn new(config: &AudioPreprocessing, sample_rate: u32) -> Self {
+        let mut stages: Vec<Box<dyn BlockProcessor>> = Vec::new();
+        if config.high_pass {
+            stages.push(Box::new(HighPass::new(HIGH_PASS_HZ, sample_rate)));
+        }
+        if config.noise_suppression {
+            match Denoiser::new(sample_rate) {
+                Some(denoiser) => stages.push(Box::new(denoiser)),
+                None => log_warn(&format!("Noise suppression skipped: unsupported rate {}Hz", sample_rate)),
+            }
+        }
+        if config.auto_gain {
+            stages.push(Box::new(AutoGain::new(sample_rate)));
+        }
+        Self { stages }
+    }
+
+    /// Whether any block stage is enabled
+    pub fn is_active(&self) -> bool {
+        !self.stages.is_empty()
+    }
+
+    /// Run one block through every stage
+    pub fn process(&mut self, block: &mut [f32]) {
+        for stage in &mut self.stages {
+            stage.process(block);
+        }
+    }
+}
+
+/// 2nd-order Butterworth high-pass (RBJ biquad); also removes DC offset
+struct HighPass {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl HighPass {
+    fn new(cutoff_hz: f32, sample_rate: u32) -> Self {
+        let w0 = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos = w0.cos();
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: (1.0 + cos) / 2.0 / a0,
+            b1: -(1.0 + cos) / a0,
+            b2: (1.0 + cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+}
+
+impl BlockProcessor for HighPass {
+    fn process(&mut self, block: &mut [f32]) {
+        // Transposed direct form II
+        for sample in block {
+            let x = *sample;
+            let y = self.b0 * x + self.z1;
+            self.z1 = self.b1 * x - self.a1 * y + self.z2;
+            self.z2 = self.b2 * x - self.a2 * y;
+            *sample = y;
+        }
+    }
+}
+
+/// RNNoise noise suppression (nnnoiseless)
+///
+/// Lower rates that divide 48kHz are upsampled by linear interpolation and
+/// decimated back after denoising; the images this creates above the
+/// original Nyquist are treated as noise by the model. Adds one RNNoise
+/// frame (10ms) of latency; output length always equals input length.
+struct Denoiser {
+    state: Box<DenoiseState<'static>>,
+    /// 48kHz / input rate
+    factor: usize,
+    /// Last input sample, for interpolation
+    previous: f32,
+    frame_in: Vec<f32>,
+    frame_out: Vec<f32>,
+    /// Denoised samples at the input rate, waiting to be returned
+    ready: VecDeque<f32>,
+}
+
+impl Denoiser {
+    fn new(sample_rate: u32) -> Option<Self> {
+        if sample_rate == 0 || !DENOISE_RATE.is_multiple_of(sample_rate) {
+            return None;
+        }
+        Some(Self {
+            state: DenoiseState::new(),
+            factor: (DENOISE_RATE / sample_rate) as usize,
+            previous: 0.0,
+            frame_in: Vec::with_capacity(DenoiseState::FRAME_SIZE),
+            frame_out: vec![0.0; DenoiseState::FRAME_SIZE],
+            ready: VecDeque::with_capacity(DenoiseState::FRAME_SIZE),
+        })
+    }
+}
+
+impl BlockProcessor for Denoiser {
+    fn process(&mut self, block: &mut [f32]) {
+        for sample in block {
+            let x = *sample;
+            // RNNoise expects 16-bit sample range
+            for k in 1..=self.factor {
+                let t = k as f32 / self.factor as f32;
+                self.frame_in.push((self.previous + (x - self.previous) * t) * 32768.0);
+            }
+            self.previous = x;
+
+            if self.frame_in.len() == DenoiseState::FRAME_SIZE {
+                self.state.process_frame(&mut self.frame_out, &self.frame_in);
+                self.frame_in.clear();
+                for group in self.frame_out.chunks(self.factor) {
+                    let avg = group.iter().sum::<f32>() / group.len() as f32;
+                    self.ready.push_back(avg / 32768.0);
+                }
+            }
+
+            *sample = self.ready.pop_front().unwrap_or(0.0);
+        }
+    }
+}
+
+/// Automatic gain control toward a target RMS, gated on silence
+struct AutoGain {
+    gain: f32,
+    /// Smoothed mean square level
+    level: f32,
+    detector: f32,
+    attack: f32,
+    release: f32,
+}
+
+impl AutoGain {
+    fn new(sample_rate: u32) -> Self {
+        let coeff = |ms: f32| 1.0 - (-1.0 / (ms / 1000.0 * sample_rate as f32)).exp();
+        Self {
+            gain: 1.0,
+            level: 0.0,
+            detector: coeff(AGC_DETECTOR_MS),
+            attack: coeff(AGC_ATTACK_MS),
+            release: coeff(AGC_RELEASE_MS),
+        }
+    }
+}
+
+impl BlockProcessor for AutoGain {
+    fn process(&mut self, block: &mut [f32]) {
+        for sample in block {
+            let x = *sample;
+            self.level += self.detector * (x * x - self.level);
+            let rms = self.level.sqrt();
+
+            if rms > AGC_GATE_RMS {
+                let desired = (AGC_TARGET_RMS / rms).clamp(AGC_MIN_GAIN, AGC_MAX_GAIN);
+                // Turn down fast (no clipping on a sudden loud word), up slowly (no pumping)
+                let speed = if desired < self.gain { self.attack } else { self.release };
+                self.gain += speed * (desired - self.gain);
+            }
+
+            *sample = (x * self.gain).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// Scale a 16-bit WAV in place so its peak reaches -1 dBFS
+///
+/// Two streaming passes (find the peak, rewrite through a temp file).
+/// Quiet files are boosted by at most +20 dB so near-silence stays quiet.
+pub fn normalize_peak(path: &str) -> Result<(), PipelineError> {
+    let read_err = |e: hound::Error| PipelineError::CorruptAudio(format!("Failed to read WAV: {}", e));
+    let write_err = |e: hound::Error| PipelineError::Io(format!("Failed to write normalized WAV: {}", e));
+
+    let reader = WavReader::open(path).map_err(read_err)?;
+    let spec = reader.spec();
+    let mut peak = 0i32;
+    for sample in reader.into_samples::<i16>() {
+        peak = peak.max((sample.map_err(read_err)? as i32).abs());
+    }
+    if peak == 0 {
+        return Ok(());
+    }
+
+    let gain = (NORMALIZE_PEAK * 32767.0 / peak as f32).min(NORMALIZE_MAX_GAIN);
+    if (gain - 1.0).abs() < 0.01 {
+        return Ok(());
+    }
+
+    let temp_path = format!("{}.normalizing", path);
+    let reader = WavReader::open(path).map_err(read_err)?;
+    let mut writer = WavWriter::create(&temp_path, spec).map_err(write_err)?;
+    for sample in reader.into_samples::<i16>() {
+        let scaled = (sample.map_err(read_err)? as f32 * gain).clamp(-32768.0, 32767.0) as i16;
+        writer.write_sample(scaled).map_err(write_err)?;
+    }
+    writer.finalize().map_err(write_err)?;
+
+    std::fs::rename(&temp_path, path)
+        .map_err(|e| PipelineError::Io(format!("Failed to replace normalized WAV: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_pass_removes_dc_offset() {
+        let mut filter = HighPass::new(HIGH_PASS_HZ, 16_000);
+        let mut block = vec![0.5f32; 16_000];
+        filter.process(&mut block);
+        assert!(block[15_999].abs() < 0.001, "residual {}", block[15_999]);
+    }
+
+    #[test]
+    fn auto_gain_boosts_quiet_speech_toward_target() {
+        let rate = 16_000;
+        let mut agc = AutoGain::new(rate);
+        let mut block: Vec<f32> = (0..rate * 3)
+            .map(|i| (2.0 * std::f32::consts::PI * 200.0 * i as f32 / rate as f32).sin() * 0.02)
+            .collect();
+        agc.process(&mut block);
+
+        let tail = &block[block.len() - rate as usize..];
+        let rms = (tail.iter().map(|s| s * s).sum::<f32>() / tail.len() as f32).sqrt();
+        assert!(rms > 0.05 && rms < 0.15, "rms {}", rms);
+    }
+
+    #[test]
+    fn auto_gain_does_not_boost_silence() {
+        let mut agc = AutoGain::new(16_000);
+        let mut block = vec![0.001f32; 16_000];
+        agc.process(&mut block);
+        assert!((block[15_999] - 0.001).abs() < 1e-6);
+    }
+}
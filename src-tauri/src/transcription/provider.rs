@@ -0,0 +1,357 @@
+// TTP - Talk To Paste
+// Pluggable speech-to-text providers
+//
+// The pipeline talks to a `TranscriptionProvider` built from `Settings`
+// instead of a hard-wired Groq endpoint. OpenAI-style multipart APIs
+// (Groq, OpenAI, self-hosted faster-whisper / LocalAI) share one
+// implementation; Deepgram-style JSON APIs have their own, and
+// `TranscriptionMode` decides whether on-device whisper.cpp runs first.
+// Cloud providers encode the WAV to the most compact format they accept
+// (see `upload_format`) right before uploading.
+
+use super::encode::{encode_for_upload, AudioFormat};
+use super::error::PipelineError;
+use super::local::LocalWhisperProvider;
+use super::{deepgram, whisper};
+use crate::logging::log_warn;
+use crate::settings::{TranscriptionMode, TranscriptionProviderKind, TranscriptionSettings, UploadFormat};
+use std::future::Future;
+
+/// Groq OpenAI-compatible API base
+const GROQ_BASE_URL: &str = "https://api.groq.com/openai/v1";
+
+/// OpenAI API base
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Deepgram API base
+const DEEPGRAM_BASE_URL: &str = "https://api.deepgram.com/v1";
+
+/// Maximum upload size in bytes (25MB Groq API limit); larger recordings are chunked
+pub(super) const MAX_AUDIO_SIZE: u64 = 25_000_000;
+
+/// What the provider should produce from the audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TranscriptionTask {
+    /// Text in the spoken language
+    #[default]
+    Transcribe,
+    /// English text, whatever the spoken language (Whisper translate task)
+    TranslateToEnglish,
+}
+
+/// A single transcription job handed to a provider
+pub struct TranscriptionRequest<'a> {
+    /// Path to the audio file (mono 16kHz WAV after conversion)
+    pub audio_path: &'a str,
+    /// Vocabulary / context hint (Whisper `prompt`)
+    pub prompt: Option<&'a str>,
+    /// Spoken language (ISO 639-1), `None` to let the provider detect it
+    pub language: Option<&'a str>,
+    pub task: TranscriptionTask,
+}
+
+/// A speech-to-text backend
+pub trait TranscriptionProvider {
+    /// Human-readable provider name for logs and error messages
+    fn name(&self) -> &str;
+
+    /// Transcribe an audio file, returning the plain text
+    fn transcribe(
+        &self,
+        request: &TranscriptionRequest<'_>,
+    ) -> impl Future<Output = Result<String, PipelineError>> + Send;
+}
+
+/// Encode for upload, run `send` on the encoded file, then delete it
+///
+/// The pipeline only estimates the encoded size when deciding whether to
+/// chunk, so the real file is checked here: anything over `MAX_AUDIO_SIZE`
+/// fails with `PayloadTooLarge` (and gets split by the caller) instead of
+/// being sent. A failed encoding falls back to the WAV only when that fits.
+async fn with_encoded_upload<F, Fut>(
+    audio_path: &str,
+    format: AudioFormat,
+    send: F,
+) -> Result<String, PipelineError>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String, PipelineError>>,
+{
+    let upload_path = match encode_for_upload(audio_path, format).await {
+        Ok(path) => path,
+        Err(e) => {
+            // Encoding is an optimization — the WAV still works if it's small enough
+            log_warn(&format!("{:?} encoding failed, uploading WAV: {}", format, e));
+            audio_path.to_string()
+        }
+    };
+
+    let result = match check_upload_size(&upload_path) {
+        Ok(()) => send(upload_path.clone()).await,
+        Err(e) => Err(e),
+    };
+    if upload_path != audio_path {
+        let _ = std::fs::remove_file(&upload_path);
+    }
+    result
+}
+
+/// `PayloadTooLarge` when the file to upload is over `MAX_AUDIO_SIZE`
+fn check_upload_size(upload_path: &str) -> Result<(), PipelineError> {
+    let size = std::fs::metadata(upload_path)
+        .map_err(|e| PipelineError::AudioNotFound(format!("Failed to read upload file: {}", e)))?
+        .len();
+    if size > MAX_AUDIO_SIZE {
+        log_warn(&format!(
+            "Upload is {:.1}MB, over the {}MB limit",
+            size as f64 / 1_000_000.0,
+            MAX_AUDIO_SIZE / 1_000_000
+        ));
+        return Err(PipelineError::PayloadTooLarge);
+    }
+    Ok(())
+}
+
+/// OpenAI-style `/audio/transcriptions` (and `/audio/translations`) multipart API
+pub struct WhisperApiProvider {
+    name: String,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    format: AudioFormat,
+}
+
+impl WhisperApiProvider {
+    pub fn new(name: &str, base_url: &str, model: &str, api_key: Option<String>, format: AudioFormat) -> Self {
+        Self {
+            name: name.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key,
+            format,
+        }
+    }
+}
+
+impl TranscriptionProvider for WhisperApiProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn transcribe(&self, request: &TranscriptionRequest<'_>) -> Result<String, PipelineError> {
+        // The translations endpoint always outputs English and takes no `language`
+        let (url, language) = match request.task {
+            TranscriptionTask::Transcribe => (format!("{}/audio/transcriptions", self.base_url), request.language),
+            TranscriptionTask::TranslateToEnglish => (format!("{}/audio/translations", self.base_url), None),
+        };
+
+        with_encoded_upload(request.audio_path, self.format, |upload_path| async move {
+            whisper::transcribe_with_provider(
+                self.api_key.as_deref(),
+                &upload_path,
+                &url,
+                &self.model,
+                &self.name,
+                request.prompt,
+                language,
+            )
+            .await
+        })
+        .await
+    }
+}
+
+/// Deepgram-style `/listen` API (raw audio body, JSON response)
+pub struct DeepgramProvider {
+    url: String,
+    model: String,
+    api_key: Option<String>,
+    format: AudioFormat,
+}
+
+impl DeepgramProvider {
+    pub fn new(base_url: &str, model: &str, api_key: Option<String>, format: AudioFormat) -> Self {
+        Self {
+            url: format!("{}/listen", base_url.trim_end_matches('/')),
+            model: model.to_string(),
+            api_key,
+            format,
+        }
+    }
+}
+
+impl TranscriptionProvider for DeepgramProvider {
+    fn name(&self) -> &str {
+        "Deepgram"
+    }
+
+    async fn transcribe(&self, request: &TranscriptionRequest<'_>) -> Result<String, PipelineError> {
+        if request.task == TranscriptionTask::TranslateToEnglish {
+            return Err(PipelineError::Config(
+                "Deepgram can't translate: use the LLM translation engine".to_string(),
+            ));
+        }
+
+        with_encoded_upload(request.audio_path, self.format, |upload_path| async move {
+            deepgram::transcribe_deepgram(
+                self.api_key.as_deref(),
+                &upload_path,
+                &self.url,
+                &self.model,
+                request.language,
+            )
+            .await
+        })
+        .await
+    }
+}
+
+/// The provider selected in settings (static dispatch over the implementations)
+pub enum ConfiguredProvider {
+    Whisper(WhisperApiProvider),
+    Deepgram(DeepgramProvider),
+}
+
+impl TranscriptionProvider for ConfiguredProvider {
+    fn name(&self) -> &str {
+        match self {
+            Self::Whisper(p) => p.name(),
+            Self::Deepgram(p) => p.name(),
+        }
+    }
+
+    async fn transcribe(&self, request: &TranscriptionRequest<'_>) -> Result<String, PipelineError> {
+        match self {
+            Self::Whisper(p) => p.transcribe(request).await,
+            Self::Deepgram(p) => p.transcribe(request).await,
+        }
+    }
+}
+
+/// Whether the provider refuses requests without an API key
+pub fn requires_api_key(kind: TranscriptionProviderKind) -> bool {
+    // Self-hosted endpoints usually run without auth
+    kind != TranscriptionProviderKind::OpenAiCompatible
+}
+
+/// Upload format for the configured provider
+///
+/// `Auto` picks Opus for the hosted APIs (all accept Ogg/Opus) and WAV for
+/// OpenAI-compatible servers, whose decoders vary. An explicit choice is
+/// honored, falling back to FLAC if this build has no Opus encoder.
+pub fn upload_format(settings: &TranscriptionSettings) -> AudioFormat {
+    let format = match settings.upload_format {
+        UploadFormat::Auto => match settings.provider {
+            TranscriptionProviderKind::OpenAiCompatible => AudioFormat::Wav,
+            _ => AudioFormat::Opus,
+        },
+        UploadFormat::Wav => AudioFormat::Wav,
+        UploadFormat::Flac => AudioFormat::Flac,
+        UploadFormat::Opus => AudioFormat::Opus,
+    };
+
+    if format.is_available() {
+        format
+    } else {
+        AudioFormat::Flac
+    }
+}
+
+/// Build the configured provider from settings
+///
+/// Fails only for an OpenAI-compatible provider without a base URL,
+/// since there is no sensible default endpoint to fall back to.
+pub fn provider_from_settings(
+    settings: &TranscriptionSettings,
+    api_key: Option<String>,
+) -> Result<ConfiguredProvider, PipelineError> {
+    let base_url = settings.base_url.as_deref().filter(|u| !u.trim().is_empty());
+    let model = settings.model.as_deref().filter(|m| !m.trim().is_empty());
+    let format = upload_format(settings);
+
+    let provider = match settings.provider {
+        TranscriptionProviderKind::Groq => ConfiguredProvider::Whisper(WhisperApiProvider::new(
+            "Groq",
+            base_url.unwrap_or(GROQ_BASE_URL),
+            model.unwrap_or("whisper-large-v3"),
+            api_key,
+            format,
+        )),
+        TranscriptionProviderKind::OpenAi => ConfiguredProvider::Whisper(WhisperApiProvider::new(
+            "OpenAI",
+            base_url.unwrap_or(OPENAI_BASE_URL),
+            model.unwrap_or("whisper-1"),
+            api_key,
+            format,
+        )),
+        TranscriptionProviderKind::OpenAiCompatible => {
+            let base_url = base_url
+                .ok_or_else(|| PipelineError::Config("OpenAI-compatible provider requires a base URL".to_string()))?;
+            ConfiguredProvider::Whisper(WhisperApiProvider::new(
+                "Custom",
+                base_url,
+                model.unwrap_or("whisper-1"),
+                api_key,
+                format,
+            ))
+        }
+        TranscriptionProviderKind::Deepgram => ConfiguredProvider::Deepgram(DeepgramProvider::new(
+            base_url.unwrap_or(DEEPGRAM_BASE_URL),
+            model.unwrap_or("nova-2"),
+            api_key,
+            format,
+        )),
+    };
+
+    Ok(provider)
+}
+
+/// Transcribe audio file with the backend configured in settings
+///
+/// In `LocalWithCloudFallback` mode a local failure (model missing, engine
+/// error) is logged and the cloud provider is tried instead.
+///
+/// # Arguments
+/// * `settings` - Transcription settings (mode, provider, models)
+/// * `api_key` - API key for the configured cloud provider (if it needs one)
+/// * `audio_path` - Path to the audio file (mono 16kHz WAV)
+/// * `prompt` - Optional vocabulary / context hint
+/// * `task` - Transcribe, or translate to English
+///
+/// The spoken language comes from `settings.language`.
+///
+/// # Returns
+/// * `Ok(String)` - Transcription text on success
+/// * `Err(PipelineError)` - Typed failure (network, auth, rate limit, ...)
+pub async fn transcribe_audio(
+    settings: &TranscriptionSettings,
+    api_key: Option<&str>,
+    audio_path: &str,
+    prompt: Option<&str>,
+    task: TranscriptionTask,
+) -> Result<String, PipelineError> {
+    let request = TranscriptionRequest { audio_path, prompt, language: settings.language(), task };
+
+    match settings.mode {
+        TranscriptionMode::Cloud => {
+            let provider = provider_from_settings(settings, api_key.map(|k| k.to_string()))?;
+            provider.transcribe(&request).await
+        }
+        TranscriptionMode::Local => {
+            LocalWhisperProvider::new(&settings.local_model)
+                .transcribe(&request)
+                .await
+        }
+        TranscriptionMode::LocalWithCloudFallback => {
+            let local = LocalWhisperProvider::new(&settings.local_model);
+            match local.transcribe(&request).await {
+                Ok(text) => Ok(text),
+                Err(e) => {
+                    log_warn(&format!("Local transcription failed, falling back to cloud: {}", e));
+                    let provider = provider_from_settings(settings, api_key.map(|k| k.to_string()))?;
+                    provider.transcribe(&request).await
+                }
+            }
+        }
+    }
+}
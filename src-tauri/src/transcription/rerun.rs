@@ -0,0 +1,217 @@
+// TTP - Talk To Paste
+// Re-transcribe and re-polish history entries
+//
+// Lets the user regenerate a past result without dictating again: re-polish
+// works from the entry's raw transcript, re-transcribe from the audio kept
+// with the entry when `keep_history_audio` is on. Both accept a model and a
+// polish style override, and re-transcribe a spoken language override, so
+// another model, style or language can be tried without changing settings.
+// Translation entries are translated again instead of polished; command-mode
+// edits and snippets can't be re-run (see `HistoryEntry::rerun_blocker`).
+
+use super::local::LOCAL_MODELS;
+use super::pipeline::{finish_text, polish_transcript, transcribe_file, whisper_prompt};
+use super::provider::TranscriptionTask;
+use super::translate::{translate_transcript, whisper_translates};
+use crate::credentials::get_provider_api_key_internal;
+use crate::history::{get_history_entry, update_history_entry, HistoryEntry};
+use crate::llm::{resolve_task, LlmTask};
+use crate::settings::{get_settings, PipelineMode, Settings, TranscriptionMode, TranscriptionSettings};
+use std::path::Path;
+
+/// Apply a non-empty model override
+fn override_model(target: &mut Option<String>, model: Option<String>) {
+    if let Some(model) = model.filter(|m| !m.trim().is_empty()) {
+        *target = Some(model);
+    }
+}
+
+/// Apply re-transcribe overrides to a copy of the transcription settings
+///
+/// The model goes where the active mode reads it: `local_model` on-device
+/// (it must be a catalog id), the cloud model otherwise. With cloud
+/// fallback, a catalog id picks the local model and anything else the
+/// cloud one.
+fn apply_transcription_overrides(
+    settings: &mut TranscriptionSettings,
+    model: Option<String>,
+    language: Option<String>,
+) -> Result<(), String> {
+    if let Some(model) = model.map(|m| m.trim().to_string()).filter(|m| !m.is_empty()) {
+        let is_local = LOCAL_MODELS.iter().any(|m| m.id == model);
+        match settings.mode {
+            TranscriptionMode::Local if !is_local => {
+                return Err(format!("Unknown local model: {}", model));
+            }
+            TranscriptionMode::Local | TranscriptionMode::LocalWithCloudFallback if is_local => {
+                settings.local_model = model;
+            }
+            _ => settings.model = Some(model),
+        }
+    }
+    if let Some(language) = language.filter(|l| !l.trim().is_empty()) {
+        settings.language = Some(language);
+    }
+    Ok(())
+}
+
+/// Polish `raw_text` with the polish LLM (model and style overridable)
+async fn polish(
+    settings: &Settings,
+    raw_text: &str,
+    model: Option<String>,
+    style: Option<String>,
+) -> Result<String, String> {
+    let mut llm = settings.polish_llm.clone();
+    override_model(&mut llm.model, model);
+    let config = resolve_task(&llm, LlmTask::Polish)?;
+    let style = style.filter(|s| !s.trim().is_empty()).unwrap_or_else(|| settings.polish_style.clone());
+    Ok(polish_transcript(&config, raw_text, &style, settings.transcription.language()).await)
+}
+
+/// Translate `raw_text` with the polish LLM (model overridable)
+async fn translate(settings: &Settings, raw_text: &str, model: Option<String>) -> Result<String, String> {
+    let mut settings = settings.clone();
+    override_model(&mut settings.polish_llm.model, model);
+    translate_transcript(&settings, raw_text).await.map_err(|e| e.user_message())
+}
+
+/// Tauri command: run a history entry's retained audio through transcription again
+///
+/// Polishes the new transcript when AI polish is enabled, like a live
+/// dictation, or translates it again for translation entries, and replaces
+/// the entry's text. `language` is an ISO 639-1 code
+/// or "auto"; it drives the Whisper prompt, polish fillers, voice commands
+/// and dictionary scope for this run.
+#[tauri::command]
+pub async fn retranscribe_history_entry(
+    timestamp: i64,
+    model: Option<String>,
+    style: Option<String>,
+    language: Option<String>,
+) -> Result<HistoryEntry, String> {
+    let entry = get_history_entry(timestamp).ok_or("History entry not found")?;
+    if let Some(reason) = entry.rerun_blocker() {
+        return Err(reason.to_string());
+    }
+    let audio = entry
+        .audio_path
+        .filter(|path| Path::new(path).exists())
+        .ok_or("No audio was kept for this transcription")?;
+
+    let mut settings = get_settings();
+    apply_transcription_overrides(&mut settings.transcription, model, language)?;
+    let key = get_provider_api_key_internal(settings.transcription.provider.into());
+
+    let prompt = whisper_prompt(settings.transcription.language());
+    let task = if entry.mode == PipelineMode::Translate && whisper_translates(&settings) {
+        TranscriptionTask::TranslateToEnglish
+    } else {
+        TranscriptionTask::Transcribe
+    };
+
+    let raw_text = transcribe_file(&settings.transcription, key.as_deref(), &audio, prompt.as_deref(), task)
+        .await
+        .map_err(|e| e.user_message())?;
+    if raw_text.trim().is_empty() {
+        return Err("No speech detected".to_string());
+    }
+
+    let (text, raw_text) = if entry.mode == PipelineMode::Translate && task == TranscriptionTask::Transcribe {
+        (translate(&settings, &raw_text, None).await?, Some(raw_text))
+    } else if settings.ai_polish_enabled {
+        match polish(&settings, &raw_text, None, style).await {
+            Ok(polished) => (polished, Some(raw_text)),
+            Err(e) => {
+                crate::logging::log_warn(&format!("Polish LLM unavailable, using raw text: {}", e));
+                (raw_text, None)
+            }
+        }
+    } else if entry.mode == PipelineMode::Translate {
+        (raw_text.clone(), Some(raw_text))
+    } else {
+        (raw_text, None)
+    };
+    let text = finish_text(&settings, &text, entry.mode);
+
+    update_history_entry(timestamp, |entry| {
+        entry.text = text;
+        entry.raw_text = raw_text;
+    })
+}
+
+/// Tauri command: polish a history entry's raw transcript again
+///
+/// Always polishes (even with AI polish turned off in settings), starting
+/// from the raw transcript so earlier polish mistakes don't compound.
+/// Translation entries are translated from their transcript again.
+#[tauri::command]
+pub async fn repolish_history_entry(
+    timestamp: i64,
+    model: Option<String>,
+    style: Option<String>,
+) -> Result<HistoryEntry, String> {
+    let entry = get_history_entry(timestamp).ok_or("History entry not found")?;
+    if let Some(reason) = entry.rerun_blocker() {
+        return Err(reason.to_string());
+    }
+    let source = entry.raw_text.unwrap_or(entry.text);
+
+    let settings = get_settings();
+    let text = match entry.mode {
+        PipelineMode::Translate => translate(&settings, &source, model).await?,
+        _ => polish(&settings, &source, model, style).await?,
+    };
+    let text = finish_text(&settings, &text, entry.mode);
+
+    update_history_entry(timestamp, |entry| {
+        entry.text = text;
+        entry.raw_text = Some(source);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_model_override_keeps_the_setting() {
+        let mut model = Some("gpt-4o-mini".to_string());
+        override_model(&mut model, None);
+        override_model(&mut model, Some("  ".to_string()));
+        assert_eq!(model.as_deref(), Some("gpt-4o-mini"));
+        override_model(&mut model, Some("llama-3.3-70b".to_string()));
+        assert_eq!(model.as_deref(), Some("llama-3.3-70b"));
+    }
+
+    #[test]
+    fn model_override_follows_the_transcription_mode() {
+        let mut cloud = TranscriptionSettings { mode: TranscriptionMode::Cloud, ..Default::default() };
+        apply_transcription_overrides(&mut cloud, Some("whisper-large-v3".to_string()), None).unwrap();
+        assert_eq!(cloud.model.as_deref(), Some("whisper-large-v3"));
+
+        let mut local = TranscriptionSettings { mode: TranscriptionMode::Local, ..Default::default() };
+        let cloud_model = local.model.clone();
+        apply_transcription_overrides(&mut local, Some("small".to_string()), None).unwrap();
+        assert_eq!(local.local_model, "small");
+        assert_eq!(local.model, cloud_model);
+        assert!(apply_transcription_overrides(&mut local, Some("whisper-large-v3".to_string()), None).is_err());
+
+        let mut fallback = TranscriptionSettings { mode: TranscriptionMode::LocalWithCloudFallback, ..Default::default() };
+        apply_transcription_overrides(&mut fallback, Some("tiny".to_string()), None).unwrap();
+        apply_transcription_overrides(&mut fallback, Some("whisper-large-v3".to_string()), None).unwrap();
+        assert_eq!(fallback.local_model, "tiny");
+        assert_eq!(fallback.model.as_deref(), Some("whisper-large-v3"));
+    }
+
+    #[test]
+    fn language_override_replaces_the_setting() {
+        let mut settings = TranscriptionSettings { language: Some("en".to_string()), ..Default::default() };
+        apply_transcription_overrides(&mut settings, None, Some(" ".to_string())).unwrap();
+        assert_eq!(settings.language.as_deref(), Some("en"));
+        apply_transcription_overrides(&mut settings, None, Some("fr".to_string())).unwrap();
+        assert_eq!(settings.language(), Some("fr"));
+        apply_transcription_overrides(&mut settings, None, Some("auto".to_string())).unwrap();
+        assert_eq!(settings.language(), None);
+    }
+}
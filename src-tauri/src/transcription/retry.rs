@@ -0,0 +1,599 @@
+// TTP - Talk To Paste
+// Persistent retry queue for recordings whose transcription failed
+//
+// When transcription fails with a retryable error (network, rate limit,
+// provider outage, missing key), the pipeline hands the backup made by
+// `backup::backup_audio` to this queue instead of letting it expire. A
+// background worker retries due jobs with exponential backoff, and any
+// successful transcription makes every pending job due at once — it proves
+// connectivity is back. Recovered text is polished (or translated, for
+// recordings made in translation mode), saved to history marked
+// as recovered and offered for copying; it is never auto-pasted, since the
+// user has long moved on from the window they dictated into.
+//
+// The queue is stored in `audio_backups/queue.json` next to the audio it
+// owns; `backup::cleanup_stale_backups` leaves queued files alone.
+
+use super::backup::{backup_dir, remove_backup, validate_wav};
+use super::convert::convert_to_mono_16khz;
+use super::error::PipelineError;
+use super::hallucination::detect_hallucination;
+use super::pipeline::{finish_text, polish_transcript, transcribe_file, whisper_prompt};
+use super::provider::TranscriptionTask;
+use super::translate::{translate_transcript, whisper_translates};
+use crate::credentials::get_provider_api_key_internal;
+use crate::logging::{log_info, log_warn};
+use crate::settings::{get_settings, PipelineMode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Notify;
+
+/// Queue file name inside the backup directory
+const QUEUE_FILE: &str = "queue.json";
+
+/// Delay before the first retry; doubles after every failed attempt
+const BASE_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Longest wait between two attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// Give up (keeping the audio for a manual retry) after this many attempts
+const MAX_ATTEMPTS: u32 = 12;
+
+/// Worker pause after the queue file couldn't be written (instead of spinning on a due job)
+const SAVE_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Recovered jobs stay listed this long so their text can still be copied
+const DONE_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Where a queued recording stands
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryStatus {
+    /// Waiting for its next attempt
+    Pending,
+    /// Being transcribed right now
+    Running,
+    /// Transcribed; `text` holds the result
+    Done,
+    /// Gave up or hit a permanent error; can still be retried by hand
+    Failed,
+}
+
+/// One failed recording waiting to be transcribed again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryJob {
+    /// Backup file name, unique per recording
+    pub id: String,
+    /// The backup WAV this job owns
+    pub audio_path: String,
+    /// Unix timestamp in milliseconds when the recording first failed
+    pub created_at: i64,
+    /// Retries made so far (the original failure not included)
+    pub attempts: u32,
+    /// Unix timestamp in milliseconds of the next automatic attempt
+    pub next_attempt_at: i64,
+    /// User-facing reason of the latest failure
+    pub last_error: Option<String>,
+    pub status: RetryStatus,
+    /// Final text once recovered
+    pub text: Option<String>,
+    /// Mode the recording was made in
+    #[serde(default)]
+    pub mode: PipelineMode,
+}
+
+/// Serializes read-modify-write cycles on the queue file
+static QUEUE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Wakes the worker when jobs are added or made due
+fn wake_signal() -> &'static Notify {
+    static WAKE: OnceLock<Notify> = OnceLock::new();
+    WAKE.get_or_init(Notify::new)
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Wait before the attempt following `attempts` failed ones
+fn backoff(attempts: u32) -> Duration {
+    BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempts.min(16)))
+        .min(MAX_BACKOFF)
+}
+
+fn queue_path(app: &AppHandle) -> PathBuf {
+    backup_dir(app).join(QUEUE_FILE)
+}
+
+fn load(app: &AppHandle) -> Vec<RetryJob> {
+    std::fs::read_to_string(queue_path(app))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(app: &AppHandle, jobs: &[RetryJob]) -> Result<(), String> {
+    let path = queue_path(app);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(jobs)
+        .map_err(|e| format!("Failed to serialize retry queue: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write retry queue: {}", e))
+}
+
+/// Load, modify and save the queue under the lock, then tell the UI
+fn update<T>(app: &AppHandle, change: impl FnOnce(&mut Vec<RetryJob>) -> T) -> Result<T, String> {
+    let _guard = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut jobs = load(app);
+    let result = change(&mut jobs);
+    save(app, &jobs)?;
+    let _ = app.emit("retry-queue-changed", ());
+    Ok(result)
+}
+
+/// Audio files owned by the queue (never cleaned up as stale)
+pub fn queued_files(app: &AppHandle) -> HashSet<PathBuf> {
+    let _guard = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    load(app)
+        .into_iter()
+        .filter(|job| job.status != RetryStatus::Done)
+        .map(|job| PathBuf::from(job.audio_path))
+        .collect()
+}
+
+/// Queue a backup whose transcription just failed with `error`
+pub fn enqueue(
+    app: &AppHandle,
+    backup_path: &Path,
+    error: &PipelineError,
+    mode: PipelineMode,
+) -> Result<(), String> {
+    let id = backup_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or("Invalid backup path: no filename")?;
+    let now = now_ms();
+
+    update(app, |jobs| {
+        jobs.retain(|job| job.id != id);
+        jobs.push(RetryJob {
+            id,
+            audio_path: backup_path.to_string_lossy().to_string(),
+            created_at: now,
+            attempts: 0,
+            next_attempt_at: now + backoff(0).as_millis() as i64,
+            last_error: Some(error.user_message()),
+            status: RetryStatus::Pending,
+            text: None,
+            mode,
+        });
+    })?;
+
+    wake_signal().notify_one();
+    Ok(())
+}
+
+/// Make every pending job due now (called after a transcription succeeds)
+pub fn retry_pending_now(app: &AppHandle) {
+    let any_pending = {
+        let _guard = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        load(app).iter().any(|job| job.status == RetryStatus::Pending)
+    };
+    if !any_pending {
+        return;
+    }
+
+    let now = now_ms();
+    let result = update(app, |jobs| {
+        for job in jobs.iter_mut().filter(|job| job.status == RetryStatus::Pending) {
+            job.next_attempt_at = job.next_attempt_at.min(now);
+        }
+    });
+    if let Err(e) = result {
+        log_warn(&format!("Failed to reschedule retry queue: {}", e));
+    }
+    wake_signal().notify_one();
+}
+
+/// Start the retry worker (called once from `setup()`)
+///
+/// Jobs interrupted by a quit are made pending again, and recovered jobs
+/// past their retention are dropped.
+pub fn start(app: &AppHandle) {
+    let now = now_ms();
+    let result = update(app, |jobs| {
+        jobs.retain(|job| {
+            job.status != RetryStatus::Done || now - job.created_at < DONE_RETENTION.as_millis() as i64
+        });
+        for job in jobs.iter_mut().filter(|job| job.status == RetryStatus::Running) {
+            job.status = RetryStatus::Pending;
+        }
+    });
+    if let Err(e) = result {
+        log_warn(&format!("Failed to load retry queue: {}", e));
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let save_failed = match take_due_job(&app) {
+                Ok(Some(job)) => {
+                    run_job(&app, job).await;
+                    continue;
+                }
+                Ok(None) => false,
+                Err(e) => {
+                    log_warn(&format!("Failed to update retry queue: {}", e));
+                    true
+                }
+            };
+
+            // Sleep until the next job is due or something changes
+            let wait = {
+                let _guard = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+                idle_wait(&load(&app), now_ms(), save_failed)
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = wake_signal().notified() => {}
+            }
+        }
+    });
+}
+
+/// How long the worker sleeps when it has no job to run
+///
+/// Until the next pending job is due, or `SAVE_RETRY_DELAY` when claiming a
+/// due job failed to save: that job is still due, so waiting for it would
+/// retry the write in a tight loop.
+fn idle_wait(jobs: &[RetryJob], now: i64, save_failed: bool) -> Duration {
+    if save_failed {
+        return SAVE_RETRY_DELAY;
+    }
+    jobs.iter()
+        .filter(|job| job.status == RetryStatus::Pending)
+        .map(|job| (job.next_attempt_at - now).max(0) as u64)
+        .min()
+        .map(Duration::from_millis)
+        .unwrap_or(MAX_BACKOFF)
+}
+
+/// Mark the earliest due pending job running and return it
+fn claim_due(jobs: &mut [RetryJob], now: i64) -> Option<RetryJob> {
+    let job = jobs
+        .iter_mut()
+        .filter(|job| job.status == RetryStatus::Pending && job.next_attempt_at <= now)
+        .min_by_key(|job| job.next_attempt_at)?;
+    job.status = RetryStatus::Running;
+    Some(job.clone())
+}
+
+/// Claim the next due job, marking it running in the queue file
+fn take_due_job(app: &AppHandle) -> Result<Option<RetryJob>, String> {
+    let _guard = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut jobs = load(app);
+    let Some(job) = claim_due(&mut jobs, now_ms()) else {
+        return Ok(None);
+    };
+    save(app, &jobs)?;
+    let _ = app.emit("retry-queue-changed", ());
+    Ok(Some(job))
+}
+
+/// Retry one job and record the outcome
+async fn run_job(app: &AppHandle, job: RetryJob) {
+    log_info(&format!("Retrying transcription of {} (attempt {})", job.id, job.attempts + 1));
+    let outcome = transcribe_job(&job).await;
+
+    let recovered = match &outcome {
+        Ok(Some(text)) => {
+            if let Err(e) = crate::history::add_recovered_history_entry(&text.final_text, text.raw_text.as_deref(), job.mode) {
+                log_warn(&format!("Failed to save recovered transcription to history: {}", e));
+            }
+            true
+        }
+        _ => false,
+    };
+
+    let result = update(app, |jobs| {
+        if let Some(entry) = jobs.iter_mut().find(|entry| entry.id == job.id) {
+            record_outcome(entry, &outcome, now_ms());
+        }
+    });
+    if let Err(e) = result {
+        log_warn(&format!("Failed to update retry queue: {}", e));
+    }
+
+    match outcome {
+        Ok(Some(_)) => {
+            log_info(&format!("Recovered transcription of {}", job.id));
+            remove_backup(Path::new(&job.audio_path));
+        }
+        Ok(None) => log_info(&format!("Retried recording {} has no speech", job.id)),
+        Err(e) => log_warn(&format!("Retry of {} failed: {}", job.id, e)),
+    }
+
+    if recovered {
+        let _ = app
+            .notification()
+            .builder()
+            .title("TTP")
+            .body("Recovered a dictation that failed earlier — copy it from History")
+            .show();
+        crate::telemetry::analytics::track(app, "transcription_recovered", Some(serde_json::json!({
+            "attempts": job.attempts + 1
+        })));
+    }
+}
+
+/// Update a job after an attempt: done, pending again with backoff, or failed
+fn record_outcome(entry: &mut RetryJob, outcome: &Result<Option<RecoveredText>, PipelineError>, now: i64) {
+    entry.attempts += 1;
+    match outcome {
+        Ok(Some(text)) => {
+            entry.status = RetryStatus::Done;
+            entry.text = Some(text.final_text.clone());
+            entry.last_error = None;
+        }
+        Ok(None) => {
+            entry.status = RetryStatus::Failed;
+            entry.last_error = Some(PipelineError::NoSpeech.user_message());
+        }
+        Err(e) => {
+            entry.last_error = Some(e.user_message());
+            entry.status = if e.is_retryable() && entry.attempts < MAX_ATTEMPTS {
+                entry.next_attempt_at = now + backoff(entry.attempts).as_millis() as i64;
+                RetryStatus::Pending
+            } else {
+                RetryStatus::Failed
+            };
+        }
+    }
+}
+
+/// Text recovered from a queued recording
+struct RecoveredText {
+    final_text: String,
+    /// Unpolished text, when polish ran
+    raw_text: Option<String>,
+}
+
+/// Transcribe, filter and polish a backup; `None` when it holds no speech
+async fn transcribe_job(job: &RetryJob) -> Result<Option<RecoveredText>, PipelineError> {
+    validate_wav(&job.audio_path)?;
+    let settings = get_settings();
+
+    let converted = convert_to_mono_16khz(&job.audio_path, &settings.transcription.preprocessing)
+        .unwrap_or_else(|e| {
+            log_warn(&format!("Retry conversion failed, sending original: {}", e));
+            job.audio_path.clone()
+        });
+    let cleanup_converted = || {
+        if converted != job.audio_path {
+            let _ = std::fs::remove_file(&converted);
+        }
+    };
+
+    let translate = job.mode == PipelineMode::Translate;
+    let task = if translate && whisper_translates(&settings) {
+        TranscriptionTask::TranslateToEnglish
+    } else {
+        TranscriptionTask::Transcribe
+    };
+
+    let key = get_provider_api_key_internal(settings.transcription.provider.into());
+    let prompt = whisper_prompt(settings.transcription.language());
+    let transcription =
+        transcribe_file(&settings.transcription, key.as_deref(), &converted, prompt.as_deref(), task).await;
+    cleanup_converted();
+
+    let raw_text = transcription?;
+    if raw_text.trim().is_empty() {
+        return Ok(None);
+    }
+    if let Some(rule) = detect_hallucination(&raw_text, settings.transcription.language()) {
+        log_info(&format!("Retried recording {} filtered as hallucination: {}", job.id, rule));
+        return Ok(None);
+    }
+
+    let polished = if translate && task == TranscriptionTask::Transcribe {
+        Some(translate_transcript(&settings, &raw_text).await?)
+    } else if settings.ai_polish_enabled {
+        match crate::llm::resolve_task(&settings.polish_llm, crate::llm::LlmTask::Polish) {
+            Ok(config) => Some(polish_transcript(&config, &raw_text, &settings.polish_style, settings.transcription.language()).await),
+            Err(e) => {
+                log_warn(&format!("Polish LLM unavailable for recovered text: {}", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(Some(match polished {
+        Some(text) => RecoveredText {
+            final_text: finish_text(&settings, &text, job.mode),
+            raw_text: Some(raw_text),
+        },
+        None => RecoveredText {
+            final_text: finish_text(&settings, &raw_text, job.mode),
+            raw_text: None,
+        },
+    }))
+}
+
+/// Tauri command: list queued recordings, oldest first
+#[tauri::command]
+pub fn get_retry_jobs(app: AppHandle) -> Vec<RetryJob> {
+    let _guard = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut jobs = load(&app);
+    jobs.sort_by_key(|job| job.created_at);
+    jobs
+}
+
+/// Tauri command: retry a pending or failed job right away
+#[tauri::command]
+pub fn retry_job_now(app: AppHandle, id: String) -> Result<(), String> {
+    update(&app, |jobs| {
+        let job = jobs
+            .iter_mut()
+            .find(|job| job.id == id)
+            .ok_or("Recording not found in retry queue")?;
+        match job.status {
+            RetryStatus::Pending | RetryStatus::Failed => {
+                if !Path::new(&job.audio_path).exists() {
+                    return Err("The recording's audio file is gone".to_string());
+                }
+                job.status = RetryStatus::Pending;
+                job.next_attempt_at = 0;
+                Ok(())
+            }
+            RetryStatus::Running => Err("Already retrying".to_string()),
+            RetryStatus::Done => Err("Already transcribed".to_string()),
+        }
+    })??;
+
+    wake_signal().notify_one();
+    Ok(())
+}
+
+/// Tauri command: drop a job and delete its audio
+#[tauri::command]
+pub fn discard_retry_job(app: AppHandle, id: String) -> Result<(), String> {
+    let removed = update(&app, |jobs| remove_job(jobs, &id))??;
+
+    if Path::new(&removed.audio_path).exists() {
+        remove_backup(Path::new(&removed.audio_path));
+    }
+    Ok(())
+}
+
+/// Take a job out of the queue unless it is running
+fn remove_job(jobs: &mut Vec<RetryJob>, id: &str) -> Result<RetryJob, String> {
+    let index = jobs
+        .iter()
+        .position(|job| job.id == id)
+        .ok_or("Recording not found in retry queue")?;
+    if jobs[index].status == RetryStatus::Running {
+        return Err("Can't discard while retrying".to_string());
+    }
+    Ok(jobs.remove(index))
+}
+
+/// Tauri command: copy a recovered job's text to the clipboard
+#[tauri::command]
+pub fn copy_retry_job_text(app: AppHandle, id: String) -> Result<(), String> {
+    let text = get_retry_jobs(app.clone())
+        .into_iter()
+        .find(|job| job.id == id)
+        .and_then(|job| job.text)
+        .ok_or("No recovered text for this recording")?;
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(0), BASE_BACKOFF);
+        assert_eq!(backoff(1), BASE_BACKOFF * 2);
+        assert_eq!(backoff(3), BASE_BACKOFF * 8);
+        assert_eq!(backoff(10), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    fn job(id: &str, status: RetryStatus, next_attempt_at: i64) -> RetryJob {
+        RetryJob {
+            id: id.to_string(),
+            audio_path: format!("/backups/{}", id),
+            created_at: 0,
+            attempts: 0,
+            next_attempt_at,
+            last_error: None,
+            status,
+            text: None,
+            mode: PipelineMode::Dictate,
+        }
+    }
+
+    #[test]
+    fn claims_the_earliest_due_pending_job() {
+        let mut jobs = vec![
+            job("later", RetryStatus::Pending, 2_000),
+            job("failed", RetryStatus::Failed, 0),
+            job("due", RetryStatus::Pending, 500),
+            job("first", RetryStatus::Pending, 100),
+        ];
+        assert_eq!(claim_due(&mut jobs, 1_000).unwrap().id, "first");
+        assert_eq!(jobs[3].status, RetryStatus::Running);
+        assert_eq!(claim_due(&mut jobs, 1_000).unwrap().id, "due");
+        assert!(claim_due(&mut jobs, 1_000).is_none());
+        assert_eq!(jobs[0].status, RetryStatus::Pending);
+    }
+
+    #[test]
+    fn worker_backs_off_when_the_queue_cannot_be_saved() {
+        let jobs = vec![job("due", RetryStatus::Pending, 0), job("later", RetryStatus::Pending, 60_000)];
+        assert_eq!(idle_wait(&jobs, 1_000, false), Duration::ZERO);
+        assert_eq!(idle_wait(&jobs, 1_000, true), SAVE_RETRY_DELAY);
+
+        let later = vec![job("later", RetryStatus::Pending, 60_000), job("done", RetryStatus::Done, 0)];
+        assert_eq!(idle_wait(&later, 1_000, false), Duration::from_millis(59_000));
+        assert_eq!(idle_wait(&[], 1_000, false), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn outcomes_update_the_job() {
+        let mut entry = job("a", RetryStatus::Running, 0);
+        record_outcome(&mut entry, &Err(PipelineError::Timeout), 1_000);
+        assert_eq!(entry.status, RetryStatus::Pending);
+        assert_eq!(entry.attempts, 1);
+        assert_eq!(entry.next_attempt_at, 1_000 + backoff(1).as_millis() as i64);
+        assert!(entry.last_error.is_some());
+
+        let recovered = RecoveredText { final_text: "Hello.".to_string(), raw_text: None };
+        record_outcome(&mut entry, &Ok(Some(recovered)), 2_000);
+        assert_eq!(entry.status, RetryStatus::Done);
+        assert_eq!(entry.text.as_deref(), Some("Hello."));
+        assert_eq!(entry.last_error, None);
+
+        let mut silent = job("b", RetryStatus::Running, 0);
+        record_outcome(&mut silent, &Ok(None), 0);
+        assert_eq!(silent.status, RetryStatus::Failed);
+
+        let mut permanent = job("c", RetryStatus::Running, 0);
+        record_outcome(&mut permanent, &Err(PipelineError::PayloadTooLarge), 0);
+        assert_eq!(permanent.status, RetryStatus::Failed);
+
+        let mut exhausted = job("d", RetryStatus::Running, 0);
+        exhausted.attempts = MAX_ATTEMPTS - 1;
+        record_outcome(&mut exhausted, &Err(PipelineError::Timeout), 0);
+        assert_eq!(exhausted.status, RetryStatus::Failed);
+    }
+
+    #[test]
+    fn discard_skips_running_jobs() {
+        let mut jobs = vec![job("running", RetryStatus::Running, 0), job("failed", RetryStatus::Failed, 0)];
+        assert!(remove_job(&mut jobs, "running").is_err());
+        assert!(remove_job(&mut jobs, "missing").is_err());
+        assert_eq!(remove_job(&mut jobs, "failed").unwrap().id, "failed");
+        assert_eq!(jobs.len(), 1);
+    }
+}
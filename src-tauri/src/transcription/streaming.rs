@@ -0,0 +1,347 @@
+// TTP - Talk To Paste
+// Streaming transcription while recording
+//
+// When `transcription.streaming` is on, the recorder's cpal stream also
+// feeds mono PCM into a session here. Whenever enough audio has built up,
+// it is cut at the latest pause, converted to 16kHz and transcribed with the
+// configured provider (cloud or local). The running transcript is emitted to
+// the pill as `partial-transcript`. When recording stops, the pipeline only
+// waits for the tail instead of the whole file.
+
+use super::convert::convert_to_mono_16khz;
+use super::error::PipelineError;
+use super::provider::TranscriptionTask;
+use super::transcribe_audio;
+use crate::audio_monitor::rms_i16;
+use crate::credentials::get_provider_api_key_internal;
+use crate::logging::{log_info, log_warn};
+use crate::settings::{get_settings, TranscriptionMode, TranscriptionSettings};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::time::sleep;
+
+/// Don't send a segment before this much audio has built up
+const MIN_SEGMENT_SECS: f32 = 6.0;
+
+/// Cut at the quietest point even without a real pause past this length
+const MAX_SEGMENT_SECS: f32 = 20.0;
+
+/// RMS below this counts as a pause between words
+const PAUSE_RMS: f32 = 0.01;
+
+/// RMS analysis frame length
+const FRAME_MS: u32 = 30;
+
+/// Tails shorter than this are dropped (Whisper hallucinates on blips)
+const MIN_TAIL_SECS: f32 = 0.3;
+
+/// How often the worker checks for new audio
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Characters of the transcript so far passed as the prompt for the next segment
+const PROMPT_CONTEXT_CHARS: usize = 200;
+
+/// Partial transcript event sent to the pill
+#[derive(Clone, serde::Serialize)]
+pub struct PartialTranscript {
+    pub text: String,
+}
+
+struct Session {
+    task: JoinHandle<Result<String, PipelineError>>,
+}
+
+/// The session of the current recording, if streaming is on
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Start a streaming session for a new recording
+///
+/// Called by the recorder once the input stream's rate is known.
+/// Returns the sender the recorder pushes mono i16 samples into, or
+/// `None` when streaming is off or the provider can't be used. Dropping the
+/// sender (when the recording stops) closes the session.
+pub fn start(app: &AppHandle, sample_rate: u32) -> Option<Sender<Vec<i16>>> {
+    let settings = get_settings().transcription;
+    if !settings.streaming {
+        return None;
+    }
+
+    let api_key = get_provider_api_key_internal(settings.provider.into());
+    if api_key.is_none()
+        && settings.mode == TranscriptionMode::Cloud
+        && super::provider::requires_api_key(settings.provider)
+    {
+        // The pipeline reports the missing key once recording stops
+        return None;
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let id = NEXT_SESSION_ID.fetch_add(1, Ordering::SeqCst);
+    let task = tauri::async_runtime::spawn(run_session(
+        app.clone(),
+        receiver,
+        sample_rate,
+        settings,
+        api_key,
+        id,
+    ));
+
+    if let Ok(mut session) = SESSION.lock() {
+        // A session left over from an abandoned recording
+        if let Some(previous) = session.replace(Session { task }) {
+            previous.task.abort();
+        }
+    }
+    eprintln!("[Streaming] Session {} started at {}Hz", id, sample_rate);
+    Some(sender)
+}
+
+/// Wait for the current session to transcribe its tail and return the full text
+///
+/// `None` when no session ran for this recording. An error means the caller
+/// should fall back to transcribing the recorded file.
+pub async fn finish() -> Option<Result<String, PipelineError>> {
+    let session = SESSION.lock().ok()?.take()?;
+    Some(
+        session
+            .task
+            .await
+            .map_err(|e| PipelineError::Io(format!("Streaming task failed: {}", e)))
+            .and_then(|result| result),
+    )
+}
+
+/// Drop the current session without waiting for it (recording discarded)
+pub fn cancel() {
+    if let Some(session) = SESSION.lock().ok().and_then(|mut s| s.take()) {
+        session.task.abort();
+    }
+}
+
+/// Worker: collect audio, transcribe a segment at each pause, emit partials
+async fn run_session(
+    app: AppHandle,
+    receiver: Receiver<Vec<i16>>,
+    sample_rate: u32,
+    settings: TranscriptionSettings,
+    api_key: Option<String>,
+    id: u64,
+) -> Result<String, PipelineError> {
+    let mut pending: Vec<i16> = Vec::new();
+    let mut transcript = String::new();
+    let mut segment = 0usize;
+
+    loop {
+        // Drain everything captured since the last poll
+        let mut recording_done = false;
+        loop {
+            match receiver.try_recv() {
+                Ok(samples) => pending.extend(samples),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    recording_done = true;
+                    break;
+                }
+            }
+        }
+        if recording_done {
+            break;
+        }
+
+        let Some(cut) = find_segment_end(&pending, sample_rate) else {
+            sleep(POLL_INTERVAL).await;
+            continue;
+        };
+
+        let samples: Vec<i16> = pending.drain(..cut).collect();
+        let text = transcribe_segment(&samples, sample_rate, &settings, api_key.as_deref(), &transcript, id, segment)
+            .await?;
+        segment += 1;
+        append_text(&mut transcript, &text);
+        app.emit("partial-transcript", &PartialTranscript { text: transcript.clone() }).ok();
+    }
+
+    // Recording stopped — transcribe whatever came after the last cut
+    if pending.len() as f32 / sample_rate as f32 >= MIN_TAIL_SECS {
+        let text = transcribe_segment(&pending, sample_rate, &settings, api_key.as_deref(), &transcript, id, segment)
+            .await?;
+        segment += 1;
+        append_text(&mut transcript, &text);
+    }
+
+    log_info(&format!("Streaming session {} transcribed {} segments", id, segment));
+    Ok(transcript)
+}
+
+/// Where to end the next segment, if enough audio is pending
+///
+/// Prefers the latest pause after `MIN_SEGMENT_SECS`; past
+/// `MAX_SEGMENT_SECS` takes the quietest frame instead.
+fn find_segment_end(samples: &[i16], sample_rate: u32) -> Option<usize> {
+    let min_len = (MIN_SEGMENT_SECS * sample_rate as f32) as usize;
+    let max_len = (MAX_SEGMENT_SECS * sample_rate as f32) as usize;
+    let frame = (sample_rate * FRAME_MS / 1000).max(1) as usize;
+    if samples.len() < min_len {
+        return None;
+    }
+
+    let mut latest_pause = None;
+    let mut quietest = None;
+    let mut quietest_rms = f32::MAX;
+    let mut pos = min_len;
+    while pos + frame <= samples.len().min(max_len) {
+        let rms = rms_i16(&samples[pos..pos + frame]);
+        if rms < PAUSE_RMS {
+            latest_pause = Some(pos + frame / 2);
+        }
+        if rms < quietest_rms {
+            quietest_rms = rms;
+            quietest = Some(pos + frame / 2);
+        }
+        pos += frame;
+    }
+
+    match latest_pause {
+        Some(cut) => Some(cut),
+        None if samples.len() >= max_len => quietest.or(Some(max_len)),
+        None => None,
+    }
+}
+
+/// Write a segment to disk, convert it and send it to the provider
+async fn transcribe_segment(
+    samples: &[i16],
+    sample_rate: u32,
+    settings: &TranscriptionSettings,
+    api_key: Option<&str>,
+    transcript: &str,
+    id: u64,
+    index: usize,
+) -> Result<String, PipelineError> {
+    // Silent stretches only make Whisper hallucinate ("Thank you.")
+    if rms_i16(samples) < PAUSE_RMS {
+        return Ok(String::new());
+    }
+
+    let path = std::env::temp_dir().join(format!("ttp_stream_{}_{}.wav", id, index));
+    let path = path.to_string_lossy().to_string();
+    write_segment(&path, samples, sample_rate)?;
+
+    let converted = convert_to_mono_16khz(&path, &settings.preprocessing).unwrap_or_else(|e| {
+        log_warn(&format!("Streaming segment conversion failed, sending original: {}", e));
+        path.clone()
+    });
+
+    let base = super::pipeline::whisper_prompt(settings.language());
+    let prompt = segment_prompt(base.as_deref(), transcript);
+
+    let result = transcribe_audio(settings, api_key, &converted, prompt.as_deref(), TranscriptionTask::Transcribe).await;
+
+    let _ = std::fs::remove_file(&path);
+    if converted != path {
+        let _ = std::fs::remove_file(&converted);
+    }
+    result
+}
+
+/// Prompt for the next segment: the pipeline's language hint and glossary,
+/// then the end of the text so far
+///
+/// The tail keeps Whisper's casing and spelling consistent across segments.
+/// It goes last because Whisper drops the start of an over-long prompt.
+fn segment_prompt(base: Option<&str>, transcript: &str) -> Option<String> {
+    let context: String = {
+        let chars: Vec<char> = transcript.chars().collect();
+        chars[chars.len().saturating_sub(PROMPT_CONTEXT_CHARS)..].iter().collect()
+    };
+    let parts: Vec<&str> = [base.unwrap_or(""), context.trim()]
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+/// Mono 16-bit WAV at the device rate
+fn write_segment(path: &str, samples: &[i16], sample_rate: u32) -> Result<(), PipelineError> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(path, spec)
+        .map_err(|e| PipelineError::Io(format!("Failed to create segment WAV: {}", e)))?;
+    for sample in samples {
+        writer
+            .write_sample(*sample)
+            .map_err(|e| PipelineError::Io(format!("Failed to write segment sample: {}", e)))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| PipelineError::Io(format!("Failed to finalize segment WAV: {}", e)))
+}
+
+fn append_text(transcript: &mut String, text: &str) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    if !transcript.is_empty() {
+        transcript.push(' ');
+    }
+    transcript.push_str(text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_waits_for_minimum_length() {
+        let rate = 1000;
+        let samples = vec![0i16; (MIN_SEGMENT_SECS * rate as f32) as usize - 1];
+        assert_eq!(find_segment_end(&samples, rate), None);
+    }
+
+    #[test]
+    fn segment_ends_at_latest_pause() {
+        // 7s speech, 0.3s pause, 1s speech at 1kHz
+        let rate = 1000;
+        let mut samples = vec![8000i16; 7000];
+        samples.extend(vec![0i16; 300]);
+        samples.extend(vec![8000i16; 1000]);
+
+        let cut = find_segment_end(&samples, rate).expect("cut at the pause");
+        assert!((7000..7300).contains(&cut), "cut at {}", cut);
+    }
+
+    #[test]
+    fn continuous_speech_waits_until_maximum_length() {
+        let rate = 1000;
+        let short = vec![8000i16; 10_000];
+        assert_eq!(find_segment_end(&short, rate), None);
+
+        let long = vec![8000i16; (MAX_SEGMENT_SECS * rate as f32) as usize + 500];
+        assert!(find_segment_end(&long, rate).is_some());
+    }
+
+    #[test]
+    fn segment_prompt_keeps_glossary_and_tail() {
+        assert_eq!(segment_prompt(None, ""), None);
+        assert_eq!(segment_prompt(Some("Glossary: Tauri"), "").as_deref(), Some("Glossary: Tauri"));
+        assert_eq!(segment_prompt(None, "Hello there.").as_deref(), Some("Hello there."));
+
+        let transcript = "x".repeat(PROMPT_CONTEXT_CHARS) + " We ship Tauri.";
+        let prompt = segment_prompt(Some("Glossary: Tauri"), &transcript).unwrap();
+        assert!(prompt.starts_with("Glossary: Tauri "));
+        assert!(prompt.ends_with("We ship Tauri."));
+        assert_eq!(prompt.chars().count(), "Glossary: Tauri ".len() + PROMPT_CONTEXT_CHARS);
+    }
+}
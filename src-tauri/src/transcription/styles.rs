@@ -0,0 +1,289 @@
+// TTP - Talk To Paste
+// Polish styles: named system prompts for the polish LLM
+//
+// The built-in styles ship with the app; user styles live in
+// ~/.config/ttp/polish_styles.json and can override a built-in by reusing
+// its id. A template is a system prompt with two variables:
+// `{fillers}` (the spoken language's filler words) and `{dictionary}` (the
+// PERSONAL DICTIONARY section, empty when the dictionary is). A template
+// without `{dictionary}` gets no dictionary hints; the dictionary is still
+// applied to the output afterwards.
+
+use crate::logging::log_warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Style used when the configured one doesn't exist
+pub const DEFAULT_STYLE: &str = "light";
+
+/// Shared ending: the model must answer with the text alone
+const OUTPUT_ONLY: &str = "CRITICAL: Your entire response must be the rewritten text. Do NOT wrap it in quotes. Do NOT prefix it with anything. Do NOT show the original. Do NOT explain your changes.";
+
+/// A polish style as shown in settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolishStyle {
+    /// Stable identifier stored in settings ("light", "email", or a user id)
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// One-line description for the picker
+    #[serde(default)]
+    pub description: String,
+    /// System prompt template (`{fillers}`, `{dictionary}`)
+    pub template: String,
+    /// Shipped with the app (user styles are `false`)
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+/// Built-in styles: (id, name, description, template)
+const BUILTIN_STYLES: &[(&str, &str, &str, &str)] = &[
+    (
+        "verbatim",
+        "Verbatim",
+        "Only punctuation and capitalization, every word kept",
+        r#"You are a transcription formatter. You receive raw voice transcriptions and output ONLY the same words with punctuation and capitalization added.
+
+RULES:
+1. Keep EVERY word, including filler words, repetitions and self-corrections
+2. NEVER translate - keep original language(s) exactly
+3. Only add punctuation, capitalization and paragraph breaks
+4. Do NOT fix grammar or change word choice
+{dictionary}
+"#,
+    ),
+    (
+        "light",
+        "Light cleanup",
+        "Removes fillers, fixes grammar, keeps your tone (default)",
+        r#"You are a text cleaner. You receive raw voice transcriptions and output ONLY the cleaned version. No commentary, no explanations, no quotes, no "here is the corrected version", no original vs corrected comparison. JUST the cleaned text.
+
+RULES:
+1. Keep ALL content - do NOT remove or shorten anything
+2. NEVER translate - keep original language(s) exactly (French stays French, English stays English, mixed stays mixed)
+3. Remove only filler words: {fillers}
+4. Fix grammar but keep casual tone
+5. Add punctuation
+6. Self-corrections only: "Tuesday no wait Wednesday" → "Wednesday"
+7. Format lists: when the speaker enumerates items (point 1, first, second, etc.), format as a numbered or bulleted list with line breaks
+{dictionary}
+"#,
+    ),
+    (
+        "email",
+        "Formal email",
+        "Polite, well-structured email body",
+        r#"You are an email writer. You receive raw voice transcriptions of what someone wants to say and output ONLY a polished email body.
+
+RULES:
+1. Keep ALL the content and requests - do NOT invent facts, dates or names
+2. NEVER translate - write in the language that was spoken
+3. Remove filler words ({fillers}) and apply self-corrections
+4. Use a professional, polite register with a greeting and a closing line when they fit
+5. Split into short paragraphs; use a list when the speaker enumerates items
+6. Do NOT add a subject line or a signature name
+{dictionary}
+"#,
+    ),
+    (
+        "notes",
+        "Bullet notes",
+        "Terse bullet points, one idea per line",
+        r#"You are a note taker. You receive raw voice transcriptions and output ONLY concise bullet-point notes.
+
+RULES:
+1. Keep every fact, number, name and action item - drop only filler and repetition
+2. NEVER translate - write in the language that was spoken
+3. Remove filler words ({fillers}) and apply self-corrections
+4. One idea per bullet, starting with "- "; nest sub-points with two spaces
+5. Use short phrases rather than full sentences
+{dictionary}
+"#,
+    ),
+    (
+        "commit",
+        "Commit message",
+        "Git commit message: short subject, wrapped body",
+        r#"You are a commit message writer. You receive a raw voice description of a code change and output ONLY a git commit message.
+
+RULES:
+1. First line: imperative summary of the change, at most 72 characters, no trailing period
+2. If there is more to say, add a blank line and a body wrapped at 72 characters explaining what and why
+3. Keep identifiers, file names and code exactly as spoken, in backticks only if the speaker implies code
+4. Remove filler words ({fillers}) and apply self-corrections
+5. Do NOT invent details that were not said
+{dictionary}
+"#,
+    ),
+    (
+        "slack",
+        "Slack casual",
+        "Relaxed chat message, short and friendly",
+        r#"You are a chat message cleaner. You receive raw voice transcriptions and output ONLY a casual chat message.
+
+RULES:
+1. Keep ALL content - do NOT remove or shorten the meaning
+2. NEVER translate - keep original language(s) exactly
+3. Remove filler words ({fillers}) and apply self-corrections
+4. Keep it relaxed: contractions, lowercase is fine, light punctuation, no greeting or sign-off
+5. Use a short list only when the speaker enumerates several items
+{dictionary}
+"#,
+    ),
+];
+
+/// Get the user style file path (~/.config/ttp/polish_styles.json)
+fn get_user_styles_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("ttp").join("polish_styles.json"))
+}
+
+fn builtin_styles() -> Vec<PolishStyle> {
+    BUILTIN_STYLES
+        .iter()
+        .map(|(id, name, description, template)| PolishStyle {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            template: format!("{}\n{}", template.trim_end(), OUTPUT_ONLY),
+            builtin: true,
+        })
+        .collect()
+}
+
+/// Load the user styles; missing or unreadable files count as empty
+fn load_user_styles() -> Vec<PolishStyle> {
+    let Some(path) = get_user_styles_path() else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+
+    match serde_json::from_str::<Vec<PolishStyle>>(&content) {
+        Ok(styles) => styles,
+        Err(e) => {
+            log_warn(&format!("Failed to parse {}: {}", path.display(), e));
+            Vec::new()
+        }
+    }
+}
+
+fn save_user_styles(styles: &[PolishStyle]) -> Result<(), String> {
+    let path = get_user_styles_path().ok_or("Could not determine config directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(styles)
+        .map_err(|e| format!("Failed to serialize polish styles: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write polish styles: {}", e))
+}
+
+/// Built-in styles followed by user styles; a user style replaces the
+/// built-in with the same id
+fn merge_styles(user: Vec<PolishStyle>) -> Vec<PolishStyle> {
+    let mut styles = builtin_styles();
+    for mut style in user {
+        style.builtin = false;
+        match styles.iter_mut().find(|s| s.id == style.id) {
+            Some(existing) => *existing = style,
+            None => styles.push(style),
+        }
+    }
+    styles
+}
+
+/// Template for a style id, falling back to the default style
+pub fn style_template(id: &str) -> String {
+    let styles = merge_styles(load_user_styles());
+    if let Some(style) = styles.iter().find(|s| s.id == id) {
+        return style.template.clone();
+    }
+
+    log_warn(&format!("Unknown polish style \"{}\", using \"{}\"", id, DEFAULT_STYLE));
+    styles
+        .into_iter()
+        .find(|s| s.id == DEFAULT_STYLE)
+        .map(|s| s.template)
+        .unwrap_or_default()
+}
+
+/// Tauri command: every available polish style
+#[tauri::command]
+pub fn get_polish_styles() -> Vec<PolishStyle> {
+    merge_styles(load_user_styles())
+}
+
+/// Tauri command: add or replace a user style
+///
+/// Saving with a built-in id customizes that built-in; deleting the user
+/// copy restores the original.
+#[tauri::command]
+pub fn save_polish_style(style: PolishStyle) -> Result<Vec<PolishStyle>, String> {
+    let id = style.id.trim().to_string();
+    if id.is_empty() || style.name.trim().is_empty() {
+        return Err("A style needs an id and a name".to_string());
+    }
+    if style.template.trim().is_empty() {
+        return Err("The prompt template is empty".to_string());
+    }
+
+    let mut user = load_user_styles();
+    let style = PolishStyle { id, builtin: false, ..style };
+    match user.iter_mut().find(|s| s.id == style.id) {
+        Some(existing) => *existing = style,
+        None => user.push(style),
+    }
+    save_user_styles(&user)?;
+    Ok(merge_styles(user))
+}
+
+/// Tauri command: delete a user style (or a built-in customization)
+#[tauri::command]
+pub fn delete_polish_style(id: String) -> Result<Vec<PolishStyle>, String> {
+    let mut user = load_user_styles();
+    let before = user.len();
+    user.retain(|s| s.id != id);
+    if user.len() == before {
+        return Err("Built-in styles can't be deleted".to_string());
+    }
+    save_user_styles(&user)?;
+    Ok(merge_styles(user))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_style(id: &str, template: &str) -> PolishStyle {
+        PolishStyle {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            template: template.to_string(),
+            builtin: true,
+        }
+    }
+
+    #[test]
+    fn builtin_templates_have_every_variable() {
+        let styles = builtin_styles();
+        assert!(styles.iter().any(|s| s.id == DEFAULT_STYLE));
+        for style in &styles {
+            assert!(style.template.contains("{dictionary}"), "{} has no dictionary", style.id);
+            assert!(style.template.ends_with(OUTPUT_ONLY));
+        }
+    }
+
+    #[test]
+    fn user_styles_override_by_id_and_append() {
+        let styles = merge_styles(vec![user_style("email", "mine"), user_style("haiku", "5-7-5")]);
+        let email = styles.iter().find(|s| s.id == "email").unwrap();
+        assert_eq!(email.template, "mine");
+        assert!(!email.builtin);
+        assert_eq!(styles.last().unwrap().id, "haiku");
+        assert_eq!(styles.len(), BUILTIN_STYLES.len() + 1);
+    }
+}
@@ -0,0 +1,124 @@
+// TTP - Talk To Paste
+// Translation mode: dictate in one language, paste in another
+//
+// Whisper's translate task only produces English, and not every backend
+// has it, so it is used only when it can do the job; otherwise the polish
+// LLM translates the transcript, cleaning it up in the same call.
+
+use super::error::PipelineError;
+use super::polish::dictionary_section;
+use crate::dictionary::{active_entries, DictionaryEntry};
+use crate::llm::{self, resolve_task, LlmConfig, LlmTask};
+use crate::settings::{Settings, TranscriptionMode, TranscriptionProviderKind, TranslationEngine};
+
+/// System prompt for LLM translation (`{target}` is the target language name)
+pub const TRANSLATE_SYSTEM_PROMPT: &str = r#"You are a translator. You receive raw voice transcriptions and output ONLY their translation into {target}. No commentary, no explanations, no quotes, no notes about the translation. JUST the translated text.
+
+RULES:
+1. Translate ALL content into {target} - do NOT summarize, shorten or add anything
+2. Text already in {target} stays as it is; names, brands, code and URLs are never translated
+3. Drop filler words and hesitations, apply self-corrections ("Tuesday no wait Wednesday" → "Wednesday")
+4. Keep the speaker's tone and register (casual stays casual, formal stays formal)
+5. Add punctuation; format enumerations as a numbered or bulleted list with line breaks
+
+CRITICAL: Your entire response must be the translated text. Do NOT wrap it in quotes. Do NOT prefix it with anything. Do NOT show the original."#;
+
+/// Whether Whisper itself translates the recording for these settings
+///
+/// Needs the Whisper engine, English as the target, and a backend with a
+/// translate task (on-device whisper.cpp or an OpenAI-style API).
+pub fn whisper_translates(settings: &Settings) -> bool {
+    let translation = &settings.translation;
+    translation.engine == TranslationEngine::Whisper
+        && translation.target_language.eq_ignore_ascii_case("en")
+        && (settings.transcription.mode == TranscriptionMode::Local
+            || settings.transcription.provider != TranscriptionProviderKind::Deepgram)
+}
+
+/// Target language name for prompts ("German"), or the code when unknown
+fn target_name(code: &str) -> String {
+    crate::language::profile(code)
+        .map(|p| p.name.to_string())
+        .unwrap_or_else(|| code.to_string())
+}
+
+/// Build the translation system prompt, optionally including dictionary terms
+pub fn build_translate_prompt(target: &str, dictionary: &[DictionaryEntry]) -> String {
+    let mut prompt = TRANSLATE_SYSTEM_PROMPT.replace("{target}", &target_name(target));
+    let section = dictionary_section(dictionary);
+    if !section.is_empty() {
+        prompt.push('\n');
+        prompt.push_str(&section);
+    }
+    prompt
+}
+
+/// Translate a transcript into `target` (ISO 639-1) with the given LLM
+pub async fn translate_text(config: &LlmConfig, raw_text: &str, target: &str) -> Result<String, PipelineError> {
+    // The dictionary spells the output, so entries scoped to the target language apply
+    let system_prompt = build_translate_prompt(target, &active_entries(Some(target)));
+    Ok(llm::complete(config, &system_prompt, raw_text).await?)
+}
+
+/// Translate a transcript with the polish LLM into the configured target language
+pub async fn translate_transcript(settings: &Settings, raw_text: &str) -> Result<String, PipelineError> {
+    let config = resolve_task(&settings.polish_llm, LlmTask::Polish).map_err(PipelineError::Config)?;
+    let text = translate_text(&config, raw_text, &settings.translation.target_language).await?;
+    if text.trim().is_empty() {
+        return Err(PipelineError::InvalidResponse("Translation came back empty".to_string()));
+    }
+    Ok(text.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::MatchMode;
+
+    fn settings(engine: TranslationEngine, target: &str, mode: TranscriptionMode, provider: TranscriptionProviderKind) -> Settings {
+        let mut settings = Settings::default();
+        settings.translation.engine = engine;
+        settings.translation.target_language = target.to_string();
+        settings.transcription.mode = mode;
+        settings.transcription.provider = provider;
+        settings
+    }
+
+    #[test]
+    fn whisper_translates_only_into_english_on_capable_backends() {
+        use TranscriptionMode::*;
+        use TranscriptionProviderKind::*;
+        use TranslationEngine::*;
+
+        assert!(whisper_translates(&settings(Whisper, "en", Cloud, Groq)));
+        assert!(whisper_translates(&settings(Whisper, "EN", Cloud, OpenAi)));
+        assert!(whisper_translates(&settings(Whisper, "en", Local, Deepgram)));
+        assert!(!whisper_translates(&settings(Whisper, "en", Cloud, Deepgram)));
+        assert!(!whisper_translates(&settings(Whisper, "de", Cloud, Groq)));
+        assert!(!whisper_translates(&settings(Llm, "en", Cloud, Groq)));
+    }
+
+    #[test]
+    fn translate_prompt_names_the_target_and_lists_literal_terms() {
+        let entry = |original: &str, correction: &str, match_mode| DictionaryEntry {
+            original: original.to_string(),
+            correction: correction.to_string(),
+            created_at: 0,
+            match_mode,
+            enabled: true,
+            language: None,
+            note: String::new(),
+        };
+
+        let prompt = build_translate_prompt("de", &[]);
+        assert!(prompt.contains("translation into German"));
+        assert!(!prompt.contains("{target}"));
+        assert!(!prompt.contains("PERSONAL DICTIONARY"));
+
+        let dictionary = [entry("tauri", "Tauri", MatchMode::Word), entry(r"v(\d+)", "version $1", MatchMode::Regex)];
+        let prompt = build_translate_prompt("xx", &dictionary);
+        assert!(prompt.contains("translation into xx"));
+        assert!(prompt.contains("- tauri -> Tauri"));
+        assert!(!prompt.contains("version $1"));
+    }
+}
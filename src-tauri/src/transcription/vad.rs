@@ -0,0 +1,282 @@
+// TTP - Talk To Paste
+// Voice activity detection before upload
+//
+// Energy-based VAD on 30ms frames with an adaptive noise floor. Runs right
+// after `validate_wav`: recordings without speech are dropped before any
+// API call (accidental key presses, mic muted), and leading/trailing
+// silence is trimmed so Whisper never sees long silent stretches — the
+// main trigger for "Thank you for watching" style hallucinations.
+
+use super::chunking::{open_wav, samples_i16};
+use super::error::PipelineError;
+use crate::audio_monitor::rms_i16;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::path::Path;
+
+/// Analysis frame length
+const FRAME_MS: u32 = 30;
+
+/// Frames quieter than this are never speech (~-44 dBFS)
+const MIN_SPEECH_RMS: f32 = 0.006;
+
+/// Upper bound for the adaptive threshold, so continuous speech (no quiet
+/// frames to learn the noise floor from) can't push it above soft syllables
+const MAX_SPEECH_RMS: f32 = 0.03;
+
+/// Speech must be this many times louder than the noise floor (~+10 dB)
+const NOISE_FLOOR_RATIO: f32 = 3.0;
+
+/// Noise floor = this percentile of frame levels
+const NOISE_PERCENTILE: f32 = 0.1;
+
+/// Consecutive loud frames needed to start speech (rejects clicks and bumps)
+const MIN_ONSET_FRAMES: usize = 3;
+
+/// Frames kept after speech drops below the threshold (word endings, short pauses)
+const HANGOVER_FRAMES: usize = 8;
+
+/// Less speech than this counts as none
+const MIN_SPEECH_SECS: f32 = 0.25;
+
+/// Silence kept around the speech when trimming
+const PADDING_SECS: f32 = 0.3;
+
+/// Don't rewrite the file to save less than this
+const MIN_TRIM_SECS: f32 = 0.5;
+
+/// What the VAD found in a recording
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeechReport {
+    /// Total duration of speech frames
+    pub speech_secs: f32,
+    /// Length of the recording
+    pub total_secs: f32,
+    /// Start of the first speech frame
+    pub start_secs: f32,
+    /// End of the last speech frame
+    pub end_secs: f32,
+}
+
+impl SpeechReport {
+    pub fn has_speech(&self) -> bool {
+        self.speech_secs >= MIN_SPEECH_SECS
+    }
+}
+
+/// Classify each frame as speech or not
+fn speech_frames(levels: &[f32]) -> Vec<bool> {
+    if levels.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sorted = levels.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let noise_floor = sorted[((sorted.len() - 1) as f32 * NOISE_PERCENTILE) as usize];
+    let threshold = (noise_floor * NOISE_FLOOR_RATIO).clamp(MIN_SPEECH_RMS, MAX_SPEECH_RMS);
+
+    let mut active = vec![false; levels.len()];
+    let mut run = 0usize;
+    let mut hangover = 0usize;
+    for (i, &level) in levels.iter().enumerate() {
+        if level > threshold {
+            run += 1;
+            if run == MIN_ONSET_FRAMES {
+                // Onset confirmed: the whole run was speech
+                active[i + 1 - run..i].fill(true);
+            }
+        } else {
+            run = 0;
+        }
+
+        if run >= MIN_ONSET_FRAMES {
+            active[i] = true;
+            hangover = HANGOVER_FRAMES;
+        } else if hangover > 0 {
+            active[i] = true;
+            hangover -= 1;
+        }
+    }
+    active
+}
+
+/// Run the VAD on interleaved i16 samples
+pub fn analyze(samples: &[i16], channels: usize, sample_rate: u32) -> SpeechReport {
+    let channels = channels.max(1);
+    let frame_len = (sample_rate * FRAME_MS / 1000).max(1) as usize;
+    let levels: Vec<f32> = samples
+        .chunks(frame_len * channels)
+        .map(rms_i16)
+        .collect();
+    report(&levels, frame_len, samples.len() / channels, sample_rate)
+}
+
+/// Build the report from per-frame levels
+fn report(levels: &[f32], frame_len: usize, total_frames: usize, sample_rate: u32) -> SpeechReport {
+    let frame_secs = frame_len as f32 / sample_rate as f32;
+    let total_secs = total_frames as f32 / sample_rate as f32;
+    let active = speech_frames(levels);
+
+    let speech_count = active.iter().filter(|&&a| a).count();
+    let first = active.iter().position(|&a| a).unwrap_or(0);
+    let last = active.iter().rposition(|&a| a).map(|i| i + 1).unwrap_or(0);
+
+    SpeechReport {
+        speech_secs: speech_count as f32 * frame_secs,
+        total_secs,
+        start_secs: first as f32 * frame_secs,
+        end_secs: (last as f32 * frame_secs).min(total_secs),
+    }
+}
+
+/// Run the VAD on a WAV, reading one analysis frame at a time
+fn analyze_file(path: &str) -> Result<(WavSpec, SpeechReport), PipelineError> {
+    let reader = open_wav(path)?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+    let sample_rate = spec.sample_rate.max(1);
+    let frame_len = (sample_rate * FRAME_MS / 1000).max(1) as usize;
+
+    let mut levels = Vec::new();
+    let mut frame = Vec::with_capacity(frame_len * channels);
+    let mut total_samples = 0usize;
+    for sample in samples_i16(reader) {
+        frame.push(sample);
+        total_samples += 1;
+        if frame.len() == frame.capacity() {
+            levels.push(rms_i16(&frame));
+            frame.clear();
+        }
+    }
+    if !frame.is_empty() {
+        levels.push(rms_i16(&frame));
+    }
+
+    Ok((spec, report(&levels, frame_len, total_samples / channels, sample_rate)))
+}
+
+/// Detect speech in a WAV and trim the silence around it
+///
+/// Returns the report and the path to transcribe: `<name>.trimmed.wav`
+/// when enough silence was cut (the caller deletes it), otherwise the input
+/// path. Nothing is written when the recording has no speech.
+pub fn trim_silence(path: &str) -> Result<(SpeechReport, String), PipelineError> {
+    let (spec, report) = analyze_file(path)?;
+    let channels = spec.channels.max(1) as usize;
+    let sample_rate = spec.sample_rate.max(1);
+
+    if !report.has_speech() {
+        return Ok((report, path.to_string()));
+    }
+
+    let start_secs = (report.start_secs - PADDING_SECS).max(0.0);
+    let end_secs = (report.end_secs + PADDING_SECS).min(report.total_secs);
+    if start_secs + (report.total_secs - end_secs) < MIN_TRIM_SECS {
+        return Ok((report, path.to_string()));
+    }
+
+    let start = (start_secs * sample_rate as f32) as usize * channels;
+    let end = (end_secs * sample_rate as f32) as usize * channels;
+
+    let output = Path::new(path)
+        .with_extension("trimmed.wav")
+        .to_string_lossy()
+        .to_string();
+    let out_spec = WavSpec {
+        channels: channels as u16,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(&output, out_spec)
+        .map_err(|e| PipelineError::Io(format!("Failed to create trimmed WAV: {}", e)))?;
+    for sample in samples_i16(open_wav(path)?).skip(start).take(end.saturating_sub(start)) {
+        writer
+            .write_sample(sample)
+            .map_err(|e| PipelineError::Io(format!("Failed to write trimmed sample: {}", e)))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| PipelineError::Io(format!("Failed to finalize trimmed WAV: {}", e)))?;
+
+    Ok((report, output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 200Hz tone at the given amplitude
+    fn tone(secs: f32, amplitude: f32, rate: u32) -> Vec<i16> {
+        (0..(secs * rate as f32) as usize)
+            .map(|i| {
+                let t = i as f32 / rate as f32;
+                ((2.0 * std::f32::consts::PI * 200.0 * t).sin() * amplitude * 32767.0) as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn silence_has_no_speech() {
+        let rate = 16_000;
+        let samples = vec![0i16; rate as usize * 3];
+        assert!(!analyze(&samples, 1, rate).has_speech());
+    }
+
+    #[test]
+    fn isolated_click_is_not_speech() {
+        let rate = 16_000;
+        let mut samples = vec![0i16; rate as usize];
+        samples.extend(tone(0.03, 0.5, rate));
+        samples.extend(vec![0i16; rate as usize]);
+        assert!(!analyze(&samples, 1, rate).has_speech());
+    }
+
+    #[test]
+    fn speech_bounds_are_found_in_noise() {
+        // 1s low noise, 1.5s "speech", 1s low noise
+        let rate = 16_000;
+        let mut samples = tone(1.0, 0.002, rate);
+        samples.extend(tone(1.5, 0.2, rate));
+        samples.extend(tone(1.0, 0.002, rate));
+
+        let report = analyze(&samples, 1, rate);
+        assert!(report.has_speech());
+        assert!((report.speech_secs - 1.5).abs() < 0.35, "speech {}", report.speech_secs);
+        assert!((report.start_secs - 1.0).abs() < 0.05, "start {}", report.start_secs);
+        assert!(report.end_secs > 2.4 && report.end_secs < 2.8, "end {}", report.end_secs);
+    }
+
+    #[test]
+    fn eight_bit_speech_is_trimmed_not_dropped() {
+        // 1s silence, 1.5s speech, 1s silence as 8-bit PCM
+        let rate = 16_000;
+        let mut samples = vec![0i16; rate as usize];
+        samples.extend(tone(1.5, 0.2, rate));
+        samples.extend(vec![0i16; rate as usize]);
+
+        let path = std::env::temp_dir().join(format!("ttp_vad_test_{}.wav", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: rate,
+            bits_per_sample: 8,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        for sample in &samples {
+            writer.write_sample((*sample >> 8) as i8).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (report, trimmed) = trim_silence(&path).unwrap();
+        let trimmed_secs = hound::WavReader::open(&trimmed).map(|r| r.duration() as f32 / rate as f32);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&trimmed);
+
+        assert!(report.has_speech());
+        assert!((report.start_secs - 1.0).abs() < 0.05, "start {}", report.start_secs);
+        let trimmed_secs = trimmed_secs.unwrap();
+        // Speech plus hangover and padding, well under the 3.5s input
+        assert!(trimmed_secs > 2.0 && trimmed_secs < 2.6, "trimmed to {}s", trimmed_secs);
+    }
+}
@@ -1,7 +1,7 @@
 // TTP - Talk To Paste
 // System tray setup and management
 
-use crate::settings::get_settings;
+use crate::settings::{get_settings, PipelineMode};
 use crate::sounds::{play_start_sound, play_stop_sound};
 use crate::state::{AppState, RecordingState};
 use std::sync::Mutex;
@@ -9,18 +9,38 @@ use tauri::{
     image::Image,
     menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::TrayIconBuilder,
-    AppHandle, Listener, Manager,
+    AppHandle, Listener, Manager, Wry,
 };
 
-pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    // Create menu items
-    let record = MenuItem::with_id(app, "record", "Start Recording", true, None::<&str>)?;
+/// Build the tray menu for the current recording state
+fn build_menu(app: &AppHandle, is_recording: bool) -> tauri::Result<Menu<Wry>> {
+    let record_text = if is_recording {
+        "Stop Recording"
+    } else {
+        "Start Recording"
+    };
+    let target = get_settings().translation.target_language;
+    let target_name = crate::language::profile(&target).map_or(target.clone(), |p| p.name.to_string());
+
+    let record = MenuItem::with_id(app, "record", record_text, true, None::<&str>)?;
+    // Translation starts from idle only; "Stop Recording" stops either mode
+    let translate = MenuItem::with_id(
+        app,
+        "translate",
+        format!("Record and Translate to {}", target_name),
+        !is_recording,
+        None::<&str>,
+    )?;
     let separator = PredefinedMenuItem::separator(app)?;
     let settings = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
     let quit = MenuItem::with_id(app, "quit", "Quit TTP", true, None::<&str>)?;
 
+    Menu::with_items(app, &[&record, &translate, &separator, &settings, &quit])
+}
+
+pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     // Build context menu
-    let menu = Menu::with_items(app, &[&record, &separator, &settings, &quit])?;
+    let menu = build_menu(app, false)?;
 
     // Use simple tray icon (monochrome, works with macOS template)
     let tray_icon = Image::from_bytes(include_bytes!("../icons/icon-idle.png"))
@@ -44,7 +64,10 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
             "record" => {
-                toggle_recording(app);
+                toggle_recording(app, PipelineMode::Dictate);
+            }
+            "translate" => {
+                toggle_recording(app, PipelineMode::Translate);
             }
             _ => {}
         })
@@ -53,8 +76,8 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-/// Toggle recording state from tray menu
-fn toggle_recording(app: &AppHandle) {
+/// Toggle recording state from tray menu (`mode` applies when starting)
+fn toggle_recording(app: &AppHandle, mode: PipelineMode) {
     let state = app.state::<Mutex<AppState>>();
 
     let Ok(mut app_state) = state.try_lock() else {
@@ -65,6 +88,7 @@ fn toggle_recording(app: &AppHandle) {
     match app_state.recording_state {
         RecordingState::Idle => {
             app_state.hands_free_mode = true; // Use hands-free mode for tray
+            app_state.pipeline_mode = mode;
             app_state.set_state(RecordingState::Recording, app);
             set_recording_icon(app, true);
             show_pill(app);
@@ -84,23 +108,10 @@ fn toggle_recording(app: &AppHandle) {
 
 /// Update tray menu text based on recording state
 fn update_tray_menu(app: &AppHandle, is_recording: bool) {
-    let text = if is_recording {
-        "Stop Recording"
-    } else {
-        "Start Recording"
-    };
     // Rebuild the menu with updated text (Tauri 2 TrayIcon has no menu() getter)
     if let Some(tray) = app.tray_by_id("main") {
-        if let Ok(record) = MenuItem::with_id(app, "record", text, true, None::<&str>) {
-            if let Ok(separator) = PredefinedMenuItem::separator(app) {
-                if let Ok(settings) = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>) {
-                    if let Ok(quit) = MenuItem::with_id(app, "quit", "Quit TTP", true, None::<&str>) {
-                        if let Ok(menu) = Menu::with_items(app, &[&record, &separator, &settings, &quit]) {
-                            let _ = tray.set_menu(Some(menu));
-                        }
-                    }
-                }
-            }
+        if let Ok(menu) = build_menu(app, is_recording) {
+            let _ = tray.set_menu(Some(menu));
         }
     }
 }
@@ -191,7 +202,8 @@ pub fn should_show_pill_for_state(recording_state: &RecordingState) -> bool {
     !settings.hide_pill_when_inactive
 }
 
-/// Set up listener for settings changes to update pill visibility and hands-free mode
+/// Set up listener for settings changes to update pill visibility, hands-free
+/// mode and the tray menu (translation target)
 pub fn setup_settings_listener(app: &AppHandle) {
     let app_handle = app.clone();
     app.listen("settings-changed", move |_event| {
@@ -209,6 +221,7 @@ pub fn setup_settings_listener(app: &AppHandle) {
                 // Only update if not currently recording (avoid disrupting active session)
                 if app_state.is_idle() {
                     app_state.hands_free_mode = settings.hands_free_mode;
+                    update_tray_menu(&app_handle, false);
                 }
             }
         }
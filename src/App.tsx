@@ -1,5 +1,5 @@
 // TTP - Talk To Paste
-// Main App component - handles mic recording control and auto-update checking
+// Main App component - observes the native recorder and handles auto-update checking
 
 import { useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
@@ -8,13 +8,12 @@ import { useUpdater } from './hooks/useUpdater';
 
 /**
  * Main App component - this window is hidden by default.
- * TTP runs from the system tray, but this component handles
- * the mic recording control by listening to backend state changes
- * and checks for updates automatically on launch + every 4 hours.
+ * TTP runs from the system tray, but this component observes
+ * the native recorder's events and checks for updates automatically
+ * on launch + every 4 hours.
  */
 function App() {
-  // This hook listens for recording state changes from the backend
-  // and controls the actual microphone recording via tauri-plugin-mic-recorder
+  // Recording runs natively in Rust; this hook only observes its events
   useRecordingControl({
     onRecordingComplete: () => {},
     onError: () => {},
@@ -1,14 +1,11 @@
 // TTP - Talk To Paste
-// Hook to control actual microphone recording via tauri-plugin-mic-recorder
-// This hooks into the recording-state-changed events from Rust and
-// starts/stops the mic recording plugin accordingly.
+// Hook to observe the native recorder
+// Recording, level metering and the hand-off to the transcription pipeline
+// all happen in Rust (recording.rs). This hook only surfaces the recorder's
+// completion and error events to the webview.
 
-import { startRecording, stopRecording } from 'tauri-plugin-mic-recorder-api';
-import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { useEffect, useRef, useCallback } from 'react';
-
-type RecordingState = 'Idle' | 'Recording' | 'Processing';
+import { useEffect, useRef } from 'react';
 
 export interface RecordingResult {
   filePath: string;
@@ -21,101 +18,28 @@ interface UseRecordingControlOptions {
 }
 
 /**
- * Hook that connects Rust recording state events to the mic-recorder plugin.
- * When Rust emits 'Recording' state (from shortcut press), we start mic recording.
- * When Rust emits 'Idle' state (from shortcut release), we stop and get the file path.
+ * Hook that listens to the Rust recorder.
+ * 'recording-complete' fires when a recording was saved and sent to the pipeline.
+ * 'recording-error' fires when the microphone could not be opened or written;
+ * Rust has already reset its state to Idle.
  */
 export function useRecordingControl(options: UseRecordingControlOptions = {}) {
-  const { onRecordingComplete, onError } = options;
-  const isRecordingRef = useRef(false);
-  const recordingStartTime = useRef<number | null>(null);
-
-  const handleStartRecording = useCallback(async () => {
-    if (isRecordingRef.current) {
-      return; // Already recording
-    }
-
-    try {
-      isRecordingRef.current = true;
-      recordingStartTime.current = Date.now();
-      await startRecording();
-    } catch (error) {
-      isRecordingRef.current = false;
-      recordingStartTime.current = null;
-      onError?.(String(error));
-      // Reset Rust state to Idle so the user can record again
-      invoke('reset_to_idle').catch(() => {});
-    }
-  }, [onError]);
-
-  const handleStopRecording = useCallback(async () => {
-    if (!isRecordingRef.current) {
-      return; // Not recording
-    }
-
-    try {
-      isRecordingRef.current = false;
-      const duration = recordingStartTime.current
-        ? (Date.now() - recordingStartTime.current) / 1000
-        : 0;
-      recordingStartTime.current = null;
-
-      // Skip very short recordings (< 0.3s) - likely accidental
-      if (duration < 0.3) {
-        try {
-          await stopRecording(); // Still need to stop the recorder
-        } catch {
-          // Ignore stop errors for short recordings
-        }
-        // Reset state to Idle so user can record again
-        await invoke('reset_to_idle');
-        return;
-      }
-
-      const filePath = await stopRecording();
-
-      onRecordingComplete?.({
-        filePath,
-        duration,
-      });
-
-      // Trigger transcription pipeline
-      invoke('process_audio', { audioPath: filePath })
-        .catch((error) => {
-          onError?.(String(error));
-        });
-    } catch (error) {
-      recordingStartTime.current = null;
-      onError?.(String(error));
-      // Reset Rust state to Idle so the user can record again
-      invoke('reset_to_idle').catch(() => {});
-    }
-  }, [onRecordingComplete, onError]);
+  // Keep the latest callbacks without re-subscribing on every render
+  const optionsRef = useRef(options);
+  optionsRef.current = options;
 
   useEffect(() => {
-    // Listen for recording state changes from Rust
-    const unlisten = listen<RecordingState>('recording-state-changed', async (event) => {
-      const state = event.payload;
+    const unlistenComplete = listen<RecordingResult>('recording-complete', (event) => {
+      optionsRef.current.onRecordingComplete?.(event.payload);
+    });
 
-      if (state === 'Recording' && !isRecordingRef.current) {
-        await handleStartRecording();
-      } else if (state === 'Processing') {
-        if (isRecordingRef.current) {
-          // Recording stopped, now processing - stop mic and trigger pipeline
-          await handleStopRecording();
-        } else {
-          // JS mic never started (plugin failed) — reset Rust state so user isn't stuck
-          invoke('reset_to_idle').catch(() => {});
-        }
-      }
+    const unlistenError = listen<string>('recording-error', (event) => {
+      optionsRef.current.onError?.(event.payload);
     });
 
     return () => {
-      unlisten.then((fn) => fn());
+      unlistenComplete.then((fn) => fn());
+      unlistenError.then((fn) => fn());
     };
-  }, [handleStartRecording, handleStopRecording]);
-
-  return {
-    isRecording: isRecordingRef.current,
-  };
+  }, []);
 }
@@ -5,11 +5,22 @@ import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import { emit } from '@tauri-apps/api/event';
 
+/** What the pipeline did with a recording (Rust PipelineMode) */
+export type PipelineMode = 'dictate' | 'translate' | 'edit';
+
+/** How a dictionary entry's original text is matched (Rust MatchMode) */
+export type MatchMode = 'word' | 'phrase' | 'case_sensitive' | 'regex';
+
 /** Dictionary entry structure matching Rust backend */
 export interface DictionaryEntry {
   original: string;
   correction: string;
   created_at: number;
+  match_mode: MatchMode;
+  enabled: boolean;
+  /** ISO 639-1 code the entry is limited to, null for every language */
+  language: string | null;
+  note: string;
 }
 
 /** History entry structure matching Rust backend */
@@ -17,16 +28,94 @@ export interface HistoryEntry {
   text: string;
   timestamp: number;
   raw_text?: string;
+  /** Transcribed later by the retry queue after the first attempt failed */
+  recovered?: boolean;
+  /** Audio kept for re-transcription (keep_history_audio setting) */
+  audio_path?: string | null;
+  /** Mode the recording was made in (missing on older entries: dictate) */
+  mode?: PipelineMode;
+  /** Text is a snippet's expansion (raw_text holds the spoken trigger) */
+  snippet?: boolean;
+}
+
+/** Recording waiting in the retry queue (matches Rust RetryJob) */
+export interface RetryJob {
+  id: string;
+  audio_path: string;
+  created_at: number;
+  attempts: number;
+  next_attempt_at: number;
+  last_error: string | null;
+  status: 'pending' | 'running' | 'done' | 'failed';
+  text: string | null;
+}
+
+/** Optional audio cleanup stages applied before upload */
+export interface AudioPreprocessing {
+  high_pass: boolean;
+  noise_suppression: boolean;
+  auto_gain: boolean;
+  normalize: boolean;
+}
+
+/** Transcription settings (only the fields the UI edits are typed) */
+export interface TranscriptionSettings {
+  preprocessing?: AudioPreprocessing;
+  /** Spoken language as an ISO 639-1 code (null = auto-detect) */
+  language?: string | null;
+  [key: string]: unknown;
+}
+
+/** Translation mode settings */
+export interface TranslationSettings {
+  /** Shortcut that records in translation mode (null = tray only) */
+  shortcut?: string | null;
+  /** Language to paste in (ISO 639-1) */
+  target_language: string;
+  engine: 'llm' | 'whisper';
+}
+
+/** Command mode settings (edit the selection by voice) */
+export interface EditSettings {
+  /** Shortcut that records an instruction for the selected text (null = off) */
+  shortcut?: string | null;
+}
+
+/** Overrides applied while an application is frontmost (unset = global setting) */
+export interface AppProfile {
+  /** Application name as the OS reports it ("Terminal", "Mail") */
+  app: string;
+  polish_enabled?: boolean | null;
+  polish_style?: string | null;
+  /** ISO 639-1 code, or "auto" */
+  language?: string | null;
+  auto_paste: boolean;
+  after_paste: 'nothing' | 'newline' | 'enter';
+  detect_corrections: boolean;
 }
 
 /** Settings structure matching Rust backend */
 export interface Settings {
   ai_polish_enabled: boolean;
+  /** Polish style id (built-in or user style) */
+  polish_style?: string;
+  /** Apply spoken formatting commands ("new line", "comma", ...) */
+  voice_commands?: boolean;
   shortcut: string;
   fn_key_enabled: boolean;
   telemetry_enabled: boolean;
   hands_free_mode: boolean;
   hide_pill_when_inactive: boolean;
+  /** Preferred microphone name (null = system default) */
+  input_device?: string | null;
+  /** Keep audio with history entries so they can be re-transcribed */
+  keep_history_audio?: boolean;
+  transcription?: TranscriptionSettings;
+  translation?: TranslationSettings;
+  edit?: EditSettings;
+  app_profiles?: AppProfile[];
+  /** Backend-only settings (providers, models, ...) round-tripped untouched */
+  [key: string]: unknown;
 }
 
 interface SettingsStore {
@@ -37,6 +126,8 @@ interface SettingsStore {
   telemetryEnabled: boolean;
   handsFreeMode: boolean;
   hidePillWhenInactive: boolean;
+  /** Last settings object loaded from the backend, including fields not mirrored above */
+  backendSettings: Partial<Settings>;
   dictionary: DictionaryEntry[];
   history: HistoryEntry[];
   loading: boolean;
@@ -60,6 +151,7 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
   telemetryEnabled: false,
   handsFreeMode: false,
   hidePillWhenInactive: false,
+  backendSettings: {},
   dictionary: [],
   history: [],
   loading: false,
@@ -70,6 +162,7 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
     try {
       const settings = await invoke<Settings>('get_settings');
       set({
+        backendSettings: settings,
         aiPolishEnabled: settings.ai_polish_enabled,
         shortcut: settings.shortcut || 'Alt+Space',
         fnKeyEnabled: settings.fn_key_enabled ?? false,
@@ -87,7 +180,9 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
   // Save settings to backend
   saveSettings: async (updates: Partial<Settings>) => {
     try {
+      // Start from the last backend snapshot so settings without UI controls aren't reset
       const currentSettings: Settings = {
+        ...get().backendSettings,
         ai_polish_enabled: get().aiPolishEnabled,
         shortcut: get().shortcut,
         fn_key_enabled: get().fnKeyEnabled,
@@ -105,6 +200,7 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
       // Emit event so other components (like pill, other windows) can react to settings changes
       emit('settings-changed', newSettings);
       set({
+        backendSettings: newSettings,
         aiPolishEnabled: newSettings.ai_polish_enabled,
         shortcut: newSettings.shortcut,
         fnKeyEnabled: newSettings.fn_key_enabled,
@@ -123,6 +219,7 @@ export const useSettingsStore = create<SettingsStore>((set, get) => ({
     try {
       await invoke('reset_settings');
       set({
+        backendSettings: {},
         aiPolishEnabled: true,
         shortcut: 'Alt+Space',
         fnKeyEnabled: false,
@@ -15,6 +15,9 @@ const BAR_COUNT = 12;
 const MIN_HEIGHT = 2;
 const MAX_HEIGHT = 20;
 
+// Streaming transcription: show only the end of the running text above the pill
+const PARTIAL_MAX_CHARS = 60;
+
 export function FloatingBar() {
   const recordingState = useRecordingState();
 
@@ -58,6 +61,24 @@ export function FloatingBar() {
     }
   }, [stage, showTutorial]);
 
+  // Streaming transcription: partial text arrives while recording
+  const [partialText, setPartialText] = useState('');
+
+  useEffect(() => {
+    if (isIdle) {
+      setPartialText('');
+      return;
+    }
+
+    const unlistenPromise = listen<{ text: string }>('partial-transcript', (event) => {
+      setPartialText(event.payload.text);
+    });
+
+    return () => {
+      unlistenPromise.then((fn) => fn());
+    };
+  }, [isIdle]);
+
   // Voice-reactive bars: driven by audio-level events from Rust
   const barRefs = useRef<(HTMLSpanElement | null)[]>([]);
   const levelRef = useRef(0);
@@ -118,6 +139,15 @@ export function FloatingBar() {
         <TutorialPill shortcutText="FN" />
       )}
 
+      {/* Partial transcript — streaming mode only */}
+      {partialText && !isError && (
+        <div className="mb-1 max-w-[360px] truncate rounded-md bg-black/70 px-2 py-0.5 text-[11px] text-white/90">
+          {partialText.length > PARTIAL_MAX_CHARS
+            ? `…${partialText.slice(-PARTIAL_MAX_CHARS)}`
+            : partialText}
+        </div>
+      )}
+
       {/* Pill */}
       <div
         className={`flex items-center justify-center rounded-full shadow-lg transition-all duration-150 ease-out ${
@@ -10,9 +10,40 @@ import { getVersion } from '@tauri-apps/api/app';
 import { relaunch } from '@tauri-apps/plugin-process';
 import { trackEvent } from '../lib/analytics';
 import { useUpdater } from '../hooks/useUpdater';
-import { useSettingsStore, DictionaryEntry, HistoryEntry } from '../stores/settings-store';
+import { useSettingsStore, AppProfile, AudioPreprocessing, DictionaryEntry, HistoryEntry, MatchMode, RetryJob, TranslationSettings } from '../stores/settings-store';
 import WhatsNew from '../components/WhatsNew';
 
+/** Input device as returned by `list_input_devices` */
+interface InputDeviceInfo {
+  name: string;
+  is_default: boolean;
+  configs: { channels: number; min_sample_rate: number; max_sample_rate: number; sample_format: string }[];
+}
+
+/** Language with a profile, as returned by `get_languages` */
+interface LanguageInfo {
+  code: string;
+  name: string;
+}
+
+/** Polish style as returned by `get_polish_styles` */
+interface PolishStyle {
+  id: string;
+  name: string;
+  description: string;
+  /** System prompt with `{fillers}` and `{dictionary}` variables */
+  template: string;
+  builtin: boolean;
+}
+
+/** Snippet as returned by `get_snippets` */
+interface Snippet {
+  trigger: string;
+  /** Text pasted for the trigger, with `{date}`, `{time}`, `{weekday}` and `{clipboard}` placeholders */
+  text: string;
+  created_at: number;
+}
+
 /**
  * Toggle switch component for settings
  */
@@ -96,24 +127,45 @@ function ConfirmDialog({
   );
 }
 
+/** Labels for dictionary match modes */
+const MATCH_MODES: { value: MatchMode; label: string }[] = [
+  { value: 'word', label: 'Whole word' },
+  { value: 'phrase', label: 'Anywhere' },
+  { value: 'case_sensitive', label: 'Exact case' },
+  { value: 'regex', label: 'Regex' },
+];
+
 /**
  * Dictionary table row component
  */
 function DictionaryRow({
   entry,
+  onToggle,
   onDelete,
 }: {
   entry: DictionaryEntry;
+  onToggle: (enabled: boolean) => void;
   onDelete: () => void;
 }) {
+  const mode = MATCH_MODES.find((m) => m.value === entry.match_mode)?.label ?? entry.match_mode;
   return (
-    <tr className="border-b border-gray-200 dark:border-gray-700">
+    <tr className={`border-b border-gray-200 dark:border-gray-700 ${entry.enabled ? '' : 'opacity-50'}`}>
       <td className="py-3 px-4 text-gray-900 dark:text-white font-mono text-sm">
         {entry.original}
+        {entry.note && (
+          <p className="font-sans text-xs text-gray-500 dark:text-gray-400">{entry.note}</p>
+        )}
       </td>
       <td className="py-3 px-4 text-gray-900 dark:text-white font-mono text-sm">
         {entry.correction}
       </td>
+      <td className="py-3 px-4 text-xs text-gray-500 dark:text-gray-400">
+        {mode}
+        {entry.language && ` · ${entry.language.toUpperCase()}`}
+      </td>
+      <td className="py-3 px-4">
+        <Toggle enabled={entry.enabled} onChange={onToggle} />
+      </td>
       <td className="py-3 px-4 text-right">
         <button
           onClick={onDelete}
@@ -143,8 +195,26 @@ function formatTimestamp(timestamp: number): string {
 /**
  * History entry row component
  */
-function HistoryRow({ entry }: { entry: HistoryEntry }) {
+function HistoryRow({
+  entry,
+  styles,
+  languages,
+  onChanged,
+}: {
+  entry: HistoryEntry;
+  styles: PolishStyle[];
+  languages: LanguageInfo[];
+  onChanged: () => void;
+}) {
   const [copied, setCopied] = useState(false);
+  const [busy, setBusy] = useState<'' | 'transcribe' | 'polish'>('');
+  const [error, setError] = useState('');
+  // Re-transcribe overrides (empty = use settings)
+  const [showRerunOptions, setShowRerunOptions] = useState(false);
+  const [rerunModel, setRerunModel] = useState('');
+  const [rerunLanguage, setRerunLanguage] = useState('');
+  const [markedNotSpeech, setMarkedNotSpeech] = useState(false);
+  const [confirmDelete, setConfirmDelete] = useState(false);
 
   const handleCopy = async () => {
     try {
@@ -156,6 +226,65 @@ function HistoryRow({ entry }: { entry: HistoryEntry }) {
     }
   };
 
+  // Regenerate the text from the kept audio or from the raw transcript
+  // (`style` overrides the polish style for this run only; re-transcribing
+  // also uses the model and language chosen under "Options")
+  const handleRerun = async (kind: 'transcribe' | 'polish', style?: string) => {
+    setBusy(kind);
+    setError('');
+    try {
+      if (kind === 'transcribe') {
+        await invoke('retranscribe_history_entry', {
+          timestamp: entry.timestamp,
+          style: style || null,
+          model: rerunModel.trim() || null,
+          language: rerunLanguage || null,
+        });
+      } else {
+        await invoke('repolish_history_entry', { timestamp: entry.timestamp, style: style || null });
+      }
+      trackEvent("history_rerun", {
+        kind,
+        style_override: String(Boolean(style)),
+        model_override: String(kind === 'transcribe' && Boolean(rerunModel.trim())),
+        language_override: String(kind === 'transcribe' && Boolean(rerunLanguage)),
+      });
+      onChanged();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy('');
+    }
+  };
+
+  // Teach the filter to discard this phrase next time (the entry is kept)
+  const handleMarkHallucination = async () => {
+    setError('');
+    try {
+      await invoke('mark_history_hallucination', { timestamp: entry.timestamp });
+      trackEvent("history_marked_hallucination");
+      setMarkedNotSpeech(true);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  // Delete the entry and its kept audio (no undo, hence the confirmation)
+  const handleDelete = async () => {
+    setError('');
+    try {
+      await invoke('delete_history_entry', { timestamp: entry.timestamp });
+      onChanged();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setConfirmDelete(false);
+    }
+  };
+
+  // Command-mode edits and snippets hold stored or edited text, not a dictation to redo
+  const rerunnable = entry.mode !== 'edit' && !entry.snippet;
+
   // Preview: first ~100 characters with ellipsis
   const preview =
     entry.text.length > 100 ? entry.text.slice(0, 100) + '...' : entry.text;
@@ -165,10 +294,111 @@ function HistoryRow({ entry }: { entry: HistoryEntry }) {
       <div className="flex-1 min-w-0">
         <p className="text-xs text-gray-500 dark:text-gray-400 mb-1">
           {formatTimestamp(entry.timestamp)}
+          {entry.recovered && (
+            <span className="ml-2 px-1.5 py-0.5 rounded bg-amber-100 dark:bg-amber-900/40 text-amber-700 dark:text-amber-300">
+              Recovered
+            </span>
+          )}
         </p>
         <p className="text-sm text-gray-900 dark:text-white break-words">
           {preview}
         </p>
+        <div className="flex gap-3 mt-1">
+          {rerunnable && entry.audio_path && (
+            <button
+              onClick={() => handleRerun('transcribe')}
+              disabled={busy !== ''}
+              className="text-xs text-blue-600 hover:text-blue-700 font-medium disabled:opacity-50"
+            >
+              {busy === 'transcribe' ? 'Transcribing...' : 'Re-transcribe'}
+            </button>
+          )}
+          {rerunnable && entry.audio_path && (
+            <button
+              onClick={() => setShowRerunOptions(!showRerunOptions)}
+              className="text-xs text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200 font-medium"
+            >
+              {showRerunOptions ? 'Hide options' : 'Options'}
+            </button>
+          )}
+          {rerunnable && (
+            <button
+              onClick={() => handleRerun('polish')}
+              disabled={busy !== ''}
+              className="text-xs text-blue-600 hover:text-blue-700 font-medium disabled:opacity-50"
+            >
+              {busy === 'polish' ? 'Polishing...' : 'Re-polish'}
+            </button>
+          )}
+          {rerunnable && styles.length > 0 && (
+            <select
+              value=""
+              onChange={(e) => e.target.value && handleRerun('polish', e.target.value)}
+              disabled={busy !== ''}
+              title="Re-polish this entry in another style"
+              className="text-xs bg-transparent text-blue-600 hover:text-blue-700 font-medium disabled:opacity-50 focus:outline-none"
+            >
+              <option value="">Re-polish as...</option>
+              {styles.map((style) => (
+                <option key={style.id} value={style.id}>{style.name}</option>
+              ))}
+            </select>
+          )}
+          {rerunnable && (
+            <button
+              onClick={handleMarkHallucination}
+              disabled={busy !== '' || markedNotSpeech}
+              title="This wasn't something I said: filter this phrase out from now on (the entry stays)"
+              className="text-xs text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200 font-medium disabled:opacity-50"
+            >
+              {markedNotSpeech ? 'Filtered from now on' : 'Not speech'}
+            </button>
+          )}
+          {confirmDelete ? (
+            <span className="text-xs text-gray-500 dark:text-gray-400">
+              Delete{entry.audio_path ? ' with its audio' : ''}?{' '}
+              <button onClick={handleDelete} className="text-red-600 hover:text-red-700 font-medium">
+                Yes
+              </button>{' '}
+              <button onClick={() => setConfirmDelete(false)} className="font-medium hover:text-gray-700 dark:hover:text-gray-200">
+                No
+              </button>
+            </span>
+          ) : (
+            <button
+              onClick={() => setConfirmDelete(true)}
+              disabled={busy !== ''}
+              className="text-xs text-red-600 hover:text-red-700 font-medium disabled:opacity-50"
+            >
+              Delete
+            </button>
+          )}
+        </div>
+        {rerunnable && entry.audio_path && showRerunOptions && (
+          <div className="flex gap-2 mt-2">
+            <select
+              value={rerunLanguage}
+              onChange={(e) => setRerunLanguage(e.target.value)}
+              title="Spoken language for re-transcribing"
+              className="px-2 py-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-xs"
+            >
+              <option value="">Language from settings</option>
+              <option value="auto">Auto-detect</option>
+              {languages.map((lang) => (
+                <option key={lang.code} value={lang.code}>{lang.name}</option>
+              ))}
+            </select>
+            <input
+              type="text"
+              value={rerunModel}
+              onChange={(e) => setRerunModel(e.target.value)}
+              placeholder="Model from settings"
+              title="Transcription model for re-transcribing (e.g. whisper-large-v3)"
+              className="flex-1 px-2 py-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-xs focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+          </div>
+        )}
+        {error && <p className="text-xs text-red-600 mt-1">{error}</p>}
       </div>
       <button
         onClick={handleCopy}
@@ -185,6 +415,185 @@ function HistoryRow({ entry }: { entry: HistoryEntry }) {
   );
 }
 
+/**
+ * App profile editor: overrides applied while one application is frontmost
+ */
+function AppProfileRow({
+  profile,
+  styles,
+  languages,
+  onChange,
+  onDelete,
+}: {
+  profile: AppProfile;
+  styles: PolishStyle[];
+  languages: LanguageInfo[];
+  onChange: (profile: AppProfile) => void;
+  onDelete: () => void;
+}) {
+  // Polish select: '' = global setting, 'off' = raw transcript, otherwise a style id
+  const polishValue = profile.polish_enabled === false ? 'off' : profile.polish_style ?? '';
+  const handlePolishChange = (value: string) => {
+    if (value === '') {
+      onChange({ ...profile, polish_enabled: null, polish_style: null });
+    } else if (value === 'off') {
+      onChange({ ...profile, polish_enabled: false, polish_style: null });
+    } else {
+      onChange({ ...profile, polish_enabled: true, polish_style: value });
+    }
+  };
+
+  const selectClass = "px-2 py-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-xs focus:outline-none focus:ring-2 focus:ring-blue-500";
+
+  return (
+    <div className="p-3 odd:bg-gray-50 dark:odd:bg-gray-800/50 space-y-2">
+      <div className="flex items-center justify-between">
+        <p className="text-sm font-medium text-gray-900 dark:text-white">{profile.app}</p>
+        <button
+          onClick={onDelete}
+          className="text-xs text-red-600 hover:text-red-700 font-medium"
+        >
+          Remove
+        </button>
+      </div>
+      <div className="grid grid-cols-2 gap-2 text-xs text-gray-600 dark:text-gray-300">
+        <label className="flex items-center justify-between gap-2">
+          Polish
+          <select value={polishValue} onChange={(e) => handlePolishChange(e.target.value)} className={selectClass}>
+            <option value="">Default</option>
+            <option value="off">Off (raw text)</option>
+            {styles.map((style) => (
+              <option key={style.id} value={style.id}>{style.name}</option>
+            ))}
+          </select>
+        </label>
+        <label className="flex items-center justify-between gap-2">
+          Language
+          <select
+            value={profile.language ?? ''}
+            onChange={(e) => onChange({ ...profile, language: e.target.value || null })}
+            className={selectClass}
+          >
+            <option value="">Default</option>
+            <option value="auto">Auto-detect</option>
+            {languages.map((lang) => (
+              <option key={lang.code} value={lang.code}>{lang.name}</option>
+            ))}
+          </select>
+        </label>
+        <label className="flex items-center justify-between gap-2">
+          After pasting
+          <select
+            value={profile.auto_paste ? profile.after_paste : 'copy'}
+            onChange={(e) => onChange(e.target.value === 'copy'
+              ? { ...profile, auto_paste: false, after_paste: 'nothing' }
+              : { ...profile, auto_paste: true, after_paste: e.target.value as AppProfile['after_paste'] })}
+            className={selectClass}
+          >
+            <option value="nothing">Nothing</option>
+            <option value="newline">Add a newline</option>
+            <option value="enter">Press Enter</option>
+            <option value="copy">Don't paste, copy only</option>
+          </select>
+        </label>
+        <label className="flex items-center justify-between gap-2">
+          Learn corrections
+          <input
+            type="checkbox"
+            checked={profile.detect_corrections}
+            onChange={(e) => onChange({ ...profile, detect_corrections: e.target.checked })}
+          />
+        </label>
+      </div>
+    </div>
+  );
+}
+
+/**
+ * Retry queue row: status of a failed recording with retry/discard/copy actions
+ */
+function RetryJobRow({ job, onChanged }: { job: RetryJob; onChanged: () => void }) {
+  const [copied, setCopied] = useState(false);
+  const [error, setError] = useState('');
+
+  const run = async (command: string) => {
+    setError('');
+    try {
+      await invoke(command, { id: job.id });
+      if (command === 'copy_retry_job_text') {
+        setCopied(true);
+        setTimeout(() => setCopied(false), 2000);
+      }
+      onChanged();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  let status: string;
+  switch (job.status) {
+    case 'pending':
+      status = `Retrying at ${new Date(job.next_attempt_at).toLocaleTimeString()}`;
+      break;
+    case 'running':
+      status = 'Retrying now...';
+      break;
+    case 'done':
+      status = 'Recovered';
+      break;
+    default:
+      status = 'Gave up';
+  }
+
+  return (
+    <div className="flex items-start gap-3 p-3 odd:bg-gray-50 dark:odd:bg-gray-800/50">
+      <div className="flex-1 min-w-0">
+        <p className="text-xs text-gray-500 dark:text-gray-400 mb-1">
+          {formatTimestamp(job.created_at)} · {status}
+          {job.attempts > 0 && ` · ${job.attempts} attempt${job.attempts === 1 ? '' : 's'}`}
+        </p>
+        {job.text ? (
+          <p className="text-sm text-gray-900 dark:text-white break-words">
+            {job.text.length > 100 ? job.text.slice(0, 100) + '...' : job.text}
+          </p>
+        ) : (
+          job.last_error && (
+            <p className="text-sm text-gray-600 dark:text-gray-300 break-words">{job.last_error}</p>
+          )
+        )}
+        {error && <p className="text-xs text-red-600 mt-1">{error}</p>}
+      </div>
+      <div className="flex-shrink-0 flex items-center gap-2">
+        {job.status === 'done' ? (
+          <button
+            onClick={() => run('copy_retry_job_text')}
+            className="p-2 text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 transition-colors"
+            title="Copy to clipboard"
+          >
+            {copied ? <Check className="w-4 h-4 text-green-500" /> : <Copy className="w-4 h-4" />}
+          </button>
+        ) : (
+          <button
+            onClick={() => run('retry_job_now')}
+            disabled={job.status === 'running'}
+            className="p-2 text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 transition-colors disabled:opacity-50"
+            title="Retry now"
+          >
+            <RefreshCw className={`w-4 h-4 ${job.status === 'running' ? 'animate-spin' : ''}`} />
+          </button>
+        )}
+        <button
+          onClick={() => run('discard_retry_job')}
+          disabled={job.status === 'running'}
+          className="text-sm text-red-600 hover:text-red-700 font-medium disabled:opacity-50"
+        >
+          {job.status === 'done' ? 'Dismiss' : 'Discard'}
+        </button>
+      </div>
+    </div>
+  );
+}
+
 /**
  * Update section component
  */
@@ -336,6 +745,7 @@ export function Settings() {
     shortcut,
     handsFreeMode,
     hidePillWhenInactive,
+    backendSettings,
     dictionary,
     history,
     loading,
@@ -350,6 +760,23 @@ export function Settings() {
   } = useSettingsStore();
 
   const isMac = navigator.platform.startsWith('Mac');
+  const selectedDevice = (backendSettings.input_device as string | null | undefined) ?? '';
+  const keepHistoryAudio = backendSettings.keep_history_audio ?? false;
+  const voiceCommands = backendSettings.voice_commands ?? true;
+  const language = backendSettings.transcription?.language ?? '';
+  const translation: TranslationSettings = {
+    shortcut: null,
+    target_language: 'en',
+    engine: 'llm',
+    ...backendSettings.translation,
+  };
+  const preprocessing: AudioPreprocessing = {
+    high_pass: false,
+    noise_suppression: false,
+    auto_gain: false,
+    normalize: false,
+    ...backendSettings.transcription?.preprocessing,
+  };
 
   const [appVersion, setAppVersion] = useState('...');
   const updateSectionRef = useRef<HTMLDivElement>(null);
@@ -372,6 +799,8 @@ export function Settings() {
   const [showClearHistoryConfirm, setShowClearHistoryConfirm] = useState(false);
   const [shortcutError, setShortcutError] = useState('');
   const [shortcutSuccess, setShortcutSuccess] = useState(false);
+  const [translationError, setTranslationError] = useState('');
+  const [editShortcutError, setEditShortcutError] = useState('');
   const [groqApiKey, setGroqApiKey] = useState('');
   const [hasGroqKey, setHasGroqKey] = useState(false);
   const [groqKeySaving, setGroqKeySaving] = useState(false);
@@ -379,29 +808,75 @@ export function Settings() {
   const [groqKeyError, setGroqKeyError] = useState('');
   const [newOriginal, setNewOriginal] = useState('');
   const [newCorrection, setNewCorrection] = useState('');
+  const [newMatchMode, setNewMatchMode] = useState<MatchMode>('word');
+  const [newEntryLanguage, setNewEntryLanguage] = useState('');
+  const [newNote, setNewNote] = useState('');
   const [addEntryError, setAddEntryError] = useState('');
   const [showRestartBanner, setShowRestartBanner] = useState(false);
+  const [inputDevices, setInputDevices] = useState<InputDeviceInfo[]>([]);
+  const [retryJobs, setRetryJobs] = useState<RetryJob[]>([]);
+  const [languages, setLanguages] = useState<LanguageInfo[]>([]);
+  const [polishStyles, setPolishStyles] = useState<PolishStyle[]>([]);
+  // Style being edited: an existing style, a new one, or none
+  const [editingStyle, setEditingStyle] = useState<PolishStyle | null>(null);
+  const [styleError, setStyleError] = useState('');
+  const [newProfileApp, setNewProfileApp] = useState('');
+  const [snippets, setSnippets] = useState<Snippet[]>([]);
+  const [newTrigger, setNewTrigger] = useState('');
+  const [newSnippetText, setNewSnippetText] = useState('');
+  const [snippetError, setSnippetError] = useState('');
+
+  // Recordings waiting for an automatic retry after a failed transcription
+  const loadRetryJobs = useCallback(() => {
+    invoke<RetryJob[]>('get_retry_jobs').then(setRetryJobs).catch(console.error);
+  }, []);
+
+  // Refresh connected microphones (headsets come and go during the day)
+  const loadInputDevices = useCallback(() => {
+    invoke<InputDeviceInfo[]>('list_input_devices').then(setInputDevices).catch(console.error);
+  }, []);
 
   // Check API key status
   const checkApiKeys = useCallback(() => {
     invoke<boolean>('has_groq_api_key').then(setHasGroqKey).catch(console.error);
   }, []);
 
+  const loadSnippets = useCallback(() => {
+    invoke<Snippet[]>('get_snippets').then(setSnippets).catch(console.error);
+  }, []);
+
   // Load settings, dictionary, and history on mount
   useEffect(() => {
+    loadSnippets();
+    invoke<LanguageInfo[]>('get_languages').then(setLanguages).catch(console.error);
+    invoke<PolishStyle[]>('get_polish_styles').then(setPolishStyles).catch(console.error);
     loadSettings();
     loadDictionary();
     loadHistory();
     checkApiKeys();
-  }, [loadSettings, loadDictionary, loadHistory, checkApiKeys]);
+  }, [loadSettings, loadDictionary, loadHistory, checkApiKeys, loadSnippets]);
 
-  // Re-check API keys when window gets focus (e.g. after setup popup)
+  // Re-check API keys and microphones when window gets focus (e.g. after setup popup)
   useEffect(() => {
+    loadInputDevices();
     const unlisten = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
-      if (focused) checkApiKeys();
+      if (focused) {
+        checkApiKeys();
+        loadInputDevices();
+      }
+    });
+    return () => { unlisten.then(fn => fn()); };
+  }, [checkApiKeys, loadInputDevices]);
+
+  // Refresh the retry queue (and history, which gains recovered entries) when it changes
+  useEffect(() => {
+    loadRetryJobs();
+    const unlisten = listen('retry-queue-changed', () => {
+      loadRetryJobs();
+      loadHistory();
     });
     return () => { unlisten.then(fn => fn()); };
-  }, [checkApiKeys]);
+  }, [loadRetryJobs, loadHistory]);
 
   // Refresh dictionary when backend auto-detects corrections
   useEffect(() => {
@@ -463,6 +938,63 @@ export function Settings() {
     }
   };
 
+  // Handle microphone selection ('' = system default)
+  const handleInputDeviceChange = async (name: string) => {
+    try {
+      await saveSettings({ input_device: name || null });
+      trackEvent("setting_changed", { setting_name: "input_device", new_value: name ? "custom" : "default" });
+    } catch (error) {
+      console.error('Failed to save microphone setting:', error);
+    }
+  };
+
+  // Handle keep audio toggle
+  const handleKeepHistoryAudioToggle = async (enabled: boolean) => {
+    try {
+      await saveSettings({ keep_history_audio: enabled });
+      trackEvent("setting_changed", { setting_name: "keep_history_audio", new_value: String(enabled) });
+    } catch (error) {
+      console.error('Failed to save keep audio setting:', error);
+    }
+  };
+
+  // Handle voice commands toggle
+  const handleVoiceCommandsToggle = async (enabled: boolean) => {
+    try {
+      await saveSettings({ voice_commands: enabled });
+      trackEvent("setting_changed", { setting_name: "voice_commands", new_value: String(enabled) });
+    } catch (error) {
+      console.error('Failed to save voice commands setting:', error);
+    }
+  };
+
+  // Handle spoken language change ('' = auto-detect)
+  const handleLanguageChange = async (code: string) => {
+    try {
+      await saveSettings({
+        transcription: { ...backendSettings.transcription, language: code || null },
+      });
+      trackEvent("setting_changed", { setting_name: "language", new_value: code || 'auto' });
+    } catch (error) {
+      console.error('Failed to save language setting:', error);
+    }
+  };
+
+  // Handle audio cleanup stage toggles
+  const handlePreprocessingToggle = async (stage: keyof AudioPreprocessing, enabled: boolean) => {
+    try {
+      await saveSettings({
+        transcription: {
+          ...backendSettings.transcription,
+          preprocessing: { ...preprocessing, [stage]: enabled },
+        },
+      });
+      trackEvent("setting_changed", { setting_name: `preprocessing_${stage}`, new_value: String(enabled) });
+    } catch (error) {
+      console.error('Failed to save audio cleanup setting:', error);
+    }
+  };
+
   // Handle Groq API key save (validates before saving)
   const handleGroqKeySave = async () => {
     if (!groqApiKey.trim()) return;
@@ -518,6 +1050,103 @@ export function Settings() {
     }
   };
 
+  // Handle polish style selection
+  const polishStyle = backendSettings.polish_style ?? 'light';
+  const selectedStyle = polishStyles.find((s) => s.id === polishStyle);
+
+  const handlePolishStyleChange = async (id: string) => {
+    try {
+      await saveSettings({ polish_style: id });
+      trackEvent("setting_changed", { setting_name: "polish_style", new_value: polishStyles.find((s) => s.id === id)?.builtin ? id : 'custom' });
+    } catch (error) {
+      console.error('Failed to save polish style:', error);
+    }
+  };
+
+  // Save the style being edited (a built-in id saves a customized copy)
+  const handleSaveStyle = async () => {
+    if (!editingStyle) return;
+    setStyleError('');
+    const id = editingStyle.id || editingStyle.name.trim().toLowerCase().replace(/[^a-z0-9]+/g, '-').replace(/^-|-$/g, '');
+    try {
+      const styles = await invoke<PolishStyle[]>('save_polish_style', { style: { ...editingStyle, id } });
+      setPolishStyles(styles);
+      setEditingStyle(null);
+      await handlePolishStyleChange(id);
+    } catch (error) {
+      setStyleError(String(error));
+    }
+  };
+
+  // Delete a user style, or restore a customized built-in
+  const handleDeleteStyle = async (id: string) => {
+    setStyleError('');
+    try {
+      const styles = await invoke<PolishStyle[]>('delete_polish_style', { id });
+      setPolishStyles(styles);
+      setEditingStyle(null);
+      if (!styles.some((s) => s.id === id)) {
+        await handlePolishStyleChange('light');
+      }
+    } catch (error) {
+      setStyleError(String(error));
+    }
+  };
+
+  // App profiles (matched on the frontmost app's name)
+  const appProfiles = backendSettings.app_profiles ?? [];
+
+  const saveAppProfiles = async (profiles: AppProfile[]) => {
+    try {
+      await saveSettings({ app_profiles: profiles });
+    } catch (error) {
+      console.error('Failed to save app profiles:', error);
+    }
+  };
+
+  const handleAddProfile = async () => {
+    const app = newProfileApp.trim();
+    if (!app || appProfiles.some((p) => p.app.toLowerCase() === app.toLowerCase())) return;
+    await saveAppProfiles([
+      ...appProfiles,
+      { app, auto_paste: true, after_paste: 'nothing', detect_corrections: true },
+    ]);
+    setNewProfileApp('');
+    trackEvent("app_profile_added");
+  };
+
+  // Handle translation mode changes (the shortcut is registered before saving)
+  const handleTranslationChange = async (change: Partial<TranslationSettings>) => {
+    setTranslationError('');
+    try {
+      if ('shortcut' in change) {
+        await invoke('update_translation_shortcut_cmd', { shortcut: change.shortcut || null });
+      }
+      await saveSettings({ translation: { ...translation, ...change } });
+      for (const [name, value] of Object.entries(change)) {
+        trackEvent("setting_changed", { setting_name: `translation_${name}`, new_value: String(value ?? 'off') });
+      }
+    } catch (error) {
+      console.error('Failed to update translation settings:', error);
+      setTranslationError(String(error));
+    }
+  };
+
+  // Handle command mode shortcut change (registered before saving)
+  const editShortcut = backendSettings.edit?.shortcut ?? '';
+
+  const handleEditShortcutChange = async (value: string) => {
+    setEditShortcutError('');
+    try {
+      await invoke('update_edit_shortcut_cmd', { shortcut: value || null });
+      await saveSettings({ edit: { ...backendSettings.edit, shortcut: value || null } });
+      trackEvent("setting_changed", { setting_name: "edit_shortcut", new_value: value || 'off' });
+    } catch (error) {
+      console.error('Failed to update command mode shortcut:', error);
+      setEditShortcutError(String(error));
+    }
+  };
+
   // Handle clear dictionary
   const handleClearDictionary = async () => {
     try {
@@ -553,15 +1182,54 @@ export function Settings() {
       return;
     }
     try {
-      await invoke('add_dictionary_entry', { original: orig, correction: corr });
+      await invoke('add_dictionary_entry', {
+        original: orig,
+        correction: corr,
+        matchMode: newMatchMode,
+        language: newEntryLanguage || null,
+        note: newNote.trim(),
+      });
       setNewOriginal('');
       setNewCorrection('');
+      setNewNote('');
       await loadDictionary();
     } catch (error) {
       setAddEntryError(String(error));
     }
   };
 
+  // Handle add snippet (an existing trigger is replaced)
+  const handleAddSnippet = async () => {
+    setSnippetError('');
+    try {
+      await invoke('add_snippet', { trigger: newTrigger, text: newSnippetText });
+      setNewTrigger('');
+      setNewSnippetText('');
+      loadSnippets();
+    } catch (error) {
+      setSnippetError(String(error));
+    }
+  };
+
+  const handleDeleteSnippet = async (trigger: string) => {
+    try {
+      await invoke('delete_snippet', { trigger });
+      loadSnippets();
+    } catch (error) {
+      console.error('Failed to delete snippet:', error);
+    }
+  };
+
+  // Handle enabling or disabling a dictionary entry
+  const handleToggleEntry = async (original: string, enabled: boolean) => {
+    try {
+      await invoke('set_dictionary_entry_enabled', { original, enabled });
+      await loadDictionary();
+    } catch (error) {
+      console.error('Failed to update entry:', error);
+    }
+  };
+
   // Handle delete single entry
   const handleDeleteEntry = async (original: string) => {
     try {
@@ -707,6 +1375,85 @@ export function Settings() {
           )}
         </section>
 
+        {/* Translation Mode Section */}
+        <section className="bg-white dark:bg-gray-800 rounded-lg shadow-sm p-6 mb-6">
+          <h2 className="text-lg font-semibold text-gray-900 dark:text-white mb-4">
+            Translation Mode
+          </h2>
+          <p className="text-sm text-gray-500 dark:text-gray-400 mb-4">
+            Speak in any language, paste the translation. Start it with its own shortcut or from the tray menu.
+          </p>
+
+          <div className="space-y-4">
+            <div className="flex items-center justify-between">
+              <p className="text-gray-900 dark:text-white font-medium">Shortcut</p>
+              <select
+                value={translation.shortcut ?? ''}
+                onChange={(e) => handleTranslationChange({ shortcut: e.target.value || null })}
+                disabled={loading}
+                className="max-w-[220px] px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+              >
+                <option value="">Off (tray only)</option>
+                {(isMac
+                  ? [
+                      { value: 'Alt+Shift+Space', label: '⌥⇧ Space' },
+                      { value: 'CmdOrCtrl+Shift+T', label: '⌘⇧ T' },
+                    ]
+                  : [
+                      { value: 'Ctrl+Alt+Space', label: 'Ctrl + Alt + Space' },
+                      { value: 'Ctrl+Shift+T', label: 'Ctrl + Shift + T' },
+                    ]
+                )
+                  .filter((opt) => opt.value !== shortcut)
+                  .map((opt) => (
+                    <option key={opt.value} value={opt.value}>{opt.label}</option>
+                  ))}
+              </select>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <p className="text-gray-900 dark:text-white font-medium">Translate to</p>
+              <select
+                value={translation.target_language}
+                onChange={(e) => handleTranslationChange({ target_language: e.target.value })}
+                disabled={loading}
+                className="max-w-[220px] px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+              >
+                {languages.map((lang) => (
+                  <option key={lang.code} value={lang.code}>{lang.name}</option>
+                ))}
+                {!languages.some((l) => l.code === translation.target_language) && (
+                  <option value={translation.target_language}>{translation.target_language}</option>
+                )}
+              </select>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div className="flex-1 pr-4">
+                <p className="text-gray-900 dark:text-white font-medium">Translated by</p>
+                <p className="text-sm text-gray-500 dark:text-gray-400 mt-1">
+                  Whisper only translates into English; other languages always use the AI model
+                </p>
+              </div>
+              <select
+                value={translation.engine}
+                onChange={(e) => handleTranslationChange({ engine: e.target.value as TranslationSettings['engine'] })}
+                disabled={loading}
+                className="max-w-[220px] px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+              >
+                <option value="llm">AI model (polish LLM)</option>
+                <option value="whisper">Whisper</option>
+              </select>
+            </div>
+          </div>
+
+          {translationError && (
+            <p className="text-sm text-red-600 dark:text-red-400 mt-3">
+              {translationError}
+            </p>
+          )}
+        </section>
+
         {/* Recording Mode Section */}
         <section className="bg-white dark:bg-gray-800 rounded-lg shadow-sm p-6 mb-6">
           <h2 className="text-lg font-semibold text-gray-900 dark:text-white mb-4">
@@ -731,7 +1478,7 @@ export function Settings() {
           </div>
 
           {/* Hide pill when inactive toggle */}
-          <div className="flex items-center justify-between">
+          <div className="flex items-center justify-between mb-4">
             <div className="flex-1 pr-4">
               <p className="text-gray-900 dark:text-white font-medium">
                 Hide pill when inactive
@@ -746,6 +1493,62 @@ export function Settings() {
               disabled={loading}
             />
           </div>
+
+          {/* Microphone selection */}
+          <div className="flex items-center justify-between">
+            <div className="flex-1 pr-4">
+              <p className="text-gray-900 dark:text-white font-medium">
+                Microphone
+              </p>
+              <p className="text-sm text-gray-500 dark:text-gray-400 mt-1">
+                Falls back to the system default when the selected device isn't connected
+              </p>
+            </div>
+            <select
+              value={selectedDevice}
+              onChange={(e) => handleInputDeviceChange(e.target.value)}
+              disabled={loading}
+              className="max-w-[220px] px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="">System default</option>
+              {inputDevices.map((device) => (
+                <option key={device.name} value={device.name}>
+                  {device.name}{device.is_default ? ' (default)' : ''}
+                </option>
+              ))}
+              {selectedDevice && !inputDevices.some((d) => d.name === selectedDevice) && (
+                <option value={selectedDevice}>{selectedDevice} (not connected)</option>
+              )}
+            </select>
+          </div>
+
+          {/* Audio cleanup before upload */}
+          <div className="mt-6 pt-4 border-t border-gray-200 dark:border-gray-700">
+            <p className="text-gray-900 dark:text-white font-medium">
+              Audio cleanup
+            </p>
+            <p className="text-sm text-gray-500 dark:text-gray-400 mt-1 mb-3">
+              Applied to the recording before it's sent for transcription
+            </p>
+            {([
+              ['high_pass', 'Remove rumble', 'Filters out low hum, desk bumps and DC offset'],
+              ['noise_suppression', 'Suppress background noise', 'Reduces fans, traffic and office chatter'],
+              ['auto_gain', 'Automatic gain', 'Evens out quiet or distant speech'],
+              ['normalize', 'Normalize volume', 'Raises the overall level to just below clipping'],
+            ] as const).map(([stage, label, description]) => (
+              <div key={stage} className="flex items-center justify-between mb-3 last:mb-0">
+                <div className="flex-1 pr-4">
+                  <p className="text-sm text-gray-900 dark:text-white">{label}</p>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">{description}</p>
+                </div>
+                <Toggle
+                  enabled={preprocessing[stage]}
+                  onChange={(enabled) => handlePreprocessingToggle(stage, enabled)}
+                  disabled={loading}
+                />
+              </div>
+            ))}
+          </div>
         </section>
 
         {/* Transcription Section */}
@@ -812,6 +1615,32 @@ export function Settings() {
               )}
           </div>
 
+          {/* Spoken language */}
+          <div className="flex items-center justify-between mb-6">
+            <div className="flex-1 pr-4">
+              <p className="text-gray-900 dark:text-white font-medium">
+                Language
+              </p>
+              <p className="text-sm text-gray-500 dark:text-gray-400 mt-1">
+                Pick your language if it's often mis-detected; also sets the filler words polish removes
+              </p>
+            </div>
+            <select
+              value={language}
+              onChange={(e) => handleLanguageChange(e.target.value)}
+              disabled={loading}
+              className="max-w-[220px] px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="">Auto-detect</option>
+              {languages.map((lang) => (
+                <option key={lang.code} value={lang.code}>{lang.name}</option>
+              ))}
+              {language && !languages.some((l) => l.code === language) && (
+                <option value={language}>{language}</option>
+              )}
+            </select>
+          </div>
+
           {/* AI Polish Toggle */}
           <div className="flex items-center justify-between">
             <div className="flex-1 pr-4">
@@ -829,6 +1658,218 @@ export function Settings() {
             />
           </div>
 
+          {/* Polish Style */}
+          {aiPolishEnabled && (
+            <div className="mt-4">
+              <div className="flex items-center justify-between">
+                <div className="flex-1 pr-4">
+                  <p className="text-gray-900 dark:text-white font-medium">Polish style</p>
+                  <p className="text-sm text-gray-500 dark:text-gray-400 mt-1">
+                    {selectedStyle?.description || 'How the AI rewrites your dictation'}
+                  </p>
+                </div>
+                <select
+                  value={polishStyle}
+                  onChange={(e) => handlePolishStyleChange(e.target.value)}
+                  disabled={loading}
+                  className="max-w-[220px] px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+                >
+                  {polishStyles.map((style) => (
+                    <option key={style.id} value={style.id}>
+                      {style.name}{style.builtin ? '' : ' (custom)'}
+                    </option>
+                  ))}
+                </select>
+              </div>
+
+              <div className="flex gap-3 mt-2">
+                {selectedStyle && (
+                  <button
+                    onClick={() => setEditingStyle({ ...selectedStyle })}
+                    className="text-xs text-blue-600 hover:text-blue-700 font-medium"
+                  >
+                    Edit prompt
+                  </button>
+                )}
+                <button
+                  onClick={() => setEditingStyle({
+                    id: '',
+                    name: '',
+                    description: '',
+                    template: selectedStyle?.template ?? '',
+                    builtin: false,
+                  })}
+                  className="text-xs text-blue-600 hover:text-blue-700 font-medium"
+                >
+                  New style
+                </button>
+                {selectedStyle && !selectedStyle.builtin && (
+                  <button
+                    onClick={() => handleDeleteStyle(selectedStyle.id)}
+                    title="Deleting an edited built-in style restores the original"
+                    className="text-xs text-red-600 hover:text-red-700 font-medium"
+                  >
+                    Delete
+                  </button>
+                )}
+              </div>
+
+              {editingStyle && (
+                <div className="mt-3 space-y-2">
+                  {!editingStyle.id && (
+                    <input
+                      type="text"
+                      value={editingStyle.name}
+                      onChange={(e) => setEditingStyle({ ...editingStyle, name: e.target.value })}
+                      placeholder="Style name"
+                      className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+                    />
+                  )}
+                  <input
+                    type="text"
+                    value={editingStyle.description}
+                    onChange={(e) => setEditingStyle({ ...editingStyle, description: e.target.value })}
+                    placeholder="Short description"
+                    className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+                  />
+                  <textarea
+                    value={editingStyle.template}
+                    onChange={(e) => setEditingStyle({ ...editingStyle, template: e.target.value })}
+                    rows={10}
+                    className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-xs font-mono focus:outline-none focus:ring-2 focus:ring-blue-500"
+                  />
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    {'{fillers}'} becomes the filler words of your language, {'{dictionary}'} your personal dictionary.
+                  </p>
+                  <div className="flex gap-2">
+                    <button
+                      onClick={handleSaveStyle}
+                      className="px-3 py-1.5 text-xs font-medium text-white bg-blue-600 hover:bg-blue-700 rounded-md transition-colors"
+                    >
+                      Save
+                    </button>
+                    <button
+                      onClick={() => { setEditingStyle(null); setStyleError(''); }}
+                      className="px-3 py-1.5 text-xs font-medium text-gray-700 dark:text-gray-300 border border-gray-300 dark:border-gray-600 hover:bg-gray-100 dark:hover:bg-gray-700 rounded-md transition-colors"
+                    >
+                      Cancel
+                    </button>
+                  </div>
+                </div>
+              )}
+              {styleError && (
+                <p className="text-sm text-red-600 dark:text-red-400 mt-2">{styleError}</p>
+              )}
+            </div>
+          )}
+
+          {/* Voice Commands Toggle */}
+          <div className="flex items-center justify-between mt-4">
+            <div className="flex-1 pr-4">
+              <p className="text-gray-900 dark:text-white font-medium">
+                Voice Commands
+              </p>
+              <p className="text-sm text-gray-500 dark:text-gray-400 mt-1">
+                Say "new line", "full stop", "bullet point", "scratch that" or "all caps ... end caps"
+                (in French: "à la ligne", "insère une virgule", "nouvelle puce"...). Works with AI Polish off.
+              </p>
+            </div>
+            <Toggle
+              enabled={voiceCommands}
+              onChange={handleVoiceCommandsToggle}
+              disabled={loading}
+            />
+          </div>
+
+        </section>
+
+        {/* Command Mode Section */}
+        <section className="bg-white dark:bg-gray-800 rounded-lg shadow-sm p-6 mb-6">
+          <h2 className="text-lg font-semibold text-gray-900 dark:text-white mb-4">
+            Command Mode
+          </h2>
+          <p className="text-sm text-gray-500 dark:text-gray-400 mb-4">
+            Select some text, hold this shortcut and say what to do with it: "make this more polite",
+            "translate to English", "turn into bullet points". The AI model rewrites the selection in place.
+          </p>
+
+          <div className="flex items-center justify-between">
+            <p className="text-gray-900 dark:text-white font-medium">Shortcut</p>
+            <select
+              value={editShortcut}
+              onChange={(e) => handleEditShortcutChange(e.target.value)}
+              disabled={loading}
+              className="max-w-[220px] px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="">Off</option>
+              {(isMac
+                ? [
+                    { value: 'Alt+Shift+E', label: '⌥⇧ E' },
+                    { value: 'CmdOrCtrl+Shift+E', label: '⌘⇧ E' },
+                  ]
+                : [
+                    { value: 'Ctrl+Alt+E', label: 'Ctrl + Alt + E' },
+                    { value: 'Ctrl+Shift+E', label: 'Ctrl + Shift + E' },
+                  ]
+              )
+                .filter((opt) => opt.value !== shortcut && opt.value !== translation.shortcut)
+                .map((opt) => (
+                  <option key={opt.value} value={opt.value}>{opt.label}</option>
+                ))}
+            </select>
+          </div>
+
+          {editShortcutError && (
+            <p className="text-sm text-red-600 dark:text-red-400 mt-3">
+              {editShortcutError}
+            </p>
+          )}
+        </section>
+
+        {/* App Profiles Section */}
+        <section className="bg-white dark:bg-gray-800 rounded-lg shadow-sm p-6 mb-6">
+          <h2 className="text-lg font-semibold text-gray-900 dark:text-white mb-4">
+            App Profiles
+          </h2>
+          <p className="text-sm text-gray-500 dark:text-gray-400 mb-4">
+            Different settings for the app you dictate into, e.g. raw text in Terminal or a formal
+            email style in Mail. Use the app name as shown in the menu bar.
+            {!isMac && ' Profiles are only applied on macOS for now.'}
+          </p>
+
+          {appProfiles.length > 0 && (
+            <div className="border border-gray-200 dark:border-gray-700 rounded-md mb-4">
+              {appProfiles.map((profile, index) => (
+                <AppProfileRow
+                  key={profile.app}
+                  profile={profile}
+                  styles={polishStyles}
+                  languages={languages}
+                  onChange={(updated) => saveAppProfiles(appProfiles.map((p, i) => (i === index ? updated : p)))}
+                  onDelete={() => saveAppProfiles(appProfiles.filter((_, i) => i !== index))}
+                />
+              ))}
+            </div>
+          )}
+
+          <div className="flex gap-2">
+            <input
+              type="text"
+              value={newProfileApp}
+              onChange={(e) => setNewProfileApp(e.target.value)}
+              onKeyDown={(e) => e.key === 'Enter' && handleAddProfile()}
+              placeholder="App name, e.g. Terminal"
+              disabled={loading}
+              className="flex-1 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+            <button
+              onClick={handleAddProfile}
+              disabled={loading || !newProfileApp.trim()}
+              className="px-4 py-2 text-sm font-medium text-white bg-blue-600 hover:bg-blue-700 disabled:opacity-50 rounded-md transition-colors"
+            >
+              Add
+            </button>
+          </div>
         </section>
 
         {/* Privacy & Telemetry Section */}
@@ -929,6 +1970,39 @@ export function Settings() {
               Add
             </button>
           </div>
+          <div className="mb-4 flex gap-2">
+            <select
+              value={newMatchMode}
+              onChange={(e) => setNewMatchMode(e.target.value as MatchMode)}
+              className="px-2 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm"
+            >
+              {MATCH_MODES.map((m) => (
+                <option key={m.value} value={m.value}>{m.label}</option>
+              ))}
+            </select>
+            <select
+              value={newEntryLanguage}
+              onChange={(e) => setNewEntryLanguage(e.target.value)}
+              className="px-2 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm"
+            >
+              <option value="">All languages</option>
+              {languages.map((l) => (
+                <option key={l.code} value={l.code}>{l.name}</option>
+              ))}
+            </select>
+            <input
+              type="text"
+              value={newNote}
+              onChange={(e) => setNewNote(e.target.value)}
+              placeholder="Note (optional)"
+              className="flex-1 px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+          </div>
+          {newMatchMode === 'regex' && (
+            <p className="text-xs text-gray-500 dark:text-gray-400 -mt-2 mb-3">
+              Misheard is a regular expression (add (?i) to ignore case); use $1 in the correction for captured groups.
+            </p>
+          )}
           {addEntryError && (
             <p className="text-red-500 text-sm mb-3">{addEntryError}</p>
           )}
@@ -948,6 +2022,10 @@ export function Settings() {
                     <th className="text-left py-2 px-4 text-xs font-medium text-gray-500 dark:text-gray-400 uppercase tracking-wider">
                       Correction
                     </th>
+                    <th className="text-left py-2 px-4 text-xs font-medium text-gray-500 dark:text-gray-400 uppercase tracking-wider">
+                      Match
+                    </th>
+                    <th className="w-16"></th>
                     <th className="w-20"></th>
                   </tr>
                 </thead>
@@ -956,6 +2034,7 @@ export function Settings() {
                     <DictionaryRow
                       key={entry.original}
                       entry={entry}
+                      onToggle={(enabled) => handleToggleEntry(entry.original, enabled)}
                       onDelete={() => handleDeleteEntry(entry.original)}
                     />
                   ))}
@@ -965,6 +2044,100 @@ export function Settings() {
           )}
         </section>
 
+        {/* Snippets Section */}
+        <section className="bg-white dark:bg-gray-800 rounded-lg shadow-sm p-6 mb-6">
+          <h2 className="text-lg font-semibold text-gray-900 dark:text-white mb-1">
+            Snippets
+          </h2>
+          <p className="text-sm text-gray-500 dark:text-gray-400 mb-4">
+            Say a trigger on its own to paste its text as is, without polish.
+            Placeholders: {'{date}'}, {'{time}'}, {'{weekday}'}, {'{clipboard}'}.
+          </p>
+
+          {/* Add snippet form */}
+          <div className="mb-4 space-y-2">
+            <input
+              type="text"
+              value={newTrigger}
+              onChange={(e) => setNewTrigger(e.target.value)}
+              placeholder="insert my address"
+              className="w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+            <textarea
+              value={newSnippetText}
+              onChange={(e) => setNewSnippetText(e.target.value)}
+              placeholder={'Standup {date}\nYesterday:\nToday:\nBlockers:'}
+              rows={4}
+              className="w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500"
+            />
+            <div className="flex justify-end">
+              <button
+                onClick={handleAddSnippet}
+                disabled={!newTrigger.trim() || !newSnippetText.trim()}
+                className="px-3 py-1.5 text-sm font-medium text-white bg-blue-600 hover:bg-blue-700 disabled:bg-gray-400 disabled:cursor-not-allowed rounded-md transition-colors"
+              >
+                Save Snippet
+              </button>
+            </div>
+          </div>
+          {snippetError && (
+            <p className="text-red-500 text-sm mb-3">{snippetError}</p>
+          )}
+
+          {snippets.length === 0 ? (
+            <p className="text-gray-500 dark:text-gray-400 text-center py-4">
+              No snippets yet
+            </p>
+          ) : (
+            <div className="rounded-md border border-gray-200 dark:border-gray-700 divide-y divide-gray-200 dark:divide-gray-700">
+              {snippets.map((snippet) => (
+                <div key={snippet.trigger} className="p-3 flex gap-3 items-start">
+                  <div className="flex-1 min-w-0">
+                    <p className="text-sm font-medium text-gray-900 dark:text-white">
+                      &ldquo;{snippet.trigger}&rdquo;
+                    </p>
+                    <p className="text-sm text-gray-500 dark:text-gray-400 font-mono whitespace-pre-wrap line-clamp-3">
+                      {snippet.text}
+                    </p>
+                  </div>
+                  <button
+                    onClick={() => {
+                      setNewTrigger(snippet.trigger);
+                      setNewSnippetText(snippet.text);
+                    }}
+                    className="text-sm text-blue-600 hover:text-blue-700 font-medium"
+                  >
+                    Edit
+                  </button>
+                  <button
+                    onClick={() => handleDeleteSnippet(snippet.trigger)}
+                    className="text-red-600 hover:text-red-700 text-sm font-medium"
+                  >
+                    Delete
+                  </button>
+                </div>
+              ))}
+            </div>
+          )}
+        </section>
+
+        {/* Failed Transcriptions Section (only while something is queued) */}
+        {retryJobs.length > 0 && (
+          <section className="bg-white dark:bg-gray-800 rounded-lg shadow-sm p-6 mb-6">
+            <h2 className="text-lg font-semibold text-gray-900 dark:text-white mb-1">
+              Failed Transcriptions
+            </h2>
+            <p className="text-sm text-gray-500 dark:text-gray-400 mb-4">
+              Recordings are kept and retried automatically until they go through
+            </p>
+            <div className="max-h-80 overflow-y-auto rounded-md border border-gray-200 dark:border-gray-700">
+              {retryJobs.map((job) => (
+                <RetryJobRow key={job.id} job={job} onChanged={loadRetryJobs} />
+              ))}
+            </div>
+          </section>
+        )}
+
         {/* History Section */}
         <section className="bg-white dark:bg-gray-800 rounded-lg shadow-sm p-6 mb-6">
           <div className="flex items-center justify-between mb-4">
@@ -981,6 +2154,23 @@ export function Settings() {
             )}
           </div>
 
+          {/* Keep audio for re-transcription */}
+          <div className="flex items-center justify-between mb-4">
+            <div className="flex-1 pr-4">
+              <p className="text-gray-900 dark:text-white font-medium">
+                Keep audio
+              </p>
+              <p className="text-sm text-gray-500 dark:text-gray-400 mt-1">
+                Store each recording with its transcription so it can be re-transcribed. Clearing history deletes it.
+              </p>
+            </div>
+            <Toggle
+              enabled={keepHistoryAudio}
+              onChange={handleKeepHistoryAudioToggle}
+              disabled={loading}
+            />
+          </div>
+
           {history.length === 0 ? (
             <p className="text-gray-500 dark:text-gray-400 text-center py-8">
               No transcriptions yet
@@ -988,7 +2178,7 @@ export function Settings() {
           ) : (
             <div className="max-h-80 overflow-y-auto rounded-md border border-gray-200 dark:border-gray-700">
               {history.map((entry, index) => (
-                <HistoryRow key={`${entry.timestamp}-${index}`} entry={entry} />
+                <HistoryRow key={`${entry.timestamp}-${index}`} entry={entry} styles={polishStyles} languages={languages} onChanged={loadHistory} />
               ))}
             </div>
           )}